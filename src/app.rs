@@ -1,16 +1,32 @@
+use crate::autostart;
+use crate::bundle::Bundle;
 use crate::config;
+use crate::error::DrillError;
+use crate::error_stats::ErrorStats;
+use crate::events::{self, EventStore};
+use crate::health::{self, HealthReport};
+use crate::hooks;
+use crate::hotkey;
 use crate::logs::Logger;
 use crate::notifications;
+use crate::putty;
+use crate::ssh_uri;
 use crate::systemtray::{self, TrayMenuIds};
-use crate::tunnels::{TunnelManager, StatusUpdate};
+use crate::templates;
+use crate::updates::{self, ReleaseInfo};
+use crate::tunnels::{append_to_ssh_config, auth_warnings, is_unknown_host_key_error, monitor_interval, private_key_warnings, resolve_jump_hosts, tunnel_to_ssh_config_block, SaveRetry, TestCancel, Tunnel, TunnelManager, TunnelStatus, StatusUpdate};
 use tokio::sync::broadcast;
 use crate::windows::{self, WindowType};
+use crate::windows::confirm::ConfirmAction;
 use iced::futures::SinkExt;
 use iced::window;
 use iced::{Element, Size, Subscription, Task};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 // use std::sync::{Mutex};
+use global_hotkey::{GlobalHotKeyEvent, GlobalHotKeyManager, HotKeyState};
 use tray_icon::menu::MenuEvent;
 use tray_icon::TrayIcon;
 
@@ -22,68 +38,246 @@ pub struct App {
     tunnels_file: PathBuf,
     tray_icon: Option<TrayIcon>,
     menu_ids: Option<TrayMenuIds>,
+    /// Tray menu rebuilds that failed in a row; the previous menu stays up meanwhile
+    tray_update_failures: u32,
+    /// Whether a retry of the failed tray menu rebuild is already scheduled
+    tray_retry_pending: bool,
     logger: Logger,
-    status_receiver: broadcast::Receiver<StatusUpdate>,
+    /// Receiver created with the status channel, shared with the status subscription
+    /// It exists before any tunnel starts, so updates sent before the
+    /// subscription first runs wait in it instead of being lost.
+    status_receiver: Arc<Mutex<broadcast::Receiver<StatusUpdate>>>,
+    /// Arguments of later launches, forwarded by the single-instance lock
+    launch_receiver: Option<broadcast::Receiver<Vec<String>>>,
+    /// The most recently removed tunnel and its position, kept while its undo toast is open
+    removed_tunnel: Option<(usize, Tunnel)>,
+    /// Result of the last update check, if one was made
+    latest_release: Option<ReleaseInfo>,
+    checking_for_update: bool,
+    /// Persistent log of status changes, if it could be opened
+    event_store: Option<EventStore>,
+    /// Error counts by type, when `error_stats` is enabled and the file could be read
+    error_stats: Option<ErrorStats>,
+    /// Cached autostart state for the dashboard checkbox
+    autostart_enabled: bool,
+    /// Print a one-line summary to the terminal instead of the log stream
+    status_line: bool,
+    /// Where the health report is written, if the path could be determined
+    health_file: Option<PathBuf>,
+    started_at: std::time::Instant,
+    /// Tunnels flagged in the tray because a forward's target was unreachable
+    forward_warning_tunnels: BTreeSet<String>,
+    /// Tunnel notifications held back briefly so bursts show as one summary
+    notification_batch: notifications::NotificationBatch,
+    /// Last error notified per tunnel, so the same error isn't notified again
+    /// Cleared when the tunnel connects, disconnects or is connected by hand.
+    notified_errors: BTreeMap<String, String>,
+    /// Tunnels with a tray connection test running, so a second click doesn't start another
+    tray_tests: BTreeSet<String>,
+    /// Retries a tunnels file save that failed transiently, on the monitor ticks
+    save_retry: SaveRetry,
+    /// Stops the connection test running in a tunnel form window
+    form_tests: BTreeMap<window::Id, TestCancel>,
+    /// Keeps the toggle hotkey registered
+    _hotkey_manager: Option<GlobalHotKeyManager>,
+    toggle_hotkey_id: Option<u32>,
+}
+
+/// How long the undo toast stays open after removing a tunnel
+const UNDO_TOAST_DURATION: Duration = Duration::from_secs(6);
+/// Time added by the tray's "Keep 30 More Minutes"
+const DISCONNECT_TIMER_EXTENSION: Duration = Duration::from_secs(30 * 60);
+/// How long the "Saved" confirmation stays in the tunnel form after Apply
+const SAVED_NOTICE_DURATION: Duration = Duration::from_secs(2);
+/// Delay before retrying a failed tray menu rebuild, doubled per failure in a row
+const TRAY_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// Longest delay between tray menu rebuild retries
+const TRAY_RETRY_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Result of a connection test, as delivered back to the tunnel form or a tray test
+/// (`DrillError` can't be cloned into a message, so it is reduced to what the form needs)
+#[derive(Debug, Clone)]
+pub enum TestOutcome {
+    Success(String),
+    /// Offered the IdentitiesOnly quick fix
+    TooManyAuthFailures(String),
+    /// Offered to trust the host key on first connect
+    UnknownHostKey(String),
+    Failed(String),
 }
 
 /// Identifies which field in the tunnel form was changed
 #[derive(Debug, Clone)]
 pub enum TunnelFormField {
     Name(String),
+    DisplayName(String),
     LocalHost(String),
+    BindInterface(String),
     LocalPort(String),
     RemoteHost(String),
     RemotePort(String),
+    RemoteIsSocket(bool),
+    RemoteSocket(String),
     SshUser(String),
     SshHost(String),
     SshPort(String),
     PrivateKey(String),
+    SshConfigFile(String),
+    IdentitiesOnly(bool),
+    AcceptNewHostKeys(bool),
+    InventoryFile(String),
+    AutoStart(bool),
+    OpenWebOnConnect(bool),
+    CleanExitDisconnects(bool),
+    Notifications(bool),
+    Favorite(bool),
+    EnvKey(usize, String),
+    EnvValue(usize, String),
+    AddEnv,
+    RemoveEnv(usize),
+    RequiredInterface(String),
+    GateHost(String),
+    AutoDisconnect(String),
+    SshBinary(String),
+    AuthMode(crate::tunnels::AuthMode),
+    JumpTunnel(Option<String>),
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     // Tray menu events
     TrayMenuEvent(MenuEvent),
+    HotkeyEvent(GlobalHotKeyEvent),
     OpenAbout,
+    OpenDashboard,
+    CheckForUpdates,
+    UpdateCheckFinished(Option<ReleaseInfo>),
+    OpenReleasePage(String),
     OpenCreateTunnel,
+    /// Read an ssh:// URI from the clipboard and pre-fill the create form with it
+    PasteConnectionUri,
+    ConnectionUriPasted(Option<String>),
     TunnelConnect(String),
+    /// Connect once on another local port, leaving the tunnel's setting alone
+    TunnelConnectOnPort(String, String),
+    OpenConnectOnPort(String),
+    ConnectOnPortMessage(window::Id, windows::connect_on_port::Message),
     TunnelDisconnect(String),
+    ReconnectErroredTunnels,
+    TunnelExtendTimer(String),
+    TunnelGenerateServiceFile(String),
+    TunnelShowEffectiveConfig(String),
+    TunnelCopySshConfig(String),
+    /// Test a saved tunnel's ssh connection from the tray, reporting by notification
+    TunnelTest(String),
+    TunnelTestFinished(String, TestOutcome),
+    EffectiveConfigCopy(window::Id),
     TunnelOpenWeb(String),
     TunnelEdit(String),
+    /// Open a form pre-filled from the tunnel that saves as a new tunnel
+    TunnelEditAsNew(String),
     TunnelRemove(String),
+    RequestRemoveTunnel(String),
+    /// Stop and start running tunnels again, with their current settings
+    TunnelsRestart(Vec<String>),
+    /// Mark a tunnel as served by the external forward on its local port
+    TunnelAdopt(String),
+    /// Reconnect accepting an unknown host key, saving that on the tunnel when true
+    TunnelTrustHost(String, bool),
+    ExportBundle,
+    ImportBundle,
+    ToggleStartAtLogin,
+    OpenLogViewer,
+    LogViewerRefresh(window::Id),
+    LogViewerMessage(window::Id, windows::log_viewer::Message),
+    CopyLogPath,
+    OpenCurrentLog,
+    /// Show the tunnel notifications batched since the first one
+    FlushNotifications,
+    OpenEventLog,
+    OpenErrorSummary,
+    ErrorSummaryReset,
+    OpenBatchEdit,
+    BatchEditMessage(window::Id, windows::batch_edit::Message),
+    OpenPuttyImport,
+    PuttyImportMessage(window::Id, windows::putty_import::Message),
+    EventLogMessage(window::Id, windows::event_log::Message),
+    RequestQuit,
     Quit,
+    ConfirmMessage(window::Id, windows::confirm::Message),
+
+    // Undo toast shown after removing a tunnel
+    UndoRemoveTunnel,
+    UndoToastDismiss(window::Id),
+    UndoToastExpired(window::Id),
 
     // Tunnel status monitoring
     TunnelStatusUpdate(StatusUpdate),
+    MonitorTunnels,
 
     // Window events
     WindowOpened(window::Id, WindowType),
+    WindowCloseRequested(window::Id),
     WindowClosed(window::Id),
 
     // Unified tunnel form messages (handles both create and edit)
     TunnelFormFieldChanged(window::Id, TunnelFormField),
+    TunnelFormTabSelected(window::Id, windows::FormTab),
+    TunnelFormTemplateSelected(window::Id, String),
+    TunnelFormTemplateNameChanged(window::Id, String),
+    TunnelFormSaveTemplate(window::Id),
+    TunnelFormDeleteTemplate(window::Id, String),
     TunnelFormBrowsePrivateKey(window::Id),
+    TunnelFormBrowseSshConfigFile(window::Id),
+    TunnelFormBrowseSshBinary(window::Id),
+    TunnelFormAssignFreePort(window::Id),
     TunnelFormTest(window::Id),
+    TunnelFormTestTick,
+    TunnelFormCancelTest(window::Id),
+    TunnelFormTestFinished(window::Id, Instant, TestOutcome),
     TunnelFormSubmit(window::Id),
+    TunnelFormApply(window::Id),
+    TunnelFormSavedNoticeExpired(window::Id),
     TunnelFormCancel(window::Id),
 
+    // Drill was launched again while running
+    InstanceLaunched(Vec<String>),
+
     // Internal
     UpdateTrayMenu,
+    /// Scheduled retry of a tray menu rebuild that failed
+    RetryTrayMenu,
 }
 
 impl App {
-    pub fn new() -> (Self, Task<Message>) {
-        let (config_path, mut logger): (std::path::PathBuf, crate::logs::Logger) = match config::init_config() {
-            Ok((config_path, logger)) => (config_path, logger),
+    pub fn new(status_line: bool, launch_receiver: Option<broadcast::Receiver<Vec<String>>>) -> (Self, Task<Message>) {
+        let (config_path, run_dir, mut logger): (std::path::PathBuf, std::path::PathBuf, crate::logs::Logger) = match config::init_config() {
+            Ok((config_path, run_dir, logger)) => (config_path, run_dir, logger),
             Err(e) => {
                 println!("Error initializing configuration: {}", e);
                 std::process::exit(1);
             }
         };
+        // The status line replaces the log stream on the terminal
+        logger.set_echo_stdout(!status_line);
         logger.log_print("Drill - Multi-Platform tunnel drilling Application");
         logger.log_print(&format!("Platform: {}", get_platform()));
         logger.log_print("");
         logger.log_print(&format!("Configuration loaded from: {}", config_path.display()));
+        logger.log_print(&format!("Runtime directory: {}", run_dir.display()));
+
+        let mut settings = match config::load_settings(&config_path) {
+            Ok(settings) => settings,
+            Err(e) => {
+                logger.log_print(&format!("Error loading settings, using defaults: {}", e));
+                config::Settings::default()
+            }
+        };
+        logger.set_echo_stdout(!status_line && config::log_to_stdout(&settings));
+        notifications::set_timeouts(
+            settings.notification_timeout_ms.unwrap_or(notifications::DEFAULT_TIMEOUT_MS),
+            settings.error_notification_timeout_ms.unwrap_or(notifications::DEFAULT_ERROR_TIMEOUT_MS),
+        );
 
         // Load tunnels from the tunnels file
         let tunnels_file = match config::get_tunnels_file_path() {
@@ -93,50 +287,147 @@ impl App {
                 std::process::exit(1);
             }
         };
+        // A tunnels file the user can't write is managed by someone else
+        if !settings.locked && config::tunnels_file_read_only(&tunnels_file) {
+            logger.log_print("Tunnels file is read-only, running in managed mode");
+            settings.locked = true;
+        }
 
         let tunnels = match TunnelManager::load_tunnels(&tunnels_file) {
             Ok(t) => t,
             Err(e) => {
                 logger.log_print(&format!("Error loading tunnels: {}", e));
+                // An unreadable file is never saved over (see `save_tunnels`), its tunnels stay on disk
+                rfd::MessageDialog::new()
+                    .set_title("Cannot Load Tunnels")
+                    .set_description(format!(
+                        "{}\n\nDrill starts without tunnels. Fix the file (or move it away) and restart Drill to get them back.",
+                        e
+                    ))
+                    .set_level(rfd::MessageLevel::Error)
+                    .show();
                 Vec::new()
             }
         };
 
+        let event_store = match config::get_events_file_path().and_then(EventStore::open) {
+            Ok(store) => Some(store),
+            Err(e) => {
+                logger.log_print(&format!("Error opening event log: {}", e));
+                None
+            }
+        };
+
+        let error_stats = if settings.error_stats {
+            match config::get_error_stats_file_path().and_then(ErrorStats::open) {
+                Ok(stats) => Some(stats),
+                Err(e) => {
+                    logger.log_print(&format!("Error opening error summary: {}", e));
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
         // Create tunnel manager
         let mut tunnel_manager = TunnelManager::new();
-        tunnel_manager.set_tunnels(tunnels.clone());
+        tunnel_manager.set_tunnels(tunnels);
+        tunnel_manager.set_settings(settings);
+        match logger.try_clone() {
+            Ok(ssh_logger) => tunnel_manager.set_logger(ssh_logger),
+            Err(e) => logger.log_print(&format!("Could not share log file with tunnels: {}", e)),
+        }
+        for problem in tunnel_manager.refresh_inventories().1 {
+            logger.log_print(&problem);
+        }
         // Create status channel
         let (status_tx, status_rx) = broadcast::channel(100);
         tunnel_manager.set_status_channel(status_tx);
-        let status_receiver = status_rx;
+        let status_receiver = Arc::new(Mutex::new(status_rx));
 
         // Initialize system tray
-        let tunnel_statuses: Vec<(String, crate::tunnels::TunnelStatus)> = tunnel_manager
-            .get_tunnels()
-            .iter()
-            .map(|t| (t.name.clone(), tunnel_manager.get_tunnel_status(&t.name)))
-            .collect();
-        let (tray_icon, menu_ids) = match systemtray::init_tray(&tunnels, &tunnel_statuses) {
-            Ok((icon, ids)) => (Some(icon), Some(ids)),
-            Err(e) => {
-                logger.log_print(&format!("Error initializing system tray: {}", e));
-                std::process::exit(1);
+        let tunnel_statuses = tunnel_manager.status_snapshot();
+        let tunnel_stats = tunnel_manager.stats_snapshot();
+        let disconnect_timers = tunnel_manager.disconnect_timers_snapshot();
+        let port_overrides = tunnel_manager.port_overrides_snapshot();
+        let forward_warnings = tunnel_manager.forward_warnings_snapshot();
+        let (tray_icon, menu_ids) = if tunnel_manager.settings().disable_tray {
+            logger.log_print("System tray disabled in settings, using the dashboard window");
+            (None, None)
+        } else {
+            match systemtray::init_tray(tunnel_manager.get_tunnels(), &tunnel_statuses, &tunnel_stats, &disconnect_timers, &port_overrides, &forward_warnings, tunnel_manager.settings()) {
+                Ok((icon, ids)) => (Some(icon), Some(ids)),
+                Err(e) => {
+                    // Keep the app usable through the dashboard instead of exiting
+                    logger.log_print(&format!("Error initializing system tray, using the dashboard window: {}", e));
+                    (None, None)
+                }
             }
         };
 
+        let (hotkey_manager, toggle_hotkey_id) = match &tunnel_manager.settings().toggle_hotkey {
+            Some(spec) => match hotkey::register_toggle_hotkey(spec) {
+                Ok((manager, id)) => {
+                    logger.log_print(&format!("Registered tunnel toggle hotkey {}", spec));
+                    (Some(manager), Some(id))
+                }
+                Err(e) => {
+                    logger.log_print(&format!("Error registering hotkey: {}", e));
+                    (None, None)
+                }
+            },
+            None => (None, None),
+        };
+
         logger.log_print("Drill initialized. Application running...");
 
+        // Only check for updates on startup when the user opted in
+        let check_for_updates = tunnel_manager.settings().check_for_updates;
+        let update_check = if check_for_updates {
+            update_check_task(updates::check_for_update)
+        } else {
+            Task::none()
+        };
+        // Without a tray the dashboard is the only way in
+        let open_dashboard = if tray_icon.is_none() {
+            Task::done(Message::OpenDashboard)
+        } else {
+            Task::none()
+        };
+        let startup_task = Task::batch([update_check, open_dashboard]);
+
         (
             Self {
                 windows: BTreeMap::new(),
                 tunnel_manager,
                 tunnels_file,
                 tray_icon,
+                tray_update_failures: 0,
+                tray_retry_pending: false,
                 menu_ids,
                 logger,
                 status_receiver,
+                launch_receiver,
+                removed_tunnel: None,
+                latest_release: None,
+                checking_for_update: check_for_updates,
+                event_store,
+                error_stats,
+                autostart_enabled: autostart::is_autostart_enabled(),
+                status_line,
+                health_file: config::get_health_file_path().ok(),
+                started_at: std::time::Instant::now(),
+                forward_warning_tunnels: BTreeSet::new(),
+                notification_batch: notifications::NotificationBatch::default(),
+                notified_errors: BTreeMap::new(),
+                tray_tests: BTreeSet::new(),
+                save_retry: SaveRetry::default(),
+                form_tests: BTreeMap::new(),
+                _hotkey_manager: hotkey_manager,
+                toggle_hotkey_id,
             },
-            Task::none(),
+            startup_task,
         )
     }
 
@@ -154,81 +445,286 @@ impl App {
                 }
                 // No custom icon set (icon_alpha.png usage removed)
                 let (id, open) = window::open(window::Settings {
-                    size: Size::new(400.0, 300.0),
+                    size: Size::new(400.0, 360.0),
                     resizable: false,
                     ..window::Settings::default()
                 });
                 open.then(move |_| Task::done(Message::WindowOpened(id, WindowType::About)))
             }
 
-            Message::OpenCreateTunnel => {
-                // Check if TunnelForm window in Create mode is already open
-                if let Some((window_id, _wt)) = self.windows.iter().find(|(_, wt)| {
-                    matches!(wt, WindowType::TunnelForm { mode: windows::FormMode::Create, .. })
-                }) {
+            Message::OpenDashboard => {
+                if let Some((window_id, _)) = self.windows.iter().find(|(_, wt)| matches!(wt, WindowType::Dashboard)) {
                     return window::gain_focus(*window_id);
                 }
-                // No custom icon set (icon_alpha.png usage removed)
                 let (id, open) = window::open(window::Settings {
-                    size: Size::new(500.0, 630.0),
-                    resizable: false,
+                    size: Size::new(720.0, 520.0),
+                    min_size: Some(Size::new(600.0, 360.0)),
                     ..window::Settings::default()
                 });
-                open.then(move |_| {
-                    Task::done(Message::WindowOpened(
-                        id,
-                        WindowType::new_tunnel_form_create(),
-                    ))
-                })
+                open.then(move |_| Task::done(Message::WindowOpened(id, WindowType::Dashboard)))
+            }
+
+            Message::InstanceLaunched(args) => {
+                // Launching again is how users look for the running app, so show it
+                self.logger.log_print(&format!("Drill launched again (arguments: {:?}), showing the dashboard", args));
+                self.update(Message::OpenDashboard)
+            }
+
+            Message::CheckForUpdates => {
+                let open_about = self.update(Message::OpenAbout);
+                if self.checking_for_update {
+                    return open_about;
+                }
+                self.checking_for_update = true;
+                Task::batch([open_about, update_check_task(updates::fetch_latest_release)])
+            }
+
+            Message::UpdateCheckFinished(release) => {
+                self.checking_for_update = false;
+                match &release {
+                    Some(release) if release.is_newer() => {
+                        self.logger.log_print(&format!("Drill {} is available: {}", release.version, release.url));
+                        // The About window already shows the result
+                        if !self.windows.values().any(|wt| matches!(wt, WindowType::About)) {
+                            notifications::notify_update_available(&release.version);
+                        }
+                    }
+                    Some(_) => self.logger.log_print("Drill is up to date"),
+                    None => {}
+                }
+                self.latest_release = release;
+                Task::none()
+            }
+
+            Message::OpenReleasePage(url) => {
+                if let Err(e) = open::that(&url) {
+                    self.logger.log_print(&format!("Failed to open release page: {}", e));
+                }
+                Task::none()
+            }
+
+            Message::OpenCreateTunnel => self.open_create_form(None),
+
+            Message::PasteConnectionUri => {
+                if self.refuses_changes("Creating a tunnel") {
+                    return Task::none();
+                }
+                iced::clipboard::read().map(Message::ConnectionUriPasted)
+            }
+
+            Message::ConnectionUriPasted(contents) => {
+                let parsed = match contents {
+                    Some(contents) => ssh_uri::parse_ssh_uri(&contents),
+                    None => Err("The clipboard has no text, copy an ssh://user@host:port URI first".to_string()),
+                };
+                match parsed {
+                    Ok(tunnel) => {
+                        self.logger.log_print(&format!("Pre-filling a new tunnel from URI for {}", tunnel.name));
+                        self.open_create_form(Some(windows::TunnelFormData::from_tunnel(&tunnel)))
+                    }
+                    Err(e) => {
+                        self.logger.log_print(&format!("Invalid connection URI: {}", e));
+                        rfd::MessageDialog::new()
+                            .set_title("Paste Connection URI")
+                            .set_description(format!(
+                                "{}\n\nExpected ssh://user@host:port, optionally followed by ?L=localport:remotehost:remoteport",
+                                e
+                            ))
+                            .set_level(rfd::MessageLevel::Error)
+                            .show();
+                        Task::none()
+                    }
+                }
             }
 
             Message::TunnelStatusUpdate(update) => {
+                if self.status_line {
+                    crate::status_line::print(&self.tunnel_manager.status_snapshot());
+                }
+                if let Some(script) = &self.tunnel_manager.settings().status_hook {
+                    hooks::run_status_hook(script, &update, &mut self.logger);
+                }
+                if let Some(store) = self.event_store.as_mut() {
+                    if let Err(e) = store.record(&update) {
+                        self.logger.log_print(&format!("Error recording event: {}", e));
+                    }
+                }
+                if let Some(stats) = self.error_stats.as_mut() {
+                    if let Err(e) = stats.record(&update, self.tunnel_manager.get_tunnels()) {
+                        self.logger.log_print(&format!("Error updating error summary: {}", e));
+                    }
+                }
                 match update {
                     StatusUpdate::Connecting(_tunnel_name) => {
                         // log_print(&format!("Tunnel '{}' is connecting...", _tunnel_name));
                     }
                     StatusUpdate::Connected(tunnel_name) => {
                         // log_print(&format!("Tunnel '{}' connected successfully", tunnel_name));
-                        let _ = notifications::notify_tunnel_connected(&tunnel_name);
-                        return self.update(Message::UpdateTrayMenu);
+                        // Stale update for a connect that was cancelled since
+                        if !matches!(self.tunnel_manager.get_tunnel_status(&tunnel_name), TunnelStatus::Connected { .. }) {
+                            return self.update(Message::UpdateTrayMenu);
+                        }
+                        self.notified_errors.remove(&tunnel_name);
+                        // Persist the last connected timestamp (managed tunnels are never written)
+                        if !self.tunnel_manager.settings().locked && self.tunnel_manager.connect_times_due() {
+                            if let Err(e) = self.save_retry.save(&self.tunnels_file, self.tunnel_manager.get_tunnels(), self.tunnel_manager.settings()) {
+                                self.logger.log_print(&format!("Error saving tunnels: {}", e));
+                            }
+                        }
+                        let notify = if tunnel_notifies(&mut self.logger, self.tunnel_manager.get_tunnels(), &tunnel_name, "connected") {
+                            self.queue_notification(notifications::BatchedKind::Connected, &tunnel_name, "")
+                        } else {
+                            Task::none()
+                        };
+                        // Only on a fresh connect, not when a reconnect restores the tunnel
+                        let open_web = !self.tunnel_manager.is_reconnect(&tunnel_name)
+                            && self.tunnel_manager.get_tunnels().iter().any(|t| t.name == tunnel_name && t.open_web_on_connect);
+                        if open_web {
+                            return Task::batch([
+                                notify,
+                                self.update(Message::UpdateTrayMenu),
+                                self.update(Message::TunnelOpenWeb(tunnel_name)),
+                            ]);
+                        }
+                        return Task::batch([notify, self.update(Message::UpdateTrayMenu)]);
                     }
                     StatusUpdate::Error(tunnel_name, error) => {
                         // log_print(&format!("Tunnel '{}' error: {}", tunnel_name, error));
-                        notifications::notify_tunnel_error(&tunnel_name, &error.to_string());
+                        let event = format!("error: {}", error);
+                        if self.is_new_error(&tunnel_name, &error) && tunnel_notifies(&mut self.logger, self.tunnel_manager.get_tunnels(), &tunnel_name, &event) {
+                            // The dialog stands in for the notification, with the fix attached
+                            if is_unknown_host_key_error(&error) {
+                                if let Some(retry) = self.offer_trust_host(&tunnel_name) {
+                                    return Task::batch([retry, self.update(Message::UpdateTrayMenu)]);
+                                }
+                            }
+                            let notify = self.queue_notification(notifications::BatchedKind::Failed, &tunnel_name, &error);
+                            return Task::batch([notify, self.update(Message::UpdateTrayMenu)]);
+                        }
+                        return self.update(Message::UpdateTrayMenu);
+                    }
+                    StatusUpdate::Disconnected(tunnel_name) => {
+                        // log_print(&format!("Tunnel '{}' disconnected", tunnel_name));
+                        self.notified_errors.remove(&tunnel_name);
                         return self.update(Message::UpdateTrayMenu);
                     }
-                    StatusUpdate::Disconnected(_tunnel_name) => {
-                        // log_print(&format!("Tunnel '{}' disconnected", _tunnel_name));
+                    StatusUpdate::Reconnecting(tunnel_name, attempt) => {
+                        self.logger.log_print(&format!("Tunnel '{}' reconnecting (attempt {})", tunnel_name, attempt));
                         return self.update(Message::UpdateTrayMenu);
                     }
                 }
                 Task::none()
             }
 
+            Message::MonitorTunnels => {
+                // Failures are reported through the status channel
+                self.tunnel_manager.monitor_tunnels();
+                match self.save_retry.retry(&self.tunnels_file, self.tunnel_manager.get_tunnels(), self.tunnel_manager.settings()) {
+                    Some(Ok(())) => self.logger.log_print("Saved tunnels on retry"),
+                    Some(Err(e)) => self.logger.log_print(&format!("Error saving tunnels: {}", e)),
+                    None => {}
+                }
+                // Picks up inventory edits; a changed list is shown by the tray update below
+                let (inventories_changed, problems) = self.tunnel_manager.refresh_inventories();
+                for problem in problems {
+                    self.logger.log_print(&problem);
+                }
+                for tunnel_name in self.tunnel_manager.stop_expired_tunnels() {
+                    self.logger.log_print(&format!("Tunnel '{}' reached its time limit, disconnected", tunnel_name));
+                    // Already logged above
+                    let enabled = self.tunnel_manager.get_tunnels().iter().find(|t| t.name == tunnel_name).is_none_or(|t| t.notifications);
+                    if enabled {
+                        notifications::notify_tunnel_timer_expired(&tunnel_name);
+                    }
+                }
+                // Periodic refresh keeps the status line's clock current
+                if self.status_line {
+                    crate::status_line::print(&self.tunnel_manager.status_snapshot());
+                }
+                if let Some(health_file) = &self.health_file {
+                    if let Err(e) = HealthReport::collect(&self.tunnel_manager, self.started_at).write(health_file) {
+                        self.logger.log_print(&format!("Error writing health report: {}", e));
+                    }
+                }
+                // Flag and unflag tunnels in the tray as forward failures come and go
+                let forward_warnings = self.tunnel_manager.forward_warnings_snapshot();
+                for (tunnel_name, warning) in &forward_warnings {
+                    if !self.forward_warning_tunnels.contains(tunnel_name) {
+                        self.logger.log_print(&format!("Tunnel '{}': remote target unreachable ({})", tunnel_name, warning));
+                    }
+                }
+                let flagged: BTreeSet<String> = forward_warnings.into_keys().collect();
+                let warnings_changed = flagged != self.forward_warning_tunnels;
+                self.forward_warning_tunnels = flagged;
+                // Keep the time left shown in the tray current
+                if inventories_changed || warnings_changed || !self.tunnel_manager.disconnect_timers_snapshot().is_empty() {
+                    return self.update(Message::UpdateTrayMenu);
+                }
+                Task::none()
+            }
+
             Message::TunnelConnect(tunnel_name) => {
                 // log_print(&format!("Connect tunnel '{}'", tunnel_name));
-                if let Some(tunnel) = self.tunnel_manager.get_tunnels().iter().find(|t| t.name == tunnel_name).cloned() {
-                    match self.tunnel_manager.start_tunnel(&tunnel) {
-                        Ok(_) => {
-                            // Notification will be sent only after true connection (StatusUpdate::Connected)
-                        }
-                        Err(e) => {
-                            self.logger.log_print(&format!(
-                                "Error starting tunnel '{}': {}",
-                                tunnel_name, e
-                            ));
-                            notifications::notify_tunnel_error(&tunnel_name, &e.to_string());
-                        }
-                    }
+                self.connect_tunnel(&tunnel_name, None)
+            }
+
+            Message::TunnelConnectOnPort(tunnel_name, local_port) => {
+                self.logger.log_print(&format!("Connect tunnel '{}' on local port {}", tunnel_name, local_port));
+                self.connect_tunnel(&tunnel_name, Some(&local_port))
+            }
+
+            Message::OpenConnectOnPort(tunnel_name) => {
+                let Some(tunnel) = self.tunnel_manager.get_tunnels().iter().find(|t| t.name == tunnel_name) else {
+                    return Task::none();
+                };
+                let configured_port = tunnel.local_port.clone();
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(380.0, 170.0),
+                    resizable: false,
+                    ..window::Settings::default()
+                });
+                open.then(move |_| {
+                    Task::done(Message::WindowOpened(
+                        id,
+                        WindowType::ConnectOnPort {
+                            tunnel_name: tunnel_name.clone(),
+                            configured_port: configured_port.clone(),
+                            local_port: String::new(),
+                            error_message: None,
+                        },
+                    ))
+                })
+            }
+
+            Message::ConnectOnPortMessage(window_id, message) => {
+                self.handle_connect_on_port_message(window_id, message)
+            }
+
+            Message::HotkeyEvent(event) => {
+                if Some(event.id) != self.toggle_hotkey_id || event.state != HotKeyState::Pressed {
+                    return Task::none();
+                }
+                let primary = hotkey::primary_tunnel(self.tunnel_manager.get_tunnels(), self.tunnel_manager.settings());
+                let Some(tunnel_name) = primary.map(|t| t.name.clone()) else {
+                    self.logger.log_print("Toggle hotkey pressed, but there is no primary tunnel");
+                    notifications::notify_no_primary_tunnel();
+                    return Task::none();
+                };
+                // The connect and disconnect paths notify about the resulting state
+                match self.tunnel_manager.get_tunnel_status(&tunnel_name) {
+                    TunnelStatus::Disconnected | TunnelStatus::Error { .. } => self.update(Message::TunnelConnect(tunnel_name)),
+                    _ => self.update(Message::TunnelDisconnect(tunnel_name)),
                 }
-                self.update(Message::UpdateTrayMenu)
             }
 
             Message::TunnelDisconnect(tunnel_name) => {
                 // log_print(&format!("Disconnect tunnel '{}'", tunnel_name));
+                let mut notify = Task::none();
                 match self.tunnel_manager.stop_tunnel(&tunnel_name) {
                     Ok(_) => {
-                        notifications::notify_tunnel_disconnected(&tunnel_name);
+                        if tunnel_notifies(&mut self.logger, self.tunnel_manager.get_tunnels(), &tunnel_name, "disconnected") {
+                            notify = self.queue_notification(notifications::BatchedKind::Disconnected, &tunnel_name, "");
+                        }
                     }
                     Err(e) => {
                         self.logger.log_print(&format!(
@@ -237,18 +733,124 @@ impl App {
                         ));
                     }
                 }
+                Task::batch([notify, self.update(Message::UpdateTrayMenu)])
+            }
+
+            Message::ReconnectErroredTunnels => {
+                // Only tunnels in Error: idle, connecting and connected ones are left alone
+                let errored: Vec<Tunnel> = self
+                    .tunnel_manager
+                    .status_snapshot()
+                    .into_iter()
+                    .filter(|(_, status)| matches!(status, TunnelStatus::Error { .. }))
+                    .filter_map(|(name, _)| self.tunnel_manager.get_tunnels().iter().find(|t| t.name == name).cloned())
+                    .collect();
+                if errored.is_empty() {
+                    return Task::none();
+                }
+                let mut started = 0;
+                let mut failed = 0;
+                for tunnel in &errored {
+                    match self.tunnel_manager.start_tunnel(tunnel, None) {
+                        Ok(_) => started += 1,
+                        Err(e) => {
+                            failed += 1;
+                            self.logger.log_print(&format!("Error reconnecting tunnel '{}': {}", tunnel.name, e));
+                        }
+                    }
+                }
+                self.logger.log_print(&format!("Reconnecting {} errored tunnel(s), {} failed to start", started, failed));
+                notifications::notify_errored_tunnels_reconnected(started, failed);
+                self.update(Message::UpdateTrayMenu)
+            }
+
+            Message::TunnelGenerateServiceFile(tunnel_name) => {
+                self.handle_generate_service_file(&tunnel_name)
+            }
+
+            Message::TunnelShowEffectiveConfig(tunnel_name) => {
+                let manager = &self.tunnel_manager;
+                let Some(tunnel) = manager.get_tunnels().iter().find(|t| t.name == tunnel_name) else {
+                    return Task::none();
+                };
+                let config = match windows::effective_config::render(tunnel, manager.get_tunnels(), manager.settings()) {
+                    Ok(config) => config,
+                    Err(e) => format!("Cannot build the ssh command: {}", e),
+                };
+                // Refresh an already open window for this tunnel
+                let open_window = self.windows.iter_mut().find(|(_, wt)| {
+                    matches!(wt, WindowType::EffectiveConfig { tunnel_name: name, .. } if *name == tunnel_name)
+                });
+                if let Some((window_id, WindowType::EffectiveConfig { config: shown, .. })) = open_window {
+                    *shown = config;
+                    return window::gain_focus(*window_id);
+                }
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(760.0, 520.0),
+                    ..window::Settings::default()
+                });
+                open.then(move |_| {
+                    Task::done(Message::WindowOpened(id, WindowType::EffectiveConfig {
+                        tunnel_name: tunnel_name.clone(),
+                        config: config.clone(),
+                    }))
+                })
+            }
+
+            Message::TunnelCopySshConfig(tunnel_name) => {
+                self.handle_copy_ssh_config(&tunnel_name)
+            }
+
+            Message::TunnelTest(tunnel_name) => {
+                if self.tray_tests.contains(&tunnel_name) {
+                    self.logger.log_print(&format!("Tunnel '{}' is already being tested", tunnel_name));
+                    return Task::none();
+                }
+                let Some(tunnel) = self.tunnel_manager.get_tunnels().iter().find(|t| t.name == tunnel_name).cloned() else {
+                    return Task::none();
+                };
+                let test = match self.tunnel_manager.connection_test(&tunnel) {
+                    Ok(test) => test,
+                    Err(e) => {
+                        notifications::notify_connection_test(&tunnel_name, false, &e.to_string());
+                        return Task::none();
+                    }
+                };
+                self.logger.log_print(&format!("Testing tunnel '{}'", tunnel_name));
+                self.tray_tests.insert(tunnel_name.clone());
+                Task::perform(run_connection_test(test), move |outcome| {
+                    Message::TunnelTestFinished(tunnel_name.clone(), outcome)
+                })
+            }
+
+            Message::TunnelTestFinished(tunnel_name, outcome) => {
+                self.tray_tests.remove(&tunnel_name);
+                let (succeeded, result) = tray_test_result(outcome);
+                self.logger.log_print(&format!("Test of tunnel '{}': {}", tunnel_name, result));
+                notifications::notify_connection_test(&tunnel_name, succeeded, &result);
+                Task::none()
+            }
+
+            Message::EffectiveConfigCopy(window_id) => {
+                match self.windows.get(&window_id) {
+                    Some(WindowType::EffectiveConfig { config, .. }) => iced::clipboard::write(config.clone()),
+                    _ => Task::none(),
+                }
+            }
+
+            Message::TunnelExtendTimer(tunnel_name) => {
+                self.tunnel_manager.extend_disconnect_timer(&tunnel_name, DISCONNECT_TIMER_EXTENSION);
+                self.logger.log_print(&format!("Extended the time limit of tunnel '{}'", tunnel_name));
                 self.update(Message::UpdateTrayMenu)
             }
 
             Message::TunnelOpenWeb(tunnel_name) => {
                 // log_print(&format!("Open web for tunnel '{}'", tunnel_name));
                 if let Some(tunnel) = self.tunnel_manager.get_tunnels().iter().find(|t| t.name == tunnel_name) {
-                    let url = format!("http://{}:{}", tunnel.local_host, tunnel.local_port);
+                    let url = tunnel.web_url(self.tunnel_manager.local_port_override(&tunnel_name));
                     self.logger.log_print(&format!("Opening URL: {}", url));
-                        if let Err(_e) =
-                            TunnelManager::save_tunnels(&self.tunnels_file, self.tunnel_manager.get_tunnels())
-                        {
-                            self.logger.log_print(&format!("Error saving tunnels: {}", _e));
+                    if let Err(e) = open::that(&url) {
+                        self.logger.log_print(&format!("Failed to open {}: {}", url, e));
                     }
                 } else {
                     self.logger.log_print(&format!("Tunnel '{}' not found", tunnel_name));
@@ -258,6 +860,9 @@ impl App {
 
             Message::TunnelEdit(tunnel_name) => {
                 // log_print(&format!("Edit tunnel '{}'", tunnel_name));
+                if self.refuses_changes("Editing a tunnel") || self.is_generated(&tunnel_name) {
+                    return Task::none();
+                }
                 // Check if TunnelForm window in Edit mode for this tunnel is already open
                 if let Some((window_id, _wt)) = self.windows.iter().find(|(_, wt)| {
                     matches!(wt, WindowType::TunnelForm { mode: windows::FormMode::Edit { tunnel_id }, .. } if {
@@ -271,16 +876,19 @@ impl App {
                 // Find the tunnel and open edit window
                 if let Some(tunnel) = self.tunnel_manager.get_tunnels().iter().find(|t| t.name == tunnel_name) {
                     let tunnel_clone = tunnel.clone();
+                    let template_names = self.template_names();
+                    let jump_choices = windows::JumpChoice::list(self.tunnel_manager.get_tunnels(), Some(&tunnel.id));
                     self.logger.log_print("Opening Edit Tunnel window...");
                     let (id, open) = window::open(window::Settings {
-                        size: Size::new(600.0, 700.0),
+                        size: Size::new(600.0, 560.0),
                         resizable: true,
+                        exit_on_close_request: false,
                         ..window::Settings::default()
                     });
                     return open.then(move |_| {
                         Task::done(Message::WindowOpened(
                             id,
-                            WindowType::new_tunnel_form_edit(&tunnel_clone),
+                            WindowType::new_tunnel_form_edit(&tunnel_clone, template_names.clone(), jump_choices.clone()),
                         ))
                     });
                 } else {
@@ -289,17 +897,55 @@ impl App {
                 Task::none()
             }
 
+            Message::TunnelEditAsNew(tunnel_name) => {
+                if self.refuses_changes("Creating a tunnel") {
+                    return Task::none();
+                }
+                let Some(tunnel) = self.tunnel_manager.get_tunnels().iter().find(|t| t.name == tunnel_name).cloned() else {
+                    self.logger.log_print(&format!("Tunnel '{}' not found", tunnel_name));
+                    return Task::none();
+                };
+                // Each click opens its own window, to try several variations side by side
+                let template_names = self.template_names();
+                let jump_choices = windows::JumpChoice::list(self.tunnel_manager.get_tunnels(), None);
+                let window_type = WindowType::new_tunnel_form_create_from(
+                    &tunnel,
+                    self.tunnel_manager.get_tunnels(),
+                    template_names,
+                    jump_choices,
+                );
+                self.logger.log_print(&format!("Opening Edit as New window for tunnel '{}'...", tunnel_name));
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(600.0, 560.0),
+                    resizable: true,
+                    exit_on_close_request: false,
+                    ..window::Settings::default()
+                });
+                open.then(move |_| Task::done(Message::WindowOpened(id, window_type.clone())))
+            }
+
             Message::TunnelRemove(tunnel_name) => {
+                if self.refuses_changes("Removing a tunnel") || self.is_generated(&tunnel_name) {
+                    return Task::none();
+                }
                 self.logger.log_print(&format!("Remove tunnel '{}'", tunnel_name));
+                self.notified_errors.remove(&tunnel_name);
                 match self.tunnel_manager.remove_tunnel(&tunnel_name) {
-                    Ok(_) => {
+                    Ok(removed) => {
                         // Save the updated tunnels list
                         if let Err(e) =
-                            TunnelManager::save_tunnels(&self.tunnels_file, self.tunnel_manager.get_tunnels())
+                            self.save_retry.save(&self.tunnels_file, self.tunnel_manager.get_tunnels(), self.tunnel_manager.settings())
                         {
                             self.logger.log_print(&format!("Error saving tunnels: {}", e));
                         } else {
-                            notifications::notify_tunnel_removed(&tunnel_name);
+                            if tunnel_notifies(&mut self.logger, std::slice::from_ref(&removed), &tunnel_name, "removed") {
+                                notifications::notify_tunnel_removed(&tunnel_name);
+                            }
+                            self.removed_tunnel = Some(removed);
+                            return Task::batch([
+                                self.update(Message::UpdateTrayMenu),
+                                self.open_undo_toast(tunnel_name),
+                            ]);
                         }
                     }
                     Err(e) => {
@@ -312,80 +958,569 @@ impl App {
                 self.update(Message::UpdateTrayMenu)
             }
 
-            Message::Quit => {
-                self.logger.log_print("Quit selected from tray menu");
-                let manager = &mut self.tunnel_manager;
-                manager.cleanup();
-                let _ = manager;
-                iced::exit()
+            Message::RequestRemoveTunnel(tunnel_name) => {
+                if self.refuses_changes("Removing a tunnel") {
+                    return Task::none();
+                }
+                self.open_confirm(
+                    "Remove Tunnel",
+                    format!("Remove tunnel '{}'?", tunnel_name),
+                    ConfirmAction::RemoveTunnel(tunnel_name),
+                )
             }
 
-            Message::WindowOpened(id, window_type) => {
-                self.windows.insert(id, window_type);
-                Task::none()
+            Message::TunnelsRestart(tunnel_names) => {
+                for tunnel_name in &tunnel_names {
+                    let Some(tunnel) = self.tunnel_manager.get_tunnels().iter().find(|t| &t.name == tunnel_name).cloned() else {
+                        continue;
+                    };
+                    let _ = self.tunnel_manager.stop_tunnel(tunnel_name);
+                    if let Err(e) = self.tunnel_manager.start_tunnel(&tunnel, None) {
+                        self.logger.log_print(&format!("Error restarting tunnel '{}': {}", tunnel_name, e));
+                    }
+                }
+                // Open edit forms of the restarted tunnels now run with what they saved
+                let restarted_ids: Vec<String> = self
+                    .tunnel_manager
+                    .get_tunnels()
+                    .iter()
+                    .filter(|t| tunnel_names.contains(&t.name))
+                    .map(|t| t.id.clone())
+                    .collect();
+                for wt in self.windows.values_mut() {
+                    if let WindowType::TunnelForm { mode: windows::FormMode::Edit { tunnel_id }, form, saved, original, restart_fields, .. } = wt {
+                        if restarted_ids.contains(tunnel_id) {
+                            *restart_fields = windows::connection_changes(saved, form);
+                            *original = Some(saved.clone());
+                        }
+                    }
+                }
+                self.update(Message::UpdateTrayMenu)
             }
 
-            Message::WindowClosed(id) => {
-                self.windows.remove(&id);
-                Task::none()
+            Message::TunnelAdopt(tunnel_name) => {
+                if self.tunnel_manager.adopt_external(&tunnel_name) {
+                    self.logger.log_print(&format!("Adopted the external forward of tunnel '{}'", tunnel_name));
+                }
+                self.update(Message::UpdateTrayMenu)
             }
 
-            // Unified tunnel form field update handler
-            Message::TunnelFormFieldChanged(window_id, field) => {
-                self.update_tunnel_form_field(window_id, field);
-                Task::none()
+            Message::TunnelTrustHost(tunnel_name, remember) => {
+                self.trust_host_and_retry(&tunnel_name, remember);
+                self.update(Message::UpdateTrayMenu)
             }
 
-            Message::TunnelFormBrowsePrivateKey(window_id) => {
-                if let Some(path) = windows::create_tunnel::browse_for_private_key() {
-                    self.update_tunnel_form_field(
-                        window_id,
-                        TunnelFormField::PrivateKey(path),
-                    );
-                }
+            Message::ExportBundle => {
+                self.handle_export_bundle();
                 Task::none()
             }
 
-            Message::TunnelFormTest(window_id) => {
-                // Get the window data and test the connection
-                let window_type = self.windows.get_mut(&window_id);
-                if window_type.is_none() {
+            Message::ImportBundle => {
+                if self.refuses_changes("Importing tunnels") {
                     return Task::none();
                 }
+                self.handle_import_bundle();
+                self.update(Message::UpdateTrayMenu)
+            }
 
-                let extra_height = match window_type.unwrap() {
-                    WindowType::TunnelForm {
-                        name, local_host, local_port, remote_host, remote_port,
-                        ssh_user, ssh_host, ssh_port, private_key,
-                        error_message, test_message, ..
-                    } => {
-                        *error_message = None;
-                        *test_message = None;
-                        match windows::create_tunnel::validate_and_create_tunnel(
-                            name, local_host, local_port, remote_host, remote_port,
-                            ssh_user, ssh_host, ssh_port, private_key,
-                        ) {
-                            Ok(tunnel) => {
-                                match TunnelManager::test_tunnel(&tunnel) {
-                                    Ok(success_msg) => *test_message = Some(success_msg),
-                                    Err(err) => *test_message = Some(format!("{}", err)),
-                                }
-                                test_message.as_ref().map(|msg| (msg.len() / 60).max(1) as f32 * 20.0 + 40.0).unwrap_or(0.0)
-                            }
-                            Err(err) => {
-                                *error_message = Some(format!("{}", err));
-                                error_message.as_ref().map(|msg| (msg.len() / 60).max(1) as f32 * 20.0 + 40.0).unwrap_or(0.0)
-                            }
-                        }
-                    }
-                    _ => return Task::none(),
+            Message::ToggleStartAtLogin => {
+                let result = if autostart::is_autostart_enabled() {
+                    autostart::disable_autostart()
+                } else {
+                    autostart::enable_autostart()
                 };
+                self.autostart_enabled = autostart::is_autostart_enabled();
+                match result {
+                    Ok(()) => {
+                        self.logger.log_print(&format!("Start at login {}", if self.autostart_enabled { "enabled" } else { "disabled" }));
+                    }
+                    Err(e) => {
+                        self.logger.log_print(&format!("Failed to change start at login: {}", e));
+                    }
+                }
+                // Rebuild the menu so the check mark matches the actual state
+                self.update(Message::UpdateTrayMenu)
+            }
 
-                window::resize(window_id, Size::new(500.0, 640.0 + extra_height))
+            Message::OpenLogViewer => {
+                if let Some((window_id, _)) = self.windows.iter().find(|(_, wt)| matches!(wt, WindowType::LogViewer { .. })) {
+                    let window_id = *window_id;
+                    return Task::batch([
+                        self.update(Message::LogViewerRefresh(window_id)),
+                        window::gain_focus(window_id),
+                    ]);
+                }
+                let lines = self.read_log_lines();
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(900.0, 600.0),
+                    ..window::Settings::default()
+                });
+                open.then(move |_| Task::done(Message::WindowOpened(id, WindowType::LogViewer { lines: lines.clone(), export: Default::default() })))
             }
 
-            Message::TunnelFormSubmit(window_id) => {
-                self.handle_tunnel_form_submit(window_id)
+            Message::OpenErrorSummary => {
+                if let Some((window_id, _)) = self.windows.iter().find(|(_, wt)| matches!(wt, WindowType::ErrorSummary)) {
+                    return window::gain_focus(*window_id);
+                }
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(560.0, 420.0),
+                    ..window::Settings::default()
+                });
+                open.then(move |_| Task::done(Message::WindowOpened(id, WindowType::ErrorSummary)))
+            }
+
+            Message::ErrorSummaryReset => {
+                if let Some(stats) = self.error_stats.as_mut() {
+                    if let Err(e) = stats.reset() {
+                        self.logger.log_print(&format!("Error resetting error summary: {}", e));
+                    }
+                }
+                Task::none()
+            }
+
+            Message::LogViewerRefresh(window_id) => {
+                let new_lines = self.read_log_lines();
+                if let Some(WindowType::LogViewer { lines, .. }) = self.windows.get_mut(&window_id) {
+                    *lines = new_lines;
+                }
+                Task::none()
+            }
+
+            Message::CopyLogPath => {
+                let log_path = self.logger.path().display().to_string();
+                self.logger.log_print(&format!("Copied log path {}", log_path));
+                iced::clipboard::write(log_path)
+            }
+
+            Message::OpenCurrentLog => {
+                // Opens with the system's default viewer for .log files
+                let log_path = self.logger.path().to_path_buf();
+                if let Err(e) = open::that(&log_path) {
+                    self.logger.log_print(&format!("Failed to open {}: {}", log_path.display(), e));
+                }
+                Task::none()
+            }
+
+            Message::FlushNotifications => {
+                self.notification_batch.flush();
+                Task::none()
+            }
+
+            Message::LogViewerMessage(window_id, message) => {
+                self.handle_log_viewer_message(window_id, message);
+                Task::none()
+            }
+
+            Message::OpenEventLog => {
+                if let Some((window_id, _)) = self.windows.iter().find(|(_, wt)| matches!(wt, WindowType::EventLog { .. })) {
+                    return window::gain_focus(*window_id);
+                }
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(900.0, 600.0),
+                    ..window::Settings::default()
+                });
+                open.then(move |_| {
+                    Task::done(Message::WindowOpened(id, WindowType::EventLog { state: Default::default() }))
+                })
+            }
+
+            Message::EventLogMessage(window_id, message) => {
+                self.handle_event_log_message(window_id, message);
+                Task::none()
+            }
+
+            Message::OpenBatchEdit => {
+                if self.refuses_changes("Batch editing") {
+                    return Task::none();
+                }
+                if let Some((window_id, _)) = self.windows.iter().find(|(_, wt)| matches!(wt, WindowType::BatchEdit { .. })) {
+                    return window::gain_focus(*window_id);
+                }
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(560.0, 460.0),
+                    ..window::Settings::default()
+                });
+                open.then(move |_| {
+                    Task::done(Message::WindowOpened(id, WindowType::BatchEdit { state: Default::default() }))
+                })
+            }
+
+            Message::BatchEditMessage(window_id, message) => {
+                self.handle_batch_edit_message(window_id, message)
+            }
+
+            Message::OpenPuttyImport => {
+                if self.refuses_changes("Importing tunnels") {
+                    return Task::none();
+                }
+                if let Some((window_id, _)) = self.windows.iter().find(|(_, wt)| matches!(wt, WindowType::PuttyImport { .. })) {
+                    return window::gain_focus(*window_id);
+                }
+                let tunnels = match putty::import_putty_sessions() {
+                    Ok(tunnels) => tunnels,
+                    Err(e) => {
+                        self.logger.log_print(&format!("Error reading PuTTY sessions: {}", e));
+                        rfd::MessageDialog::new()
+                            .set_title("Import PuTTY Sessions")
+                            .set_description(e.to_string())
+                            .set_level(rfd::MessageLevel::Error)
+                            .show();
+                        return Task::none();
+                    }
+                };
+                if tunnels.is_empty() {
+                    rfd::MessageDialog::new()
+                        .set_title("Import PuTTY Sessions")
+                        .set_description("No saved PuTTY session has a local port forwarding to import.")
+                        .show();
+                    return Task::none();
+                }
+                let state = windows::putty_import::PuttyImportState::new(
+                    tunnels,
+                    self.tunnel_manager.get_tunnels(),
+                    putty::puttygen_program().is_some(),
+                );
+                let (id, open) = window::open(window::Settings {
+                    size: Size::new(600.0, 500.0),
+                    ..window::Settings::default()
+                });
+                open.then(move |_| {
+                    Task::done(Message::WindowOpened(id, WindowType::PuttyImport { state: state.clone() }))
+                })
+            }
+
+            Message::PuttyImportMessage(window_id, message) => {
+                self.handle_putty_import_message(window_id, message)
+            }
+
+            Message::RequestQuit => {
+                // Nothing is lost by quitting with no tunnel up
+                let manager = &self.tunnel_manager;
+                let active = manager.get_tunnels().iter().filter(|t| manager.is_tunnel_active(&t.name)).count();
+                if active == 0 {
+                    return self.update(Message::Quit);
+                }
+                self.open_confirm(
+                    "Quit Drill",
+                    format!("Quit Drill? {} active tunnel(s) will be disconnected.", active),
+                    ConfirmAction::Quit,
+                )
+            }
+
+            Message::ConfirmMessage(window_id, message) => {
+                let close = window::close(window_id);
+                let Some(WindowType::Confirm { on_confirm, alternative, .. }) = self.windows.get(&window_id) else {
+                    return close;
+                };
+                match confirm_follow_up(on_confirm, alternative.as_ref().map(|(_, action)| action), message) {
+                    Some(action) => Task::batch([close, self.update(action)]),
+                    None => close,
+                }
+            }
+
+            Message::Quit => {
+                self.logger.log_print("Quit requested");
+                // Runs to completion (processes reaped) before the exit task is returned
+                let manager = &mut self.tunnel_manager;
+                manager.cleanup();
+                let _ = manager;
+                if let Some(health_file) = &self.health_file {
+                    health::remove_report(health_file);
+                }
+                if self.status_line {
+                    crate::status_line::finish();
+                }
+                iced::exit()
+            }
+
+            Message::WindowOpened(id, window_type) => {
+                self.windows.insert(id, window_type);
+                Task::none()
+            }
+
+            Message::WindowCloseRequested(id) => {
+                // Only tunnel forms ask before closing (the others close without a request)
+                let Some(window_type) = self.windows.get(&id) else {
+                    return window::close(id);
+                };
+                if !window_type.has_unsaved_changes() {
+                    return window::close(id);
+                }
+                let name = match window_type {
+                    WindowType::TunnelForm { form, .. } if !form.name.trim().is_empty() => format!("'{}'", form.name.trim()),
+                    _ => "this tunnel".to_string(),
+                };
+                self.open_confirm(
+                    "Unsaved Changes",
+                    format!("Discard your unsaved changes to {}?", name),
+                    ConfirmAction::DiscardForm(id),
+                )
+            }
+
+            Message::WindowClosed(id) => {
+                // A form closed mid-test doesn't leave its ssh running
+                if let Some(test) = self.form_tests.remove(&id) {
+                    test.cancel();
+                }
+                // Once the undo toast for the cached tunnel is gone, the removal is final
+                match self.windows.remove(&id) {
+                    Some(WindowType::UndoToast { tunnel_name }) => {
+                        if matches!(&self.removed_tunnel, Some((_, t)) if t.name == tunnel_name) {
+                            self.removed_tunnel = None;
+                        }
+                    }
+                    // Closing the dashboard when there is no tray leaves nothing to interact with
+                    Some(WindowType::Dashboard) if self.tray_icon.is_none() => {
+                        return self.update(Message::Quit);
+                    }
+                    _ => {}
+                }
+                Task::none()
+            }
+
+            Message::UndoRemoveTunnel => {
+                let close_toasts = self.close_undo_toasts();
+                let Some((index, tunnel)) = self.removed_tunnel.take() else {
+                    return close_toasts;
+                };
+                if self.tunnel_manager.get_tunnels().iter().any(|t| t.name == tunnel.name) {
+                    self.logger.log_print(&format!(
+                        "Cannot restore tunnel '{}': a tunnel with that name already exists",
+                        tunnel.name
+                    ));
+                    return close_toasts;
+                }
+                self.logger.log_print(&format!("Restoring removed tunnel '{}'", tunnel.name));
+                self.tunnel_manager.insert_tunnel(index, tunnel);
+                if let Err(e) = self.save_retry.save(&self.tunnels_file, self.tunnel_manager.get_tunnels(), self.tunnel_manager.settings()) {
+                    self.logger.log_print(&format!("Error saving tunnels: {}", e));
+                }
+                Task::batch([close_toasts, self.update(Message::UpdateTrayMenu)])
+            }
+
+            Message::UndoToastDismiss(window_id) => window::close(window_id),
+
+            Message::UndoToastExpired(window_id) => {
+                if self.windows.contains_key(&window_id) {
+                    window::close(window_id)
+                } else {
+                    Task::none()
+                }
+            }
+
+            // Unified tunnel form field update handler
+            Message::TunnelFormFieldChanged(window_id, field) => {
+                self.update_tunnel_form_field(window_id, field);
+                Task::none()
+            }
+
+            Message::TunnelFormTabSelected(window_id, tab) => {
+                if let Some(WindowType::TunnelForm { form_tab, .. }) = self.windows.get_mut(&window_id) {
+                    *form_tab = tab;
+                }
+                Task::none()
+            }
+
+            Message::TunnelFormTemplateSelected(window_id, template_name) => {
+                self.apply_template_to_form(window_id, template_name);
+                Task::none()
+            }
+
+            Message::TunnelFormTemplateNameChanged(window_id, name) => {
+                if let Some(WindowType::TunnelForm { templates: template_state, .. }) = self.windows.get_mut(&window_id) {
+                    template_state.new_name = name;
+                }
+                Task::none()
+            }
+
+            Message::TunnelFormSaveTemplate(window_id) => {
+                self.save_form_as_template(window_id);
+                Task::none()
+            }
+
+            Message::TunnelFormDeleteTemplate(window_id, template_name) => {
+                self.delete_template_from_form(window_id, template_name);
+                Task::none()
+            }
+
+            Message::TunnelFormBrowsePrivateKey(window_id) => {
+                if let Some(path) = windows::create_tunnel::browse_for_private_key() {
+                    self.update_tunnel_form_field(
+                        window_id,
+                        TunnelFormField::PrivateKey(path),
+                    );
+                }
+                Task::none()
+            }
+
+            Message::TunnelFormBrowseSshConfigFile(window_id) => {
+                if let Some(path) = windows::create_tunnel::browse_for_ssh_config_file() {
+                    self.update_tunnel_form_field(
+                        window_id,
+                        TunnelFormField::SshConfigFile(path),
+                    );
+                }
+                Task::none()
+            }
+
+            Message::TunnelFormBrowseSshBinary(window_id) => {
+                if let Some(path) = windows::create_tunnel::browse_for_ssh_binary() {
+                    self.update_tunnel_form_field(
+                        window_id,
+                        TunnelFormField::SshBinary(path),
+                    );
+                }
+                Task::none()
+            }
+
+            Message::TunnelFormAssignFreePort(window_id) => {
+                let Some(WindowType::TunnelForm { mode, form, error_message, .. }) = self.windows.get_mut(&window_id) else {
+                    return Task::none();
+                };
+                // The edited tunnel's own port is free for it to keep
+                let editing_id = match mode {
+                    windows::FormMode::Edit { tunnel_id } => Some(tunnel_id.clone()),
+                    windows::FormMode::Create | windows::FormMode::CreateFrom { .. } => None,
+                };
+                let others: Vec<Tunnel> = self
+                    .tunnel_manager
+                    .get_tunnels()
+                    .iter()
+                    .filter(|t| Some(&t.id) != editing_id.as_ref())
+                    .cloned()
+                    .collect();
+                match crate::tunnels::next_free_local_port(&others, &form.local_host) {
+                    Some(port) => {
+                        *error_message = None;
+                        self.logger.log_print(&format!("Assigned free local port {}", port));
+                        self.update_tunnel_form_field(window_id, TunnelFormField::LocalPort(port.to_string()));
+                    }
+                    None => *error_message = Some("No free local port found".to_string()),
+                }
+                Task::none()
+            }
+
+            Message::TunnelFormTest(window_id) => {
+                let Some(WindowType::TunnelForm { mode, form, error_message, test_message, test_started, .. }) = self.windows.get_mut(&window_id) else {
+                    return Task::none();
+                };
+                *error_message = None;
+                *test_message = None;
+                let prepared = windows::create_tunnel::validate_and_create_tunnel(form, &[])
+                    .map_err(|err| err.to_string())
+                    .and_then(|(mut tunnel, _)| {
+                        // Keep the id so a jump cycle through this tunnel is caught
+                        if let windows::FormMode::Edit { tunnel_id } = mode {
+                            tunnel.id = tunnel_id.clone();
+                        }
+                        self.tunnel_manager.connection_test(&tunnel).map_err(|err| err.to_string())
+                    });
+                let test = match prepared {
+                    Ok(test) => test,
+                    Err(err) => {
+                        let extra_height = (err.len() / 60).max(1) as f32 * 20.0 + 40.0;
+                        *error_message = Some(err);
+                        return window::resize(window_id, Size::new(500.0, 530.0 + extra_height));
+                    }
+                };
+
+                // ssh blocks for up to TEST_TIMEOUT, keep it off the UI thread
+                let started = Instant::now();
+                *test_started = Some(started);
+                // A superseded test's ssh isn't needed any more
+                if let Some(previous) = self.form_tests.insert(window_id, test.canceller()) {
+                    previous.cancel();
+                }
+                Task::perform(
+                    run_connection_test(test),
+                    move |outcome| Message::TunnelFormTestFinished(window_id, started, outcome),
+                )
+            }
+
+            Message::TunnelFormTestTick => Task::none(),
+
+            Message::TunnelFormCancelTest(window_id) => {
+                // The test ends with its ssh killed, its result is ignored
+                if let Some(test) = self.form_tests.remove(&window_id) {
+                    test.cancel();
+                }
+                if let Some(WindowType::TunnelForm { test_started, .. }) = self.windows.get_mut(&window_id) {
+                    *test_started = None;
+                }
+                Task::none()
+            }
+
+            Message::TunnelFormTestFinished(window_id, started, outcome) => {
+                let Some(WindowType::TunnelForm { form, test_message, test_started, .. }) = self.windows.get_mut(&window_id) else {
+                    return Task::none();
+                };
+                // A canceled or superseded test
+                if *test_started != Some(started) {
+                    return Task::none();
+                }
+                *test_started = None;
+                self.form_tests.remove(&window_id);
+                match outcome {
+                    TestOutcome::Success(success_msg) => *test_message = Some(success_msg),
+                    // Quick fix: offer to restrict ssh to the tunnel's key
+                    TestOutcome::TooManyAuthFailures(host) if !form.identities_only => {
+                        let fix = rfd::MessageDialog::new()
+                            .set_title("Too Many Authentication Failures")
+                            .set_description(format!(
+                                "{} closed the connection after too many keys were offered (ssh-agent may hold several).\n\nSet IdentitiesOnly=yes so only this tunnel's key is used?",
+                                host
+                            ))
+                            .set_buttons(rfd::MessageButtons::YesNo)
+                            .show();
+                        if matches!(fix, rfd::MessageDialogResult::Yes) {
+                            form.identities_only = true;
+                            *test_message = Some("IdentitiesOnly=yes enabled, test the connection again".to_string());
+                        } else {
+                            *test_message = Some(format!("{}", DrillError::TooManyAuthFailures(host)));
+                        }
+                    }
+                    TestOutcome::TooManyAuthFailures(host) => {
+                        *test_message = Some(format!("{}", DrillError::TooManyAuthFailures(host)));
+                    }
+                    // Quick fix: offer to let ssh record the host key on first connect
+                    TestOutcome::UnknownHostKey(host) if !form.accept_new_host_keys => {
+                        let fix = rfd::MessageDialog::new()
+                            .set_title("Unknown Host Key")
+                            .set_description(format!(
+                                "The host key of {} is not in your known_hosts.\n\nTrust it on first connect (StrictHostKeyChecking=accept-new)? A key that changes later is still refused.",
+                                host
+                            ))
+                            .set_buttons(rfd::MessageButtons::YesNo)
+                            .show();
+                        if matches!(fix, rfd::MessageDialogResult::Yes) {
+                            form.accept_new_host_keys = true;
+                            *test_message = Some("Host key checking set to accept-new, test the connection again".to_string());
+                        } else {
+                            *test_message = Some(format!("{}", DrillError::UnknownHostKey(host)));
+                        }
+                    }
+                    TestOutcome::UnknownHostKey(host) => {
+                        *test_message = Some(format!("{}", DrillError::UnknownHostKey(host)));
+                    }
+                    TestOutcome::Failed(err) => *test_message = Some(err),
+                }
+                let extra_height = test_message.as_ref().map(|msg| (msg.len() / 60).max(1) as f32 * 20.0 + 40.0).unwrap_or(0.0);
+                window::resize(window_id, Size::new(500.0, 530.0 + extra_height))
+            }
+
+            Message::TunnelFormSubmit(window_id) => {
+                self.handle_tunnel_form_submit(window_id, true)
+            }
+
+            Message::TunnelFormApply(window_id) => {
+                self.handle_tunnel_form_submit(window_id, false)
+            }
+
+            Message::TunnelFormSavedNoticeExpired(window_id) => {
+                if let Some(WindowType::TunnelForm { saved_notice, .. }) = self.windows.get_mut(&window_id) {
+                    *saved_notice = false;
+                }
+                Task::none()
             }
 
             Message::TunnelFormCancel(window_id) => window::close(window_id),
@@ -395,22 +1530,42 @@ impl App {
                     let manager = &self.tunnel_manager;
                     let tunnels = manager.get_tunnels().clone();
                     let _ = manager;
-                    let tunnel_statuses: Vec<(String, crate::tunnels::TunnelStatus)> = manager
-                        .get_tunnels()
-                        .iter()
-                        .map(|t| (t.name.clone(), manager.get_tunnel_status(&t.name)))
-                        .collect();
-                    match systemtray::update_tray_menu(tray_icon, &tunnels, &tunnel_statuses) {
+                    let tunnel_statuses = manager.status_snapshot();
+                    let tunnel_stats = manager.stats_snapshot();
+                    let disconnect_timers = manager.disconnect_timers_snapshot();
+                    let port_overrides = manager.port_overrides_snapshot();
+                    let forward_warnings = manager.forward_warnings_snapshot();
+                    // The menu and its ids are only replaced together, on success
+                    match systemtray::update_tray_menu(tray_icon, &tunnels, &tunnel_statuses, &tunnel_stats, &disconnect_timers, &port_overrides, &forward_warnings, manager.settings()) {
                         Ok(new_ids) => {
                             self.menu_ids = Some(new_ids);
+                            self.tray_update_failures = 0;
                         }
                         Err(e) => {
-                            self.logger.log_print(&format!("Error updating tray menu: {}", e));
+                            self.tray_update_failures += 1;
+                            self.logger.log_print(&format!(
+                                "Error updating tray menu (attempt {}), keeping the previous menu: {}",
+                                self.tray_update_failures, e
+                            ));
+                            if !self.tray_retry_pending {
+                                self.tray_retry_pending = true;
+                                let delay = tray_retry_delay(self.tray_update_failures);
+                                return Task::perform(tokio::time::sleep(delay), |_| Message::RetryTrayMenu);
+                            }
                         }
                     }
                 }
                 Task::none()
             }
+
+            Message::RetryTrayMenu => {
+                self.tray_retry_pending = false;
+                // A rebuild since the failure may already have succeeded
+                if self.tray_update_failures == 0 {
+                    return Task::none();
+                }
+                self.update(Message::UpdateTrayMenu)
+            }
         }
     }
 
@@ -418,22 +1573,127 @@ impl App {
         if let Some(window_type) = self.windows.get(&window_id) {
             match window_type {
                 WindowType::About => {
-                    windows::about::view().map(|msg| match msg {})
+                    windows::about::view(&self.latest_release, self.checking_for_update).map(|msg| match msg {
+                        windows::about::Message::OpenReleasePage(url) => Message::OpenReleasePage(url),
+                    })
+                }
+                WindowType::Dashboard => {
+                    let manager = &self.tunnel_manager;
+                    let tunnels = manager
+                        .get_tunnels()
+                        .iter()
+                        .map(|t| (t, manager.get_tunnel_status(&t.name), manager.local_port_override(&t.name), manager.get_tunnel_stats(&t.name)))
+                        .collect();
+                    windows::dashboard::view(tunnels, self.autostart_enabled, manager.settings().locked).map(|msg| match msg {
+                        windows::dashboard::Message::NewTunnel => Message::OpenCreateTunnel,
+                        windows::dashboard::Message::PasteUri => Message::PasteConnectionUri,
+                        windows::dashboard::Message::ReconnectErrored => Message::ReconnectErroredTunnels,
+                        windows::dashboard::Message::Connect(name) => Message::TunnelConnect(name),
+                        windows::dashboard::Message::ConnectOnPort(name) => Message::OpenConnectOnPort(name),
+                        windows::dashboard::Message::Disconnect(name) => Message::TunnelDisconnect(name),
+                        windows::dashboard::Message::OpenWeb(name) => Message::TunnelOpenWeb(name),
+                        windows::dashboard::Message::Edit(name) => Message::TunnelEdit(name),
+                        windows::dashboard::Message::EditAsNew(name) => Message::TunnelEditAsNew(name),
+                        windows::dashboard::Message::Remove(name) => Message::RequestRemoveTunnel(name),
+                        windows::dashboard::Message::ServiceFile(name) => Message::TunnelGenerateServiceFile(name),
+                        windows::dashboard::Message::EffectiveConfig(name) => Message::TunnelShowEffectiveConfig(name),
+                        windows::dashboard::Message::CopySshConfig(name) => Message::TunnelCopySshConfig(name),
+                        windows::dashboard::Message::ExportBundle => Message::ExportBundle,
+                        windows::dashboard::Message::ImportBundle => Message::ImportBundle,
+                        windows::dashboard::Message::ImportPutty => Message::OpenPuttyImport,
+                        windows::dashboard::Message::BatchEdit => Message::OpenBatchEdit,
+                        windows::dashboard::Message::ViewLogs => Message::OpenLogViewer,
+                        windows::dashboard::Message::EventLog => Message::OpenEventLog,
+                        windows::dashboard::Message::ErrorSummary => Message::OpenErrorSummary,
+                        windows::dashboard::Message::StartAtLoginToggled(_) => Message::ToggleStartAtLogin,
+                        windows::dashboard::Message::CheckForUpdates => Message::CheckForUpdates,
+                        windows::dashboard::Message::About => Message::OpenAbout,
+                        windows::dashboard::Message::Quit => Message::RequestQuit,
+                    })
+                }
+                WindowType::EventLog { state } => {
+                    let (events, tunnel_names) = match &self.event_store {
+                        Some(store) => (store.query(&state.query), store.tunnel_names()),
+                        None => (Vec::new(), Vec::new()),
+                    };
+                    windows::event_log::view(state, events, tunnel_names)
+                        .map(move |msg| Message::EventLogMessage(window_id, msg))
+                }
+                WindowType::BatchEdit { state } => {
+                    let manager = &self.tunnel_manager;
+                    let matching = manager
+                        .get_tunnels()
+                        .iter()
+                        .filter(|t| state.matches(t))
+                        .map(|t| (t, manager.is_tunnel_active(&t.name)))
+                        .collect();
+                    windows::batch_edit::view(state, matching)
+                        .map(move |msg| Message::BatchEditMessage(window_id, msg))
+                }
+                WindowType::LogViewer { lines, export } => {
+                    let log_path = self.logger.path().to_str().unwrap_or_default();
+                    let tunnel_names = self.tunnel_manager.get_tunnels().iter().map(|t| t.name.clone()).collect();
+                    windows::log_viewer::view(log_path, lines, export, tunnel_names).map(move |msg| match msg {
+                        windows::log_viewer::Message::Refresh => Message::LogViewerRefresh(window_id),
+                        msg => Message::LogViewerMessage(window_id, msg),
+                    })
+                }
+                WindowType::PuttyImport { state } => {
+                    windows::putty_import::view(state)
+                        .map(move |msg| Message::PuttyImportMessage(window_id, msg))
+                }
+                WindowType::EffectiveConfig { tunnel_name, config } => {
+                    windows::effective_config::view(tunnel_name, config).map(move |msg| match msg {
+                        windows::effective_config::Message::Copy => Message::EffectiveConfigCopy(window_id),
+                    })
+                }
+                WindowType::ErrorSummary => {
+                    windows::error_summary::view(self.error_stats.as_ref().map(ErrorStats::summary)).map(|msg| match msg {
+                        windows::error_summary::Message::Reset => Message::ErrorSummaryReset,
+                    })
+                }
+                WindowType::ConnectOnPort { tunnel_name, configured_port, local_port, error_message } => {
+                    windows::connect_on_port::view(tunnel_name, configured_port, local_port, error_message)
+                        .map(move |msg| Message::ConnectOnPortMessage(window_id, msg))
+                }
+                WindowType::Confirm { message, alternative, .. } => {
+                    windows::confirm::view(message, alternative.as_ref().map(|(label, _)| label.as_str())).map(move |msg| Message::ConfirmMessage(window_id, msg))
+                }
+                WindowType::UndoToast { tunnel_name } => {
+                    windows::undo_toast::view(tunnel_name).map(move |msg| match msg {
+                        windows::undo_toast::Message::Undo => Message::UndoRemoveTunnel,
+                        windows::undo_toast::Message::Dismiss => Message::UndoToastDismiss(window_id),
+                    })
                 }
                 WindowType::TunnelForm {
-                    mode,
-                    name, local_host, local_port, remote_host, remote_port,
-                    ssh_user, ssh_host, ssh_port, private_key,
-                    error_message, test_message,
+                    mode, form, form_tab, default_identity, templates,
+                    jump_choices, error_message, test_message, test_started, warnings, saved_notice,
+                    restart_fields, ..
                 } => {
+                    // Read on every render, so status updates refresh the banner
+                    let status = match mode {
+                        windows::FormMode::Edit { tunnel_id } => self
+                            .tunnel_manager
+                            .get_tunnels()
+                            .iter()
+                            .find(|t| &t.id == tunnel_id)
+                            .map(|t| self.tunnel_manager.get_tunnel_status(&t.name)),
+                        windows::FormMode::Create | windows::FormMode::CreateFrom { .. } => None,
+                    };
                     windows::create_tunnel::view(
                         mode,
-                        name, local_host, local_port,
-                        remote_host, remote_port,
-                        ssh_user, ssh_host, ssh_port,
-                        private_key,
+                        form,
+                        *form_tab,
+                        default_identity,
+                        templates,
+                        jump_choices,
                         error_message,
                         test_message,
+                        warnings,
+                        *saved_notice,
+                        status,
+                        *test_started,
+                        restart_fields,
                     )
                     .map(move |msg| self.map_tunnel_form_message(window_id, msg))
                 }
@@ -443,11 +1703,54 @@ impl App {
         }
     }
 
+    /// Title of a window, naming what it shows
+    /// Titles are asked for on every update, so the dashboard count follows status changes.
+    pub fn title(&self, window_id: window::Id) -> String {
+        let manager = &self.tunnel_manager;
+        match self.windows.get(&window_id) {
+            Some(WindowType::Dashboard) => {
+                let connected = manager
+                    .get_tunnels()
+                    .iter()
+                    .filter(|t| matches!(manager.get_tunnel_status(&t.name), TunnelStatus::Connected { .. }))
+                    .count();
+                format!("Drill \u{2014} {} connected", connected)
+            }
+            Some(WindowType::TunnelForm { mode: windows::FormMode::Create, .. }) => "New Tunnel".to_string(),
+            Some(WindowType::TunnelForm { mode: windows::FormMode::Edit { tunnel_id }, form, .. }) => {
+                // The saved name, the form may hold an edited one
+                let name = manager
+                    .get_tunnels()
+                    .iter()
+                    .find(|t| &t.id == tunnel_id)
+                    .map_or(form.name.as_str(), |t| t.name.as_str());
+                format!("Edit: {}", name)
+            }
+            Some(WindowType::TunnelForm { mode: windows::FormMode::CreateFrom { source_id }, .. }) => {
+                match manager.get_tunnels().iter().find(|t| &t.id == source_id) {
+                    Some(source) => format!("New from: {}", source.name),
+                    None => "New Tunnel".to_string(),
+                }
+            }
+            Some(WindowType::EffectiveConfig { tunnel_name, .. }) => format!("Config: {}", tunnel_name),
+            Some(WindowType::About) => "About Drill".to_string(),
+            Some(WindowType::LogViewer { .. }) => "Drill Logs".to_string(),
+            Some(WindowType::EventLog { .. }) => "Event Log".to_string(),
+            Some(WindowType::ErrorSummary) => "Error Summary".to_string(),
+            Some(WindowType::BatchEdit { .. }) => "Batch Edit".to_string(),
+            Some(WindowType::PuttyImport { .. }) => "Import PuTTY Sessions".to_string(),
+            Some(WindowType::Confirm { title, .. }) => title.clone(),
+            Some(WindowType::ConnectOnPort { tunnel_name, .. }) => format!("Connect {} on Port", tunnel_name),
+            Some(WindowType::UndoToast { .. }) | None => "Drill".to_string(),
+        }
+    }
+
     pub fn subscription(&self) -> Subscription<Message> {
         // Listen for window close events
         let window_events =
             iced::event::listen_with(|event, _status, id| match event {
                 iced::Event::Window(window_event) => match window_event {
+                    iced::window::Event::CloseRequested => Some(Message::WindowCloseRequested(id)),
                     iced::window::Event::Closed => Some(Message::WindowClosed(id)),
                     _ => None,
                 },
@@ -459,11 +1762,14 @@ impl App {
         let tray_subscription = Subscription::run_with_id(
             std::any::TypeId::of::<TrayEventsPoll>(),
             iced::stream::channel(100, |mut output| async move {
+                let menu_channel = MenuEvent::receiver();
                 loop {
                     // Check for menu events
-                    let menu_channel = MenuEvent::receiver();
                     while let Ok(event) = menu_channel.try_recv() {
-                        let _ = output.send(event).await;
+                        // The app side is gone, stop polling
+                        if output.send(event).await.is_err() {
+                            return;
+                        }
                     }
                     // Small delay to avoid busy-waiting  
                     tokio::time::sleep(tokio::time::Duration::from_millis(16)).await;
@@ -471,40 +1777,634 @@ impl App {
             })
         ).map(Message::TrayMenuEvent);
 
+        // Poll global hotkey events, only when a hotkey is registered
+        struct HotkeyEventsPoll;
+        let hotkey_subscription = if self.toggle_hotkey_id.is_some() {
+            Subscription::run_with_id(
+                std::any::TypeId::of::<HotkeyEventsPoll>(),
+                iced::stream::channel(10, |mut output| async move {
+                    let hotkey_channel = GlobalHotKeyEvent::receiver();
+                    loop {
+                        while let Ok(event) = hotkey_channel.try_recv() {
+                            if output.send(event).await.is_err() {
+                                return;
+                            }
+                        }
+                        tokio::time::sleep(tokio::time::Duration::from_millis(16)).await;
+                    }
+                })
+            ).map(Message::HotkeyEvent)
+        } else {
+            Subscription::none()
+        };
+
         // Tunnel status monitoring subscription
         struct TunnelStatusMonitor;
-        let mut status_receiver = self.status_receiver.resubscribe();
+        // Reads from the receiver made in App::new rather than a resubscribed one,
+        // which would only see updates sent after this first runs
+        let status_receiver = Arc::clone(&self.status_receiver);
         let status_subscription = Subscription::run_with_id(
             std::any::TypeId::of::<TunnelStatusMonitor>(),
             iced::stream::channel(100, move |mut output| async move {
                 loop {
-                    let update_opt = status_receiver.try_recv().ok();
-                    if let Some(update) = update_opt {
-                        let _ = output.send(update).await;
+                    // Forward every queued update, not just one per tick
+                    let (messages, closed) = drain_status_updates(&status_receiver);
+                    for message in messages {
+                        if output.send(message).await.is_err() {
+                            return;
+                        }
+                    }
+                    // The manager is gone, no more updates will arrive
+                    if closed {
+                        return;
+                    }
+                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+                }
+            })
+        );
+
+        // Quit cleanly (stopping the tunnels) on SIGINT/SIGTERM too
+        struct ShutdownSignal;
+        let shutdown_subscription = Subscription::run_with_id(
+            std::any::TypeId::of::<ShutdownSignal>(),
+            iced::stream::channel(1, |mut output| async move {
+                crate::daemon::shutdown_signal().await;
+                let _ = output.send(Message::Quit).await;
+            })
+        );
+
+        // Periodically check the health of running tunnels
+        let monitor_subscription = iced::time::every(monitor_interval(self.tunnel_manager.settings())).map(|_| Message::MonitorTunnels);
+
+        // Keyboard shortcuts for the dashboard, so the app is usable without a pointer
+        let dashboard_open = self.windows.values().any(|wt| matches!(wt, WindowType::Dashboard));
+        let keyboard_subscription = if dashboard_open {
+            iced::keyboard::on_key_press(|key, modifiers| {
+                if !modifiers.command() {
+                    return None;
+                }
+                match key.as_ref() {
+                    iced::keyboard::Key::Character("n") => Some(Message::OpenCreateTunnel),
+                    iced::keyboard::Key::Character("q") => Some(Message::RequestQuit),
+                    _ => None,
+                }
+            })
+        } else {
+            Subscription::none()
+        };
+
+        // Redraw the countdown of running connection tests
+        let testing = self.windows.values().any(|wt| matches!(wt, WindowType::TunnelForm { test_started: Some(_), .. }));
+        let test_progress_subscription = if testing {
+            iced::time::every(Duration::from_millis(250)).map(|_| Message::TunnelFormTestTick)
+        } else {
+            Subscription::none()
+        };
+
+        // Launches of a second instance, handed over by the single-instance lock
+        struct LaunchForwarding;
+        let launch_subscription = match &self.launch_receiver {
+            Some(receiver) => {
+                let mut launch_receiver = receiver.resubscribe();
+                Subscription::run_with_id(
+                    std::any::TypeId::of::<LaunchForwarding>(),
+                    iced::stream::channel(10, move |mut output| async move {
+                        loop {
+                            let args = match launch_receiver.recv().await {
+                                Ok(args) => args,
+                                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                                Err(broadcast::error::RecvError::Closed) => return,
+                            };
+                            if output.send(Message::InstanceLaunched(args)).await.is_err() {
+                                return;
+                            }
+                        }
+                    })
+                )
+            }
+            None => Subscription::none(),
+        };
+
+        Subscription::batch(vec![window_events, tray_subscription, hotkey_subscription, status_subscription, shutdown_subscription, monitor_subscription, keyboard_subscription, test_progress_subscription, launch_subscription])
+    }
+
+    // Helper methods for iced::daemon function references
+    pub fn title_fn(app: &App, id: window::Id) -> String {
+        app.title(id)
+    }
+
+    pub fn update_fn(app: &mut App, message: Message) -> Task<Message> {
+        app.update(message)
+    }
+
+    pub fn view_fn<'a>(app: &'a App, id: window::Id) -> Element<'a, Message> {
+        app.view(id)
+    }
+
+    pub fn subscription_fn(app: &App) -> Subscription<Message> {
+        app.subscription()
+    }
+
+    /// Applies filters and paging in an event log window, or exports the filtered events
+    fn handle_event_log_message(&mut self, window_id: window::Id, message: windows::event_log::Message) {
+        let Some(WindowType::EventLog { state }) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+        let Some(store) = &self.event_store else {
+            return;
+        };
+        let events = store.query(&state.query);
+
+        if !matches!(message, windows::event_log::Message::ExportCsv) {
+            state.update(message, windows::event_log::page_count(events.len()));
+            return;
+        }
+
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Export Event Log")
+            .set_file_name("drill-events.csv")
+            .add_filter("CSV", &["csv"])
+            .save_file()
+        else {
+            return;
+        };
+        match events::export_csv(&path, &events) {
+            Ok(_) => self.logger.log_print(&format!("Exported {} event(s) to {}", events.len(), path.display())),
+            Err(e) => self.logger.log_print(&format!("Error exporting event log: {}", e)),
+        }
+    }
+
+    /// Picks the tunnel and period of a log export, or writes the tunnel's lines to a file
+    fn handle_log_viewer_message(&mut self, window_id: window::Id, message: windows::log_viewer::Message) {
+        let Some(WindowType::LogViewer { export, .. }) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+        match message {
+            windows::log_viewer::Message::ExportTunnelSelected(tunnel_name) => export.tunnel = Some(tunnel_name),
+            windows::log_viewer::Message::ExportPeriodSelected(period) => export.period = period,
+            windows::log_viewer::Message::Export => {
+                let Some(tunnel_name) = export.tunnel.clone() else {
+                    return;
+                };
+                let since = export.period.since();
+                let Some(logs_dir) = self.logger.path().parent().map(|dir| dir.to_path_buf()) else {
+                    return;
+                };
+                let lines = match crate::logs::tunnel_log_lines(&logs_dir, &tunnel_name, since) {
+                    Ok(lines) => lines,
+                    Err(e) => {
+                        self.logger.log_print(&format!("Error reading logs of '{}': {}", tunnel_name, e));
+                        return;
+                    }
+                };
+                let file_name: String = tunnel_name
+                    .chars()
+                    .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+                    .collect();
+                let Some(path) = rfd::FileDialog::new()
+                    .set_title("Export Tunnel Log")
+                    .set_file_name(format!("drill-{}.log", file_name))
+                    .add_filter("Log", &["log", "txt"])
+                    .save_file()
+                else {
+                    return;
+                };
+                let mut content = lines.join("\n");
+                content.push('\n');
+                match std::fs::write(&path, content) {
+                    Ok(_) => self.logger.log_print(&format!("Exported {} log line(s) of '{}' to {}", lines.len(), tunnel_name, path.display())),
+                    Err(e) => self.logger.log_print(&format!("Error exporting log of '{}': {}", tunnel_name, e)),
+                }
+            }
+            windows::log_viewer::Message::Refresh => {}
+        }
+    }
+
+    /// Updates a batch edit window, or applies its find/replace to the tunnels
+    /// Running tunnels that change are restarted after confirmation
+    fn handle_batch_edit_message(&mut self, window_id: window::Id, message: windows::batch_edit::Message) -> Task<Message> {
+        let Some(WindowType::BatchEdit { state }) = self.windows.get_mut(&window_id) else {
+            return Task::none();
+        };
+        match message {
+            windows::batch_edit::Message::Cancel => return window::close(window_id),
+            windows::batch_edit::Message::Apply => {}
+            message => {
+                state.update(message);
+                return Task::none();
+            }
+        }
+
+        let replacement = match state.validated_replacement() {
+            Ok(replacement) => replacement,
+            Err(e) => {
+                state.error_message = Some(e);
+                return Task::none();
+            }
+        };
+        let state = state.clone();
+        let field = state.field;
+        let updated = self.tunnel_manager.bulk_update(
+            |t| state.matches(t),
+            |t| field.set(t, replacement.clone()),
+        );
+        // Running tunnels aren't restarted onto values that didn't reach the file
+        if let Err(e) = self.save_retry.save(&self.tunnels_file, self.tunnel_manager.get_tunnels(), self.tunnel_manager.settings()) {
+            self.logger.log_print(&format!("Error saving tunnels: {}", e));
+            if let Some(WindowType::BatchEdit { state }) = self.windows.get_mut(&window_id) {
+                state.error_message = Some(format!("Error saving tunnels: {}", e));
+            }
+            return self.update(Message::UpdateTrayMenu);
+        }
+        self.logger.log_print(&format!(
+            "Batch edit: set {} to '{}' on {} tunnel(s)",
+            field, replacement, updated.len()
+        ));
+
+        let running: Vec<String> = updated
+            .into_iter()
+            .filter(|name| self.tunnel_manager.is_tunnel_active(name))
+            .collect();
+        let mut tasks = vec![self.update(Message::UpdateTrayMenu), window::close(window_id)];
+        if !running.is_empty() {
+            tasks.push(self.open_confirm(
+                "Restart Tunnels",
+                format!(
+                    "{} running tunnel(s) use the old value: {}\n\nRestart them now?",
+                    running.len(),
+                    running.join(", ")
+                ),
+                ConfirmAction::RestartTunnels(running),
+            ));
+        }
+        Task::batch(tasks)
+    }
+
+    /// Open the create form, or focus it when it's already open
+    /// A pre-filled `form` replaces what an already open create form holds.
+    fn open_create_form(&mut self, form: Option<windows::TunnelFormData>) -> Task<Message> {
+        if self.refuses_changes("Creating a tunnel") {
+            return Task::none();
+        }
+        if let Some((window_id, wt)) = self.windows.iter_mut().find(|(_, wt)| {
+            matches!(wt, WindowType::TunnelForm { mode: windows::FormMode::Create, .. })
+        }) {
+            if let (Some(prefilled), WindowType::TunnelForm { form, error_message, saved, .. }) = (form, wt) {
+                *saved = prefilled.clone();
+                *form = prefilled;
+                *error_message = None;
+            }
+            return window::gain_focus(*window_id);
+        }
+        let template_names = self.template_names();
+        let jump_choices = windows::JumpChoice::list(self.tunnel_manager.get_tunnels(), None);
+        let form = form.unwrap_or_default();
+        // Leave room for the template picker
+        let height = if template_names.is_empty() { 520.0 } else { 560.0 };
+        // No custom icon set (icon_alpha.png usage removed)
+        let (id, open) = window::open(window::Settings {
+            size: Size::new(500.0, height),
+            resizable: false,
+            // Closing asks first when there are unsaved changes (see WindowCloseRequested)
+            exit_on_close_request: false,
+            ..window::Settings::default()
+        });
+        open.then(move |_| {
+            Task::done(Message::WindowOpened(
+                id,
+                WindowType::new_tunnel_form_create(form.clone(), template_names.clone(), jump_choices.clone()),
+            ))
+        })
+    }
+
+    /// Get the names of the saved tunnel templates
+    fn template_names(&mut self) -> Vec<String> {
+        let result = config::get_templates_file_path().and_then(|path| templates::load_templates(&path));
+        match result {
+            Ok(templates) => templates.into_iter().map(|t| t.name).collect(),
+            Err(e) => {
+                self.logger.log_print(&format!("Error loading templates: {}", e));
+                Vec::new()
+            }
+        }
+    }
+
+    /// Pre-fill a tunnel form from a saved template
+    fn apply_template_to_form(&mut self, window_id: window::Id, template_name: String) {
+        let template = config::get_templates_file_path().and_then(|path| templates::get_template(&path, &template_name));
+        let Some(WindowType::TunnelForm { form, templates: template_state, error_message, original, restart_fields, .. }) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+        match template {
+            Ok(template) => {
+                form.apply_template(&template);
+                template_state.selected = Some(template_name);
+                *error_message = None;
+                if let Some(original) = original {
+                    *restart_fields = windows::connection_changes(original, form);
+                }
+            }
+            Err(e) => *error_message = Some(format!("Error loading template: {}", e)),
+        }
+    }
+
+    /// Save the settings in a tunnel form as a named template
+    fn save_form_as_template(&mut self, window_id: window::Id) {
+        let Some(WindowType::TunnelForm { form, templates: template_state, error_message, test_message, .. }) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+        let template_name = template_state.new_name.trim().to_string();
+        if template_name.is_empty() {
+            return;
+        }
+        let result = config::get_templates_file_path()
+            .and_then(|path| templates::save_template(&path, form.to_template(&template_name)));
+        match result {
+            Ok(_) => {
+                self.logger.log_print(&format!("Saved tunnel template '{}'", template_name));
+                if !template_state.names.contains(&template_name) {
+                    template_state.names.push(template_name.clone());
+                }
+                template_state.new_name.clear();
+                *error_message = None;
+                *test_message = Some(format!("\u{2713} Template '{}' saved", template_name));
+            }
+            Err(e) => *error_message = Some(format!("Error saving template: {}", e)),
+        }
+    }
+
+    /// Delete a saved template from the tunnel form's picker
+    fn delete_template_from_form(&mut self, window_id: window::Id, template_name: String) {
+        let Some(WindowType::TunnelForm { templates: template_state, error_message, .. }) = self.windows.get_mut(&window_id) else {
+            return;
+        };
+        let result = config::get_templates_file_path().and_then(|path| templates::delete_template(&path, &template_name));
+        match result {
+            Ok(_) => {
+                self.logger.log_print(&format!("Deleted tunnel template '{}'", template_name));
+                template_state.names.retain(|name| name != &template_name);
+                template_state.selected = None;
+            }
+            Err(e) => *error_message = Some(format!("Error deleting template: {}", e)),
+        }
+    }
+
+    /// Read the tail of the current log file for the log viewer
+    fn read_log_lines(&mut self) -> Vec<String> {
+        const LOG_VIEWER_MAX_LINES: usize = 1000;
+        let log_path = self.logger.path().to_path_buf();
+        match crate::logs::read_tail(&log_path, LOG_VIEWER_MAX_LINES) {
+            Ok(lines) => lines,
+            Err(e) => {
+                self.logger.log_print(&format!("Error reading log file: {}", e));
+                Vec::new()
+            }
+        }
+    }
+
+    /// Open the undo toast for a removed tunnel, replacing any previous one
+    /// The toast closes itself after `UNDO_TOAST_DURATION`
+    fn open_undo_toast(&mut self, tunnel_name: String) -> Task<Message> {
+        let close_previous = self.close_undo_toasts();
+        let (id, open) = window::open(window::Settings {
+            size: Size::new(360.0, 110.0),
+            resizable: false,
+            level: window::Level::AlwaysOnTop,
+            ..window::Settings::default()
+        });
+        let open = open.then(move |_| {
+            Task::done(Message::WindowOpened(
+                id,
+                WindowType::UndoToast { tunnel_name: tunnel_name.clone() },
+            ))
+        });
+        let expire = Task::perform(tokio::time::sleep(UNDO_TOAST_DURATION), move |_| {
+            Message::UndoToastExpired(id)
+        });
+        Task::batch([close_previous, open, expire])
+    }
+
+    /// Ask a Yes/No question in a small window, running `on_confirm` on Yes
+    /// An identical question already open is brought to the front instead.
+    fn open_confirm(&mut self, title: &str, message: String, on_confirm: ConfirmAction) -> Task<Message> {
+        self.open_confirm_with(title, message, on_confirm, None)
+    }
+
+    /// Like `open_confirm`, with a third answer labelled and handled by `alternative`
+    fn open_confirm_with(
+        &mut self,
+        title: &str,
+        message: String,
+        on_confirm: ConfirmAction,
+        alternative: Option<(String, ConfirmAction)>,
+    ) -> Task<Message> {
+        let existing = self.windows.iter().find(|(_, wt)| {
+            matches!(wt, WindowType::Confirm { on_confirm: action, .. } if *action == on_confirm)
+        });
+        if let Some((id, _)) = existing {
+            return window::gain_focus(*id);
+        }
+        // Room for each line of a longer question
+        let height = 100.0 + 20.0 * message.lines().count().max(1) as f32;
+        let (id, open) = window::open(window::Settings {
+            size: Size::new(380.0, height),
+            resizable: false,
+            level: window::Level::AlwaysOnTop,
+            ..window::Settings::default()
+        });
+        let title = title.to_string();
+        open.then(move |_| {
+            Task::done(Message::WindowOpened(
+                id,
+                WindowType::Confirm {
+                    title: title.clone(),
+                    message: message.clone(),
+                    on_confirm: on_confirm.clone(),
+                    alternative: alternative.clone(),
+                },
+            ))
+        })
+    }
+
+    /// Close every open undo toast window
+    fn close_undo_toasts(&self) -> Task<Message> {
+        Task::batch(
+            self.windows
+                .iter()
+                .filter(|(_, wt)| matches!(wt, WindowType::UndoToast { .. }))
+                .map(|(id, _)| window::close(*id)),
+        )
+    }
+
+    /// Start a tunnel, on `local_port` instead of its configured port when given
+    /// A failure to start is logged and notified; later failures arrive as status updates.
+    fn connect_tunnel(&mut self, tunnel_name: &str, local_port: Option<&str>) -> Task<Message> {
+        // Connecting by hand asks for a fresh result, so a repeated error is reported again
+        self.notified_errors.remove(tunnel_name);
+        if let Some(tunnel) = self.tunnel_manager.get_tunnels().iter().find(|t| t.name == tunnel_name).cloned() {
+            // ssh would only fail to bind a port an external forward already serves
+            if local_port.is_none()
+                && self.tunnel_manager.settings().adopt_external_tunnels
+                && self.tunnel_manager.offers_adoption(&tunnel)
+            {
+                // No leaves it disconnected: ssh couldn't listen on the port anyway
+                return self.open_confirm(
+                    "Adopt External Tunnel",
+                    format!(
+                        "Local port {} already accepts connections, probably from an ssh -L started outside Drill.\n\nMark '{}' as connected and watch that port instead of connecting?",
+                        tunnel.local_port.trim(),
+                        tunnel_name
+                    ),
+                    ConfirmAction::AdoptExternal(tunnel_name.to_string()),
+                );
+            }
+            match self.tunnel_manager.start_tunnel(&tunnel, local_port) {
+                Ok(_) => {
+                    // Notification will be sent only after true connection (StatusUpdate::Connected)
+                }
+                Err(e) => {
+                    self.logger.log_print(&format!(
+                        "Error starting tunnel '{}': {}",
+                        tunnel_name, e
+                    ));
+                    // The failure also arrives as a status update, which then isn't notified twice
+                    if self.is_new_error(tunnel_name, &e.to_string()) && tunnel.notifications {
+                        let notify = self.queue_notification(notifications::BatchedKind::Failed, tunnel_name, &e.to_string());
+                        return Task::batch([notify, self.update(Message::UpdateTrayMenu)]);
                     }
-                    tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
                 }
-            })
-        ).map(Message::TunnelStatusUpdate);
+            }
+        }
+        self.update(Message::UpdateTrayMenu)
+    }
 
-        Subscription::batch(vec![window_events, tray_subscription, status_subscription])
+    /// Offer to trust an unknown host key and reconnect ("Trust host and retry")
+    /// Yes saves `accept_new_host_keys` on the tunnel, Trust Once trusts the key
+    /// for this connect only; ssh records the key in known_hosts either way.
+    /// Managed tunnels can't be changed, so only the one-off retry is offered.
+    /// Returns None when the offer doesn't apply (the tunnel already accepts new keys).
+    fn offer_trust_host(&mut self, tunnel_name: &str) -> Option<Task<Message>> {
+        let tunnel = self.tunnel_manager.get_tunnels().iter().find(|t| t.name == tunnel_name)?;
+        if tunnel.accept_new_host_keys {
+            return None;
+        }
+        let (question, on_confirm, alternative) = trust_host_question(tunnel_name, self.tunnel_manager.settings().locked);
+        let message = format!(
+            "'{}' failed: the host key of {} is not in your known_hosts.\n\n{}",
+            tunnel_name, tunnel.ssh_host, question
+        );
+        Some(self.open_confirm_with("Unknown Host Key", message, on_confirm, alternative))
     }
 
-    // Helper methods for iced::daemon function references
-    pub fn title_fn(_app: &App, _id: window::Id) -> String {
-        String::from("Drill")
+    /// Reconnect a tunnel accepting the server's unknown host key, as answered to `offer_trust_host`
+    fn trust_host_and_retry(&mut self, tunnel_name: &str, remember: bool) {
+        let Some(mut tunnel) = self.tunnel_manager.get_tunnels().iter().find(|t| t.name == tunnel_name).cloned() else {
+            return;
+        };
+        tunnel.accept_new_host_keys = true;
+        if remember {
+            let tunnel_id = tunnel.id.clone();
+            if let Err(e) = self.tunnel_manager.update_tunnel(&tunnel_id, tunnel.clone()) {
+                self.logger.log_print(&format!("Error updating tunnel '{}': {}", tunnel_name, e));
+                return;
+            }
+            if let Err(e) = self.save_retry.save(&self.tunnels_file, self.tunnel_manager.get_tunnels(), self.tunnel_manager.settings()) {
+                self.logger.log_print(&format!("Error saving tunnels: {}", e));
+            }
+            self.logger.log_print(&format!("Tunnel '{}' now trusts new host keys, reconnecting", tunnel_name));
+        } else {
+            self.logger.log_print(&format!("Trusting the host key of '{}' once, reconnecting", tunnel_name));
+        }
+
+        self.notified_errors.remove(tunnel_name);
+        if let Err(e) = self.tunnel_manager.start_tunnel(&tunnel, None) {
+            self.logger.log_print(&format!("Error starting tunnel '{}': {}", tunnel_name, e));
+        }
     }
 
-    pub fn update_fn(app: &mut App, message: Message) -> Task<Message> {
-        app.update(message)
+    /// Whether this error differs from the last one notified for the tunnel, remembering it
+    /// Seeing the same error again (a failing reconnect, a re-check) isn't a new error.
+    fn is_new_error(&mut self, tunnel_name: &str, error: &str) -> bool {
+        if !remember_error(&mut self.notified_errors, tunnel_name, error) {
+            self.logger.log_print(&format!("Tunnel '{}' error unchanged, not notifying again: {}", tunnel_name, error));
+            return false;
+        }
+        true
     }
 
-    pub fn view_fn<'a>(app: &'a App, id: window::Id) -> Element<'a, Message> {
-        app.view(id)
+    /// Whether managed mode (`locked`) refuses this change to the tunnels, logging it if so
+    /// The tray and dashboard already hide these actions; this covers shortcuts and open windows.
+    fn refuses_changes(&mut self, action: &str) -> bool {
+        if !self.tunnel_manager.settings().locked {
+            return false;
+        }
+        self.logger.log_print(&format!("{} is disabled, the tunnels are managed", action));
+        true
     }
 
-    pub fn subscription_fn(app: &App) -> Subscription<Message> {
-        app.subscription()
+    /// Whether the tunnel was generated from an inventory template, logging it if so
+    /// Its settings come from the template, so it isn't edited or removed on its own.
+    fn is_generated(&mut self, tunnel_name: &str) -> bool {
+        let template = self
+            .tunnel_manager
+            .get_tunnels()
+            .iter()
+            .find(|t| t.name == tunnel_name)
+            .and_then(|t| t.generated_from.as_ref())
+            .and_then(|template_id| self.tunnel_manager.get_tunnels().iter().find(|t| &t.id == template_id))
+            .map(|t| t.name.clone());
+        let Some(template) = template else {
+            return false;
+        };
+        self.logger.log_print(&format!(
+            "Tunnel '{}' is generated from template '{}', change the template or its inventory instead",
+            tunnel_name, template
+        ));
+        true
+    }
+
+    /// Queue a tunnel notification, scheduling the batch flush when it opens a new batch
+    fn queue_notification(&mut self, kind: notifications::BatchedKind, tunnel_name: &str, detail: &str) -> Task<Message> {
+        if self.notification_batch.push(kind, tunnel_name, detail) {
+            return Task::perform(tokio::time::sleep(notifications::BATCH_WINDOW), |_| Message::FlushNotifications);
+        }
+        Task::none()
+    }
+
+    /// Validates the port entered in a "Connect on Port" window and connects on it
+    fn handle_connect_on_port_message(&mut self, window_id: window::Id, message: windows::connect_on_port::Message) -> Task<Message> {
+        let Some(WindowType::ConnectOnPort { tunnel_name, local_port, error_message, .. }) = self.windows.get_mut(&window_id) else {
+            return Task::none();
+        };
+        match message {
+            windows::connect_on_port::Message::PortChanged(port) => {
+                *local_port = port;
+                *error_message = None;
+                Task::none()
+            }
+            windows::connect_on_port::Message::Cancel => window::close(window_id),
+            windows::connect_on_port::Message::Connect => {
+                let port = local_port.trim().to_string();
+                if !matches!(port.parse::<u16>(), Ok(p) if p > 0) {
+                    *error_message = Some("Enter a port between 1 and 65535".to_string());
+                    return Task::none();
+                }
+                let tunnel_name = tunnel_name.clone();
+                let owner = self.tunnel_manager.get_tunnels().iter()
+                    .find(|t| t.name == tunnel_name)
+                    .and_then(|t| self.tunnel_manager.local_port_owner(&Tunnel { local_port: port.clone(), ..t.clone() }));
+                if let Some(owner) = owner {
+                    if let Some(WindowType::ConnectOnPort { error_message, .. }) = self.windows.get_mut(&window_id) {
+                        *error_message = Some(format!("Port {} is used by running tunnel '{}'", port, owner));
+                    }
+                    return Task::none();
+                }
+                Task::batch([
+                    window::close(window_id),
+                    self.update(Message::TunnelConnectOnPort(tunnel_name, port)),
+                ])
+            }
+        }
     }
 
     /// Handles tray menu events and dispatches appropriate messages
@@ -515,23 +2415,88 @@ impl App {
             return Task::none();
         };
 
+        // The menu is out of date when its last rebuild failed: items of tunnels
+        // removed or renamed since are dropped instead of routed, and the rebuild retried
+        let stale_tunnel = menu_ids
+            .stale_tunnel(&event.id, self.tunnel_manager.get_tunnels())
+            .map(str::to_string);
+        if let Some(tunnel_name) = stale_tunnel {
+            self.logger.log_print(&format!("Ignoring tray action for tunnel '{}', which no longer exists", tunnel_name));
+            return self.update(Message::UpdateTrayMenu);
+        }
+
         // Check static menu items
         if event.id == menu_ids.create {
             return self.update(Message::OpenCreateTunnel);
         }
+        if event.id == menu_ids.create_from_uri {
+            return self.update(Message::PasteConnectionUri);
+        }
+        if event.id == menu_ids.open_dashboard {
+            return self.update(Message::OpenDashboard);
+        }
+        if event.id == menu_ids.reconnect_errored {
+            return self.update(Message::ReconnectErroredTunnels);
+        }
+        if event.id == menu_ids.export_bundle {
+            return self.update(Message::ExportBundle);
+        }
+        if event.id == menu_ids.import_bundle {
+            return self.update(Message::ImportBundle);
+        }
+        if menu_ids.import_putty.as_ref() == Some(&event.id) {
+            return self.update(Message::OpenPuttyImport);
+        }
+        if event.id == menu_ids.batch_edit {
+            return self.update(Message::OpenBatchEdit);
+        }
+        if event.id == menu_ids.view_logs {
+            return self.update(Message::OpenLogViewer);
+        }
+        if event.id == menu_ids.copy_log_path {
+            return self.update(Message::CopyLogPath);
+        }
+        if event.id == menu_ids.open_current_log {
+            return self.update(Message::OpenCurrentLog);
+        }
+        if event.id == menu_ids.event_log {
+            return self.update(Message::OpenEventLog);
+        }
+        if event.id == menu_ids.error_summary {
+            return self.update(Message::OpenErrorSummary);
+        }
+        if event.id == menu_ids.start_at_login {
+            return self.update(Message::ToggleStartAtLogin);
+        }
+        if event.id == menu_ids.check_for_updates {
+            return self.update(Message::CheckForUpdates);
+        }
         if event.id == menu_ids.about {
             return self.update(Message::OpenAbout);
         }
         if event.id == menu_ids.quit {
-            return self.update(Message::Quit);
+            return self.update(Message::RequestQuit);
         }
 
         // Check tunnel-specific menu items
+        for (tunnel_name, menu_id) in menu_ids.tunnel_favorite_toggle.iter().chain(&menu_ids.tunnel_recent_toggle) {
+            if event.id == *menu_id {
+                if self.tunnel_manager.is_tunnel_active(tunnel_name) {
+                    return self.update(Message::TunnelDisconnect(tunnel_name.clone()));
+                }
+                return self.update(Message::TunnelConnect(tunnel_name.clone()));
+            }
+        }
         for (tunnel_name, menu_id) in &menu_ids.tunnel_connect {
             if event.id == *menu_id {
                 return self.update(Message::TunnelConnect(tunnel_name.clone()));
             }
         }
+        for (tunnel_name, menu_id) in &menu_ids.tunnel_connect_on_port {
+            if event.id == *menu_id {
+                return self.update(Message::OpenConnectOnPort(tunnel_name.clone()));
+            }
+        }
         for (tunnel_name, menu_id) in &menu_ids.tunnel_disconnect {
             if event.id == *menu_id {
                 return self.update(Message::TunnelDisconnect(tunnel_name.clone()));
@@ -542,33 +2507,305 @@ impl App {
                 return self.update(Message::TunnelOpenWeb(tunnel_name.clone()));
             }
         }
+        for (tunnel_name, menu_id) in &menu_ids.tunnel_extend_timer {
+            if event.id == *menu_id {
+                return self.update(Message::TunnelExtendTimer(tunnel_name.clone()));
+            }
+        }
         for (tunnel_name, menu_id) in &menu_ids.tunnel_edit {
             if event.id == *menu_id {
                 return self.update(Message::TunnelEdit(tunnel_name.clone()));
             }
         }
+        for (tunnel_name, menu_id) in &menu_ids.tunnel_edit_as_new {
+            if event.id == *menu_id {
+                return self.update(Message::TunnelEditAsNew(tunnel_name.clone()));
+            }
+        }
         for (tunnel_name, menu_id) in &menu_ids.tunnel_remove {
             if event.id == *menu_id {
-                return self.update(Message::TunnelRemove(tunnel_name.clone()));
+                return self.update(Message::RequestRemoveTunnel(tunnel_name.clone()));
+            }
+        }
+        for (tunnel_name, menu_id) in &menu_ids.tunnel_service_file {
+            if event.id == *menu_id {
+                return self.update(Message::TunnelGenerateServiceFile(tunnel_name.clone()));
+            }
+        }
+        for (tunnel_name, menu_id) in &menu_ids.tunnel_effective_config {
+            if event.id == *menu_id {
+                return self.update(Message::TunnelShowEffectiveConfig(tunnel_name.clone()));
+            }
+        }
+        for (tunnel_name, menu_id) in &menu_ids.tunnel_copy_ssh_config {
+            if event.id == *menu_id {
+                return self.update(Message::TunnelCopySshConfig(tunnel_name.clone()));
+            }
+        }
+        for (tunnel_name, menu_id) in &menu_ids.tunnel_test {
+            if event.id == *menu_id {
+                return self.update(Message::TunnelTest(tunnel_name.clone()));
             }
         }
 
         Task::none()
     }
 
+    /// Generates a systemd unit / launchd plist for a tunnel
+    /// The file is copied to the clipboard and, if the user picks a location, saved there
+    fn handle_generate_service_file(&mut self, tunnel_name: &str) -> Task<Message> {
+        let manager = &self.tunnel_manager;
+        let Some(tunnel) = manager.get_tunnels().iter().find(|t| t.name == tunnel_name) else {
+            return Task::none();
+        };
+        let service_file = match crate::service::generate_service_file(tunnel, manager.get_tunnels(), manager.settings()) {
+            Ok(service_file) => service_file,
+            Err(e) => {
+                self.logger.log_print(&format!("Error generating service file for '{}': {}", tunnel_name, e));
+                rfd::MessageDialog::new()
+                    .set_title("Generate Service File")
+                    .set_description(e.to_string())
+                    .set_level(rfd::MessageLevel::Error)
+                    .show();
+                return Task::none();
+            }
+        };
+
+        if let Some(path) = rfd::FileDialog::new()
+            .set_title("Save Service File (also copied to the clipboard)")
+            .set_file_name(&service_file.file_name)
+            .save_file()
+        {
+            match std::fs::write(&path, &service_file.content) {
+                Ok(_) => self.logger.log_print(&format!("Saved service file for '{}' to {}", tunnel_name, path.display())),
+                Err(e) => self.logger.log_print(&format!("Error saving service file: {}", e)),
+            }
+        }
+        iced::clipboard::write(service_file.content)
+    }
+
+    /// Copies a tunnel as an ssh config Host block, optionally appending it to ~/.ssh/config
+    fn handle_copy_ssh_config(&mut self, tunnel_name: &str) -> Task<Message> {
+        let manager = &self.tunnel_manager;
+        let Some(tunnel) = manager.get_tunnels().iter().find(|t| t.name == tunnel_name) else {
+            return Task::none();
+        };
+        let block = match tunnel_to_ssh_config_block(tunnel, manager.get_tunnels()) {
+            Ok(block) => block,
+            Err(e) => {
+                self.logger.log_print(&format!("Error exporting '{}' as ssh config: {}", tunnel_name, e));
+                rfd::MessageDialog::new()
+                    .set_title("Copy as SSH Config")
+                    .set_description(e.to_string())
+                    .set_level(rfd::MessageLevel::Error)
+                    .show();
+                return Task::none();
+            }
+        };
+
+        let append = rfd::MessageDialog::new()
+            .set_title("Copy as SSH Config")
+            .set_description(format!(
+                "The ssh config block for '{}' was copied to the clipboard:\n\n{}\nAlso append it to ~/.ssh/config?",
+                tunnel_name, block
+            ))
+            .set_buttons(rfd::MessageButtons::YesNo)
+            .show();
+        if matches!(append, rfd::MessageDialogResult::Yes) {
+            match append_to_ssh_config(&block) {
+                Ok(path) => self.logger.log_print(&format!("Appended '{}' to {}", tunnel_name, path.display())),
+                Err(e) => {
+                    self.logger.log_print(&format!("Error appending to ssh config: {}", e));
+                    rfd::MessageDialog::new()
+                        .set_title("Copy as SSH Config")
+                        .set_description(format!("Could not append to ~/.ssh/config: {}", e))
+                        .set_level(rfd::MessageLevel::Error)
+                        .show();
+                }
+            }
+        }
+        iced::clipboard::write(block)
+    }
+
+    /// Exports all tunnels to a bundle file chosen by the user
+    fn handle_export_bundle(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Export Drill Bundle")
+            .set_file_name("drill-bundle.yaml")
+            .save_file()
+        else {
+            return;
+        };
+
+        let portable = rfd::MessageDialog::new()
+            .set_level(rfd::MessageLevel::Info)
+            .set_title("Export Drill Bundle")
+            .set_description("Store private key paths relative to your home directory (~/...) so they resolve on the new machine?")
+            .set_buttons(rfd::MessageButtons::YesNo)
+            .show();
+        let portable = matches!(portable, rfd::MessageDialogResult::Yes);
+
+        let bundle = Bundle::new(self.tunnel_manager.get_tunnels(), portable);
+        match bundle.save(&path) {
+            Ok(_) => {
+                self.logger.log_print(&format!(
+                    "Exported {} tunnel(s) to bundle: {}",
+                    bundle.manifest.tunnel_count,
+                    path.display()
+                ));
+                notifications::notify_bundle_exported(bundle.manifest.tunnel_count);
+            }
+            Err(e) => {
+                self.logger.log_print(&format!("Error exporting bundle: {}", e));
+            }
+        }
+    }
+
+    /// Imports tunnels from a bundle file, offering to remap missing key paths
+    fn handle_import_bundle(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .set_title("Import Drill Bundle")
+            .add_filter("Drill Bundle", &["yaml", "yml"])
+            .pick_file()
+        else {
+            return;
+        };
+
+        let mut bundle = match Bundle::load(&path) {
+            Ok(bundle) => bundle,
+            Err(e) => {
+                self.logger.log_print(&format!("Error importing bundle: {}", e));
+                return;
+            }
+        };
+        self.logger.log_print(&format!(
+            "Importing bundle exported from {} on {}",
+            bundle.manifest.platform, bundle.manifest.created_at
+        ));
+
+        // Key paths are machine specific: offer to point them at a local directory
+        let missing_keys: Vec<String> = bundle.missing_keys().iter().map(|t| t.private_key.clone()).collect();
+        if !missing_keys.is_empty() {
+            let remap = rfd::MessageDialog::new()
+                .set_level(rfd::MessageLevel::Warning)
+                .set_title("Import Drill Bundle")
+                .set_description(format!(
+                    "{} tunnel(s) reference private keys that don't exist on this machine (e.g. {}).\n\nChoose a local key directory to remap them?",
+                    missing_keys.len(),
+                    missing_keys[0]
+                ))
+                .set_buttons(rfd::MessageButtons::YesNo)
+                .show();
+            if matches!(remap, rfd::MessageDialogResult::Yes) {
+                let mut dialog = rfd::FileDialog::new().set_title("Select Key Directory");
+                if let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) {
+                    dialog = dialog.set_directory(ssh_dir);
+                }
+                if let Some(target_dir) = dialog.pick_folder() {
+                    let remapped = bundle.remap_missing_keys(&target_dir);
+                    self.logger.log_print(&format!(
+                        "Remapped {} key path(s) to {}",
+                        remapped,
+                        target_dir.display()
+                    ));
+                }
+            }
+        }
+
+        // Skip tunnels that already exist (same id or name, or the same connection)
+        let mut imported = 0;
+        let mut skipped = 0;
+        for tunnel in bundle.tunnels {
+            let existing = self.tunnel_manager.get_tunnels();
+            if let Some(original) = tunnel.duplicate_of(existing) {
+                self.logger.log_print(&format!(
+                    "Skipping '{}' from the bundle, it duplicates tunnel '{}'",
+                    tunnel.name, original.name
+                ));
+                skipped += 1;
+            } else if existing.iter().any(|t| t.id == tunnel.id || t.name == tunnel.name) {
+                skipped += 1;
+            } else {
+                self.tunnel_manager.add_tunnel(tunnel);
+                imported += 1;
+            }
+        }
+
+        if let Err(e) = self.save_retry.save(&self.tunnels_file, self.tunnel_manager.get_tunnels(), self.tunnel_manager.settings()) {
+            self.logger.log_print(&format!("Error saving tunnels: {}", e));
+            return;
+        }
+        self.logger.log_print(&format!(
+            "Imported {} tunnel(s) from bundle ({} skipped)",
+            imported, skipped
+        ));
+        notifications::notify_bundle_imported(imported, skipped);
+    }
+
+    /// Updates the PuTTY import window, or imports the selected tunnels
+    /// Tunnels whose name is already taken are skipped, as with bundles.
+    fn handle_putty_import_message(&mut self, window_id: window::Id, message: windows::putty_import::Message) -> Task<Message> {
+        let Some(WindowType::PuttyImport { state }) = self.windows.get_mut(&window_id) else {
+            return Task::none();
+        };
+        match message {
+            windows::putty_import::Message::Cancel => return window::close(window_id),
+            windows::putty_import::Message::Import => {}
+            message => {
+                state.update(message);
+                return Task::none();
+            }
+        }
+
+        let puttygen = if state.convert_keys { putty::puttygen_program() } else { None };
+        let mut imported = 0;
+        let mut skipped = 0;
+        for mut tunnel in state.selected_tunnels() {
+            if self.tunnel_manager.get_tunnels().iter().any(|t| t.name == tunnel.name) {
+                skipped += 1;
+                continue;
+            }
+            if let (Some(puttygen), true) = (&puttygen, putty::is_ppk(&tunnel.private_key)) {
+                match putty::convert_ppk(puttygen, &tunnel.private_key) {
+                    Ok(converted) => tunnel.private_key = converted,
+                    Err(e) => self.logger.log_print(&format!("Keeping the .ppk key of '{}': {}", tunnel.name, e)),
+                }
+            }
+            self.tunnel_manager.add_tunnel(tunnel);
+            imported += 1;
+        }
+
+        if let Err(e) = self.save_retry.save(&self.tunnels_file, self.tunnel_manager.get_tunnels(), self.tunnel_manager.settings()) {
+            self.logger.log_print(&format!("Error saving tunnels: {}", e));
+        }
+        self.logger.log_print(&format!(
+            "Imported {} tunnel(s) from PuTTY sessions ({} skipped)",
+            imported, skipped
+        ));
+        Task::batch([window::close(window_id), self.update(Message::UpdateTrayMenu)])
+    }
+
     /// Maps tunnel form messages from the view to app messages with window ID
     fn map_tunnel_form_message(&self, window_id: window::Id, msg: windows::create_tunnel::Message) -> Message {
         match msg {
             windows::create_tunnel::Message::NameChanged(v) => 
                 Message::TunnelFormFieldChanged(window_id, TunnelFormField::Name(v)),
+            windows::create_tunnel::Message::DisplayNameChanged(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::DisplayName(v)),
             windows::create_tunnel::Message::LocalHostChanged(v) => 
                 Message::TunnelFormFieldChanged(window_id, TunnelFormField::LocalHost(v)),
+            windows::create_tunnel::Message::BindInterfaceChanged(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::BindInterface(v)),
             windows::create_tunnel::Message::LocalPortChanged(v) => 
                 Message::TunnelFormFieldChanged(window_id, TunnelFormField::LocalPort(v)),
             windows::create_tunnel::Message::RemoteHostChanged(v) => 
                 Message::TunnelFormFieldChanged(window_id, TunnelFormField::RemoteHost(v)),
             windows::create_tunnel::Message::RemotePortChanged(v) => 
                 Message::TunnelFormFieldChanged(window_id, TunnelFormField::RemotePort(v)),
+            windows::create_tunnel::Message::RemoteIsSocketToggled(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::RemoteIsSocket(v)),
+            windows::create_tunnel::Message::RemoteSocketChanged(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::RemoteSocket(v)),
             windows::create_tunnel::Message::SshUserChanged(v) => 
                 Message::TunnelFormFieldChanged(window_id, TunnelFormField::SshUser(v)),
             windows::create_tunnel::Message::SshHostChanged(v) => 
@@ -577,12 +2814,70 @@ impl App {
                 Message::TunnelFormFieldChanged(window_id, TunnelFormField::SshPort(v)),
             windows::create_tunnel::Message::PrivateKeyChanged(v) => 
                 Message::TunnelFormFieldChanged(window_id, TunnelFormField::PrivateKey(v)),
+            windows::create_tunnel::Message::SshConfigFileChanged(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::SshConfigFile(v)),
+            windows::create_tunnel::Message::IdentitiesOnlyToggled(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::IdentitiesOnly(v)),
+            windows::create_tunnel::Message::AcceptNewHostKeysToggled(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::AcceptNewHostKeys(v)),
+            windows::create_tunnel::Message::InventoryFileChanged(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::InventoryFile(v)),
+            windows::create_tunnel::Message::AutoStartToggled(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::AutoStart(v)),
+            windows::create_tunnel::Message::OpenWebOnConnectToggled(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::OpenWebOnConnect(v)),
+            windows::create_tunnel::Message::CleanExitDisconnectsToggled(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::CleanExitDisconnects(v)),
+            windows::create_tunnel::Message::NotificationsToggled(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::Notifications(v)),
+            windows::create_tunnel::Message::FavoriteToggled(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::Favorite(v)),
+            windows::create_tunnel::Message::EnvKeyChanged(index, v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::EnvKey(index, v)),
+            windows::create_tunnel::Message::EnvValueChanged(index, v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::EnvValue(index, v)),
+            windows::create_tunnel::Message::AddEnv => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::AddEnv),
+            windows::create_tunnel::Message::RemoveEnv(index) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::RemoveEnv(index)),
+            windows::create_tunnel::Message::RequiredInterfaceChanged(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::RequiredInterface(v)),
+            windows::create_tunnel::Message::GateHostChanged(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::GateHost(v)),
+            windows::create_tunnel::Message::AutoDisconnectChanged(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::AutoDisconnect(v)),
+            windows::create_tunnel::Message::SshBinaryChanged(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::SshBinary(v)),
+            windows::create_tunnel::Message::AuthModeSelected(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::AuthMode(v)),
+            windows::create_tunnel::Message::JumpTunnelSelected(v) => 
+                Message::TunnelFormFieldChanged(window_id, TunnelFormField::JumpTunnel(v)),
+            windows::create_tunnel::Message::TabSelected(tab) => 
+                Message::TunnelFormTabSelected(window_id, tab),
+            windows::create_tunnel::Message::TemplateSelected(name) => 
+                Message::TunnelFormTemplateSelected(window_id, name),
+            windows::create_tunnel::Message::TemplateNameChanged(name) => 
+                Message::TunnelFormTemplateNameChanged(window_id, name),
+            windows::create_tunnel::Message::SaveAsTemplate => 
+                Message::TunnelFormSaveTemplate(window_id),
+            windows::create_tunnel::Message::DeleteTemplate(name) => 
+                Message::TunnelFormDeleteTemplate(window_id, name),
             windows::create_tunnel::Message::BrowsePrivateKey => 
                 Message::TunnelFormBrowsePrivateKey(window_id),
+            windows::create_tunnel::Message::BrowseSshConfigFile => 
+                Message::TunnelFormBrowseSshConfigFile(window_id),
+            windows::create_tunnel::Message::BrowseSshBinary => 
+                Message::TunnelFormBrowseSshBinary(window_id),
+            windows::create_tunnel::Message::AssignFreePort => 
+                Message::TunnelFormAssignFreePort(window_id),
             windows::create_tunnel::Message::Test => 
                 Message::TunnelFormTest(window_id),
+            windows::create_tunnel::Message::CancelTest => 
+                Message::TunnelFormCancelTest(window_id),
             windows::create_tunnel::Message::Create => 
                 Message::TunnelFormSubmit(window_id),
+            windows::create_tunnel::Message::Apply => 
+                Message::TunnelFormApply(window_id),
             windows::create_tunnel::Message::Cancel => 
                 Message::TunnelFormCancel(window_id),
         }
@@ -592,20 +2887,56 @@ impl App {
     fn update_tunnel_form_field(&mut self, window_id: window::Id, field: TunnelFormField) {
         if let Some(window_type) = self.windows.get_mut(&window_id) {
             match window_type {
-                WindowType::TunnelForm {
-                    name, local_host, local_port, remote_host, remote_port,
-                    ssh_user, ssh_host, ssh_port, private_key, ..
-                } => {
+                WindowType::TunnelForm { form, original, restart_fields, .. } => {
                     match field {
-                        TunnelFormField::Name(v) => *name = v,
-                        TunnelFormField::LocalHost(v) => *local_host = v,
-                        TunnelFormField::LocalPort(v) => *local_port = v,
-                        TunnelFormField::RemoteHost(v) => *remote_host = v,
-                        TunnelFormField::RemotePort(v) => *remote_port = v,
-                        TunnelFormField::SshUser(v) => *ssh_user = v,
-                        TunnelFormField::SshHost(v) => *ssh_host = v,
-                        TunnelFormField::SshPort(v) => *ssh_port = v,
-                        TunnelFormField::PrivateKey(v) => *private_key = v,
+                        TunnelFormField::Name(v) => form.name = v,
+                        TunnelFormField::DisplayName(v) => form.display_name = v,
+                        TunnelFormField::LocalHost(v) => form.local_host = v,
+                        TunnelFormField::BindInterface(v) => form.bind_interface = v,
+                        TunnelFormField::LocalPort(v) => form.local_port = v,
+                        TunnelFormField::RemoteHost(v) => form.remote_host = v,
+                        TunnelFormField::RemotePort(v) => form.remote_port = v,
+                        TunnelFormField::RemoteIsSocket(v) => form.remote_is_socket = v,
+                        TunnelFormField::RemoteSocket(v) => form.remote_socket = v,
+                        TunnelFormField::SshUser(v) => form.ssh_user = v,
+                        TunnelFormField::SshHost(v) => form.ssh_host = v,
+                        TunnelFormField::SshPort(v) => form.ssh_port = v,
+                        TunnelFormField::PrivateKey(v) => form.private_key = v,
+                        TunnelFormField::SshConfigFile(v) => form.ssh_config_file = v,
+                        TunnelFormField::IdentitiesOnly(v) => form.identities_only = v,
+                        TunnelFormField::AcceptNewHostKeys(v) => form.accept_new_host_keys = v,
+                        TunnelFormField::InventoryFile(v) => form.inventory_file = v,
+                        TunnelFormField::AutoStart(v) => form.auto_start = v,
+                        TunnelFormField::OpenWebOnConnect(v) => form.open_web_on_connect = v,
+                        TunnelFormField::CleanExitDisconnects(v) => form.clean_exit_disconnects = v,
+                        TunnelFormField::Notifications(v) => form.notifications = v,
+                        TunnelFormField::Favorite(v) => form.favorite = v,
+                        TunnelFormField::EnvKey(index, v) => {
+                            if let Some(row) = form.env.get_mut(index) {
+                                row.0 = v;
+                            }
+                        }
+                        TunnelFormField::EnvValue(index, v) => {
+                            if let Some(row) = form.env.get_mut(index) {
+                                row.1 = v;
+                            }
+                        }
+                        TunnelFormField::AddEnv => form.env.push((String::new(), String::new())),
+                        TunnelFormField::RemoveEnv(index) => {
+                            if index < form.env.len() {
+                                form.env.remove(index);
+                            }
+                        }
+                        TunnelFormField::RequiredInterface(v) => form.required_interface = v,
+                        TunnelFormField::GateHost(v) => form.gate_host = v,
+                        TunnelFormField::AutoDisconnect(v) => form.auto_disconnect_minutes = v,
+                        TunnelFormField::SshBinary(v) => form.ssh_binary = v,
+                        TunnelFormField::AuthMode(v) => form.auth_mode = v,
+                        TunnelFormField::JumpTunnel(v) => form.jump_tunnel_id = v,
+                    }
+                    // Recomputed on every edit so the form can flag what needs a restart
+                    if let Some(original) = original {
+                        *restart_fields = windows::connection_changes(original, form);
                     }
                 }
                 _ => {}
@@ -613,53 +2944,148 @@ impl App {
         }
     }
 
+    /// Offer to restart a tunnel an edit left running with its old connection settings
+    /// Confirming also makes the saved form its edit form's new baseline (see `TunnelsRestart`).
+    fn confirm_edit_restart(&mut self, tunnel_name: Option<String>) -> Task<Message> {
+        let Some(tunnel_name) = tunnel_name else {
+            return Task::none();
+        };
+        self.open_confirm(
+            "Restart Tunnel",
+            format!("'{}' is running with the old connection settings.\n\nRestart it now to apply the changes?", tunnel_name),
+            ConfirmAction::RestartTunnels(vec![tunnel_name]),
+        )
+    }
+
     /// Handles tunnel form submission for both create and edit modes
-    fn handle_tunnel_form_submit(&mut self, window_id: window::Id) -> Task<Message> {
+    /// With `close` false (Apply) the window stays open; a created tunnel
+    /// switches the form to edit mode so applying again updates it.
+    /// New warnings (private key, endpoints) also keep the window open once so they are seen.
+    fn handle_tunnel_form_submit(&mut self, window_id: window::Id, close: bool) -> Task<Message> {
+        if self.refuses_changes("Saving a tunnel") {
+            return Task::none();
+        }
         let window_type = self.windows.get_mut(&window_id);
         if window_type.is_none() {
             return Task::none();
         }
         match window_type.unwrap() {
             WindowType::TunnelForm {
-                mode,
-                name, local_host, local_port, remote_host, remote_port,
-                ssh_user, ssh_host, ssh_port, private_key,
-                error_message, ..
+                mode, form, error_message, warnings, saved_notice, original, restart_fields, saved, ..
             } => {
-                match windows::create_tunnel::validate_and_create_tunnel(
-                    name, local_host, local_port, remote_host, remote_port,
-                    ssh_user, ssh_host, ssh_port, private_key,
-                ) {
-                    Ok(mut tunnel) => {
+                *saved_notice = false;
+                let editing_id = match mode {
+                    windows::FormMode::Edit { tunnel_id } => Some(tunnel_id.clone()),
+                    windows::FormMode::Create | windows::FormMode::CreateFrom { .. } => None,
+                };
+                let others: Vec<Tunnel> = self
+                    .tunnel_manager
+                    .get_tunnels()
+                    .iter()
+                    .filter(|t| Some(&t.id) != editing_id.as_ref())
+                    .cloned()
+                    .collect();
+                match windows::create_tunnel::validate_and_create_tunnel(form, &others) {
+                    Ok((mut tunnel, endpoint_warnings)) => {
+                        if let Some(tunnel_id) = editing_id {
+                            tunnel.id = tunnel_id;
+                        }
+                        // Saving under the source's name would shadow it (tunnels are looked up by name)
+                        if matches!(mode, windows::FormMode::CreateFrom { .. }) && others.iter().any(|t| t.name == tunnel.name) {
+                            *error_message = Some(format!("A tunnel named '{}' already exists, choose another name", tunnel.name));
+                            return window::resize(window_id, Size::new(600.0, 600.0));
+                        }
+                        // Refuse missing or cyclic jump tunnel references
+                        if tunnel.jump_tunnel_id.is_some() {
+                            let mut candidates = others.clone();
+                            candidates.push(tunnel.clone());
+                            if let Err(e) = resolve_jump_hosts(&tunnel, &candidates) {
+                                *error_message = Some(e.to_string());
+                                return window::resize(window_id, Size::new(500.0, 590.0));
+                            }
+                        }
+                        let mut new_warnings = endpoint_warnings;
+                        new_warnings.extend(private_key_warnings(&tunnel, self.tunnel_manager.settings()));
+                        new_warnings.extend(auth_warnings(&tunnel));
+                        let close = close && (new_warnings.is_empty() || *warnings == new_warnings);
+                        *warnings = new_warnings;
                         let manager = &mut self.tunnel_manager;
+                        // Shown in the form, which then stays open; the tunnel is kept in memory
+                        let mut save_error: Option<String> = None;
+                        // A running tunnel whose connection settings changed, to offer a restart for
+                        let mut restart_prompt: Option<String> = None;
                         match mode {
-                            windows::FormMode::Create => {
-                                // log_print(&format!("Saving new tunnel: {}", tunnel.name));
+                            windows::FormMode::Create | windows::FormMode::CreateFrom { .. } => {
                                 manager.add_tunnel(tunnel.clone());
-                                if let Err(_e) = TunnelManager::save_tunnels(&self.tunnels_file, manager.get_tunnels()) {
-                                    // log_print(&format!("Error saving tunnels: {}", _e));
+                                if let Err(e) = self.save_retry.save(&self.tunnels_file, manager.get_tunnels(), manager.settings()) {
+                                    self.logger.log_print(&format!("Error saving tunnels: {}", e));
+                                    save_error = Some(format!("Error saving tunnels: {}", e));
                                 } else {
-                                    notifications::notify_tunnel_created(&tunnel.name);
+                                    if tunnel_notifies(&mut self.logger, manager.get_tunnels(), &tunnel.name, "created") {
+                                        notifications::notify_tunnel_created(&tunnel.name);
+                                    }
                                 }
+                                *mode = windows::FormMode::Edit { tunnel_id: tunnel.id.clone() };
+                                // Only what reached the file counts as saved (closing still asks otherwise)
+                                if save_error.is_none() {
+                                    *saved = form.clone();
+                                }
+                                *original = Some(form.clone());
+                                restart_fields.clear();
                             }
                             windows::FormMode::Edit { tunnel_id } => {
-                                // log_print(&format!("Updating tunnel: {}", tunnel.name));
-                                tunnel.id = tunnel_id.clone();
-                                if let Err(e) = manager.update_tunnel(tunnel_id, tunnel.clone()) {
-                                    // log_print(&format!("Error updating tunnel: {}", e));
-                                    *error_message = Some(format!("Error updating tunnel: {}", e));
-                                    let _ = manager;
-                                    return Task::none();
+                                let needs_restart = match manager.update_tunnel(tunnel_id, tunnel.clone()) {
+                                    Ok(needs_restart) => needs_restart,
+                                    Err(e) => {
+                                        *error_message = Some(format!("Error updating tunnel: {}", e));
+                                        return Task::none();
+                                    }
+                                };
+                                if let Err(e) = self.save_retry.save(&self.tunnels_file, manager.get_tunnels(), manager.settings()) {
+                                    self.logger.log_print(&format!("Error saving tunnels: {}", e));
+                                    save_error = Some(format!("Error saving tunnels: {}", e));
+                                }
+                                if save_error.is_none() {
+                                    *saved = form.clone();
                                 }
-                                if let Err(_e) = TunnelManager::save_tunnels(&self.tunnels_file, manager.get_tunnels()) {
-                                    // log_print(&format!("Error saving tunnels: {}", _e));
+                                // Until the restart is confirmed the old values stay the baseline: the tunnel still runs with them
+                                if needs_restart {
+                                    restart_prompt = Some(tunnel.name.clone());
+                                } else {
+                                    *original = Some(form.clone());
+                                    restart_fields.clear();
                                 }
                             }
                         }
-                        let _ = manager;
+                        if let Some(save_error) = save_error {
+                            *error_message = Some(save_error);
+                            let extra_height = error_message.as_ref()
+                                .map(|msg| (msg.len() / 60).max(1) as f32 * 20.0 + 40.0)
+                                .unwrap_or(0.0);
+                            return Task::batch(vec![
+                                self.update(Message::UpdateTrayMenu),
+                                window::resize(window_id, Size::new(500.0, 530.0 + extra_height)),
+                                self.confirm_edit_restart(restart_prompt),
+                            ]);
+                        }
+                        if close {
+                            return Task::batch(vec![
+                                self.update(Message::UpdateTrayMenu),
+                                window::close(window_id),
+                                self.confirm_edit_restart(restart_prompt),
+                            ]);
+                        }
+                        *error_message = None;
+                        *saved_notice = true;
+                        let extra_height = warnings.len() as f32 * 40.0;
+                        let expire = Task::perform(tokio::time::sleep(SAVED_NOTICE_DURATION), move |_| {
+                            Message::TunnelFormSavedNoticeExpired(window_id)
+                        });
                         Task::batch(vec![
                             self.update(Message::UpdateTrayMenu),
-                            window::close(window_id),
+                            window::resize(window_id, Size::new(500.0, 560.0 + extra_height)),
+                            expire,
+                            self.confirm_edit_restart(restart_prompt),
                         ])
                     }
                     Err(err) => {
@@ -667,7 +3093,7 @@ impl App {
                         let extra_height = error_message.as_ref()
                             .map(|msg| (msg.len() / 60).max(1) as f32 * 20.0 + 40.0)
                             .unwrap_or(0.0);
-                        window::resize(window_id, Size::new(500.0, 640.0 + extra_height))
+                        window::resize(window_id, Size::new(500.0, 530.0 + extra_height))
                     }
                 }
             }
@@ -689,3 +3115,305 @@ pub fn get_platform() -> &'static str {
     #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "linux")))]
     return "Unknown";
 }
+
+/// Run an update check in the background
+/// Failures (offline, rate limited, ...) just yield no release
+fn update_check_task(check: fn() -> Option<ReleaseInfo>) -> Task<Message> {
+    Task::perform(
+        async move {
+            tokio::task::spawn_blocking(check)
+                .await
+                .ok()
+                .flatten()
+        },
+        Message::UpdateCheckFinished,
+    )
+}
+
+/// Whether to notify about a tunnel's event; unknown tunnels notify
+/// A suppressed notification is logged instead, so the event isn't lost.
+fn tunnel_notifies(logger: &mut Logger, tunnels: &[Tunnel], tunnel_name: &str, event: &str) -> bool {
+    let enabled = tunnels.iter().find(|t| t.name == tunnel_name).is_none_or(|t| t.notifications);
+    if !enabled {
+        logger.log_print(&format!("Tunnel '{}' {} (notifications off for this tunnel)", tunnel_name, event));
+    }
+    enabled
+}
+
+/// The "Trust host and retry" question with the actions of its answers
+/// Yes saves `accept_new_host_keys` on the tunnel and Trust Once retries
+/// without; managed tunnels only get the one-off retry, asked as Yes/No.
+fn trust_host_question(tunnel_name: &str, locked: bool) -> (&'static str, ConfirmAction, Option<(String, ConfirmAction)>) {
+    let trust = |remember| ConfirmAction::TrustHost { tunnel_name: tunnel_name.to_string(), remember };
+    if locked {
+        return ("Trust it and retry?", trust(false), None);
+    }
+    (
+        "Trust host and retry?\n\nYes: always trust new host keys for this tunnel (StrictHostKeyChecking=accept-new)\nTrust Once: trust this host key once\nNo: leave the tunnel disconnected",
+        trust(true),
+        Some(("Trust Once".to_string(), trust(false))),
+    )
+}
+
+/// Delay before retrying the tray menu rebuild after `failures` failures in a row
+fn tray_retry_delay(failures: u32) -> Duration {
+    TRAY_RETRY_DELAY
+        .saturating_mul(1 << failures.saturating_sub(1).min(6))
+        .min(TRAY_RETRY_MAX_DELAY)
+}
+
+/// Record `error` as the last one notified for the tunnel
+/// Returns false when it is the one already recorded, which isn't notified again.
+fn remember_error(notified_errors: &mut BTreeMap<String, String>, tunnel_name: &str, error: &str) -> bool {
+    if notified_errors.get(tunnel_name).is_some_and(|notified| notified == error) {
+        return false;
+    }
+    notified_errors.insert(tunnel_name.to_string(), error.to_string());
+    true
+}
+
+/// Take every queued status update as messages, and whether the channel closed
+/// The lock is released before the messages are sent, so it is never held across an await.
+fn drain_status_updates(status_receiver: &Mutex<broadcast::Receiver<StatusUpdate>>) -> (Vec<Message>, bool) {
+    let mut receiver = match status_receiver.lock() {
+        Ok(receiver) => receiver,
+        Err(poisoned) => poisoned.into_inner(),
+    };
+    let mut messages = Vec::new();
+    loop {
+        match receiver.try_recv() {
+            Ok(update) => messages.push(Message::TunnelStatusUpdate(update)),
+            // Updates were dropped: rebuild the tray from the manager's
+            // state so it doesn't stay stale
+            Err(broadcast::error::TryRecvError::Lagged(_)) => messages.push(Message::UpdateTrayMenu),
+            Err(broadcast::error::TryRecvError::Empty) => return (messages, false),
+            Err(broadcast::error::TryRecvError::Closed) => return (messages, true),
+        }
+    }
+}
+
+/// Run a connection test on a blocking thread (ssh blocks for up to TEST_TIMEOUT)
+async fn run_connection_test(test: crate::tunnels::ConnectionTest) -> TestOutcome {
+    match tokio::task::spawn_blocking(move || test.run()).await {
+        Ok(Ok(message)) => TestOutcome::Success(message),
+        Ok(Err(DrillError::TooManyAuthFailures(host))) => TestOutcome::TooManyAuthFailures(host),
+        Ok(Err(DrillError::UnknownHostKey(host))) => TestOutcome::UnknownHostKey(host),
+        Ok(Err(err)) => TestOutcome::Failed(err.to_string()),
+        Err(err) => TestOutcome::Failed(format!("Connection test stopped: {}", err)),
+    }
+}
+
+/// Whether a tray connection test succeeded and the result its notification shows
+fn tray_test_result(outcome: TestOutcome) -> (bool, String) {
+    match outcome {
+        TestOutcome::Success(_) => (true, "SSH OK".to_string()),
+        TestOutcome::TooManyAuthFailures(host) => (false, DrillError::TooManyAuthFailures(host).to_string()),
+        TestOutcome::UnknownHostKey(host) => (false, DrillError::UnknownHostKey(host).to_string()),
+        TestOutcome::Failed(err) => (false, err),
+    }
+}
+
+/// The message a confirmed action dispatches
+fn confirm_action_message(action: ConfirmAction) -> Message {
+    match action {
+        ConfirmAction::RemoveTunnel(tunnel_name) => Message::TunnelRemove(tunnel_name),
+        ConfirmAction::Quit => Message::Quit,
+        ConfirmAction::DiscardForm(window_id) => Message::TunnelFormCancel(window_id),
+        ConfirmAction::RestartTunnels(tunnel_names) => Message::TunnelsRestart(tunnel_names),
+        ConfirmAction::AdoptExternal(tunnel_name) => Message::TunnelAdopt(tunnel_name),
+        ConfirmAction::TrustHost { tunnel_name, remember } => Message::TunnelTrustHost(tunnel_name, remember),
+    }
+}
+
+/// Message to dispatch once a confirmation window is answered: the
+/// confirmed action, the alternative's, or nothing when canceled
+fn confirm_follow_up(on_confirm: &ConfirmAction, alternative: Option<&ConfirmAction>, answer: windows::confirm::Message) -> Option<Message> {
+    match answer {
+        windows::confirm::Message::Confirm => Some(confirm_action_message(on_confirm.clone())),
+        windows::confirm::Message::Alternative => alternative.cloned().map(confirm_action_message),
+        windows::confirm::Message::Cancel => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn poisoned_status_receiver_still_delivers_updates() {
+        let (sender, receiver) = broadcast::channel(10);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let holder = Arc::clone(&receiver);
+        let _ = std::thread::spawn(move || {
+            let _guard = holder.lock().unwrap();
+            panic!("poison the lock");
+        })
+        .join();
+        assert!(receiver.is_poisoned());
+
+        sender.send(StatusUpdate::Connected("web".to_string())).unwrap();
+        let (messages, closed) = drain_status_updates(&receiver);
+
+        assert!(matches!(messages.as_slice(), [Message::TunnelStatusUpdate(StatusUpdate::Connected(name))] if name == "web"));
+        assert!(!closed);
+    }
+
+    #[test]
+    fn lagged_status_updates_resync_the_tray() {
+        let (sender, receiver) = broadcast::channel(1);
+        let receiver = Mutex::new(receiver);
+        sender.send(StatusUpdate::Connecting("web".to_string())).unwrap();
+        sender.send(StatusUpdate::Connected("web".to_string())).unwrap();
+        drop(sender);
+
+        let (messages, closed) = drain_status_updates(&receiver);
+
+        assert!(matches!(messages.as_slice(), [Message::UpdateTrayMenu, Message::TunnelStatusUpdate(StatusUpdate::Connected(_))]));
+        assert!(closed);
+    }
+
+    #[test]
+    fn muted_tunnels_log_instead_of_notifying() {
+        let log_path = std::env::temp_dir().join(format!("drill-app-{}-notify.log", std::process::id()));
+        let mut logger = Logger::new(std::fs::File::create(&log_path).unwrap(), log_path.clone());
+        logger.set_echo_stdout(false);
+        let loud = crate::tunnels::test_tunnel("web", "18080");
+        let muted = Tunnel { notifications: false, ..crate::tunnels::test_tunnel("flaky", "18081") };
+        let tunnels = vec![loud, muted];
+
+        assert!(tunnel_notifies(&mut logger, &tunnels, "web", "connected"));
+        assert!(!tunnel_notifies(&mut logger, &tunnels, "flaky", "disconnected"));
+        // A tunnel removed meanwhile still notifies
+        assert!(tunnel_notifies(&mut logger, &tunnels, "gone", "disconnected"));
+
+        let log = std::fs::read_to_string(&log_path).unwrap();
+        assert!(log.contains("Tunnel 'flaky' disconnected (notifications off for this tunnel)"));
+        assert!(!log.contains("'web'"));
+        let _ = std::fs::remove_file(&log_path);
+    }
+
+    #[test]
+    fn confirming_dispatches_the_stored_action() {
+        use windows::confirm::Message as Answer;
+        let remove = ConfirmAction::RemoveTunnel("web".to_string());
+        let restart = ConfirmAction::RestartTunnels(vec!["web".to_string(), "db".to_string()]);
+
+        assert!(matches!(confirm_follow_up(&remove, None, Answer::Confirm), Some(Message::TunnelRemove(name)) if name == "web"));
+        assert!(matches!(confirm_follow_up(&ConfirmAction::Quit, None, Answer::Confirm), Some(Message::Quit)));
+        assert!(matches!(confirm_follow_up(&restart, None, Answer::Confirm), Some(Message::TunnelsRestart(names)) if names == ["web", "db"]));
+        let adopt = ConfirmAction::AdoptExternal("web".to_string());
+        assert!(matches!(confirm_follow_up(&adopt, None, Answer::Confirm), Some(Message::TunnelAdopt(name)) if name == "web"));
+    }
+
+    #[test]
+    fn canceling_dispatches_nothing() {
+        use windows::confirm::Message as Answer;
+        for action in [ConfirmAction::RemoveTunnel("web".to_string()), ConfirmAction::Quit, ConfirmAction::RestartTunnels(Vec::new())] {
+            assert!(confirm_follow_up(&action, None, Answer::Cancel).is_none());
+        }
+    }
+
+    #[test]
+    fn status_updates_sent_before_the_first_drain_are_delivered() {
+        let (status_tx, status_rx) = broadcast::channel(4);
+        // The manager reports before the subscription ever runs
+        status_tx.send(StatusUpdate::Connecting("web".to_string())).unwrap();
+        status_tx.send(StatusUpdate::Connected("web".to_string())).unwrap();
+        let status_receiver = Mutex::new(status_rx);
+
+        let (messages, closed) = drain_status_updates(&status_receiver);
+
+        assert!(!closed);
+        assert!(matches!(
+            messages.as_slice(),
+            [
+                Message::TunnelStatusUpdate(StatusUpdate::Connecting(first)),
+                Message::TunnelStatusUpdate(StatusUpdate::Connected(second)),
+            ] if first == "web" && second == "web"
+        ));
+        assert!(drain_status_updates(&status_receiver).0.is_empty());
+    }
+
+    #[test]
+    fn the_same_error_is_notified_once() {
+        let mut notified_errors = BTreeMap::new();
+        assert!(remember_error(&mut notified_errors, "web", "Connection refused"));
+        assert!(!remember_error(&mut notified_errors, "web", "Connection refused"));
+        // Another tunnel or another error is news
+        assert!(remember_error(&mut notified_errors, "db", "Connection refused"));
+        assert!(remember_error(&mut notified_errors, "web", "Host key verification failed"));
+        assert!(remember_error(&mut notified_errors, "web", "Connection refused"));
+        // Connecting, disconnecting or removing the tunnel forgets its error
+        notified_errors.remove("web");
+        assert!(remember_error(&mut notified_errors, "web", "Connection refused"));
+    }
+
+    #[test]
+    fn tray_retries_back_off_up_to_a_minute() {
+        let delays: Vec<u64> = (1..=8).map(|failures| tray_retry_delay(failures).as_secs()).collect();
+        assert_eq!(delays, [1, 2, 4, 8, 16, 32, 60, 60]);
+    }
+
+    #[test]
+    fn trust_host_answers() {
+        use windows::confirm::Message as Answer;
+        let answer = |locked: bool, answer: Answer| {
+            let (_, on_confirm, alternative) = trust_host_question("web", locked);
+            match confirm_follow_up(&on_confirm, alternative.as_ref().map(|(_, action)| action), answer) {
+                Some(Message::TunnelTrustHost(name, remember)) if name == "web" => Some(remember),
+                _ => None,
+            }
+        };
+        assert_eq!(answer(false, Answer::Confirm), Some(true));
+        assert_eq!(answer(false, Answer::Alternative), Some(false));
+        assert_eq!(answer(false, Answer::Cancel), None);
+        // Managed: Yes retries once, without changing the tunnel
+        assert_eq!(answer(true, Answer::Confirm), Some(false));
+        assert_eq!(answer(true, Answer::Alternative), None);
+        assert_eq!(answer(true, Answer::Cancel), None);
+    }
+
+    #[test]
+    fn discarding_a_form_cancels_it() {
+        use windows::confirm::Message as Answer;
+        let window_id = window::Id::unique();
+        let discard = ConfirmAction::DiscardForm(window_id);
+
+        assert!(matches!(confirm_follow_up(&discard, None, Answer::Confirm), Some(Message::TunnelFormCancel(id)) if id == window_id));
+        assert!(confirm_follow_up(&discard, None, Answer::Cancel).is_none());
+    }
+
+    /// Run a tray test of `tunnel` with `runner` as ssh, as the tray's Test Connection item does
+    async fn tray_test(runner: crate::runner::MockRunner, tunnel: Tunnel) -> (bool, String) {
+        let mut manager = TunnelManager::new();
+        manager.set_command_runner(std::sync::Arc::new(runner));
+        manager.set_tunnels(vec![tunnel.clone()]);
+        tray_test_result(run_connection_test(manager.connection_test(&tunnel).unwrap()).await)
+    }
+
+    #[tokio::test]
+    async fn tray_test_reports_ssh_ok() {
+        let result = tray_test(crate::runner::MockRunner::success(), crate::tunnels::test_tunnel("db", "15432")).await;
+
+        assert_eq!(result, (true, "SSH OK".to_string()));
+    }
+
+    #[tokio::test]
+    async fn tray_test_reports_the_ssh_error() {
+        let runner = crate::runner::MockRunner::exiting(255, "user@example.com: Permission denied (publickey).\n");
+
+        let (succeeded, result) = tray_test(runner, crate::tunnels::test_tunnel("db", "15432")).await;
+
+        assert!(!succeeded);
+        assert!(result.contains("Permission denied"), "{}", result);
+    }
+
+    #[test]
+    fn tray_test_explains_the_fixable_failures() {
+        let (succeeded, result) = tray_test_result(TestOutcome::TooManyAuthFailures("example.com".to_string()));
+        assert!(!succeeded);
+        assert!(result.starts_with("Too many authentication failures on example.com"));
+        let (_, result) = tray_test_result(TestOutcome::UnknownHostKey("example.com".to_string()));
+        assert!(result.starts_with("Unknown host key for example.com"));
+    }
+}
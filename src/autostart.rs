@@ -0,0 +1,189 @@
+use crate::error::{DrillResult, DrillError};
+
+/// Identifier used for the autostart entry on every platform
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+const AUTOSTART_LABEL: &str = "com.drill.app";
+
+/// Get the path of the running executable as a string
+fn current_exe_path() -> DrillResult<String> {
+    let exe = std::env::current_exe()?;
+    Ok(exe.to_string_lossy().to_string())
+}
+
+// macOS: a LaunchAgent plist in ~/Library/LaunchAgents
+
+#[cfg(target_os = "macos")]
+fn launch_agent_path() -> DrillResult<std::path::PathBuf> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| DrillError::Config("Could not determine home directory".to_string()))?;
+    Ok(home_dir
+        .join("Library")
+        .join("LaunchAgents")
+        .join(format!("{}.plist", AUTOSTART_LABEL)))
+}
+
+/// Register Drill to start at login
+#[cfg(target_os = "macos")]
+pub fn enable_autostart() -> DrillResult<()> {
+    let plist_path = launch_agent_path()?;
+    if let Some(parent) = plist_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let plist = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        AUTOSTART_LABEL,
+        xml_escape(&current_exe_path()?)
+    );
+    std::fs::write(&plist_path, plist)?;
+    Ok(())
+}
+
+/// Remove the login registration
+#[cfg(target_os = "macos")]
+pub fn disable_autostart() -> DrillResult<()> {
+    let plist_path = launch_agent_path()?;
+    if plist_path.exists() {
+        std::fs::remove_file(&plist_path)?;
+    }
+    Ok(())
+}
+
+/// Check whether Drill is registered to start at login
+#[cfg(target_os = "macos")]
+pub fn is_autostart_enabled() -> bool {
+    launch_agent_path().map(|path| path.exists()).unwrap_or(false)
+}
+
+#[cfg(target_os = "macos")]
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+// Linux: an XDG autostart entry in ~/.config/autostart
+
+#[cfg(target_os = "linux")]
+fn desktop_entry_path() -> DrillResult<std::path::PathBuf> {
+    let config_dir = dirs::config_dir()
+        .ok_or_else(|| DrillError::Config("Could not determine config directory".to_string()))?;
+    Ok(config_dir.join("autostart").join("drill.desktop"))
+}
+
+/// Register Drill to start at login
+#[cfg(target_os = "linux")]
+pub fn enable_autostart() -> DrillResult<()> {
+    let entry_path = desktop_entry_path()?;
+    if let Some(parent) = entry_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let entry = format!(
+        "[Desktop Entry]\nType=Application\nName=Drill\nComment=SSH tunnel manager\nExec=\"{}\"\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+        current_exe_path()?
+    );
+    std::fs::write(&entry_path, entry)?;
+    Ok(())
+}
+
+/// Remove the login registration
+#[cfg(target_os = "linux")]
+pub fn disable_autostart() -> DrillResult<()> {
+    let entry_path = desktop_entry_path()?;
+    if entry_path.exists() {
+        std::fs::remove_file(&entry_path)?;
+    }
+    Ok(())
+}
+
+/// Check whether Drill is registered to start at login
+#[cfg(target_os = "linux")]
+pub fn is_autostart_enabled() -> bool {
+    desktop_entry_path().map(|path| path.exists()).unwrap_or(false)
+}
+
+// Windows: a value under the current user's Run registry key
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+/// Run reg.exe without showing a console window
+#[cfg(target_os = "windows")]
+pub(crate) fn reg_command(args: &[&str]) -> DrillResult<std::process::Output> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    let output = std::process::Command::new("reg")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()?;
+    Ok(output)
+}
+
+/// Register Drill to start at login
+#[cfg(target_os = "windows")]
+pub fn enable_autostart() -> DrillResult<()> {
+    let exe = format!("\"{}\"", current_exe_path()?);
+    let output = reg_command(&["add", RUN_KEY, "/v", "Drill", "/t", "REG_SZ", "/d", &exe, "/f"])?;
+    if !output.status.success() {
+        return Err(DrillError::Config(format!(
+            "Could not add startup entry: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Remove the login registration
+#[cfg(target_os = "windows")]
+pub fn disable_autostart() -> DrillResult<()> {
+    if !is_autostart_enabled() {
+        return Ok(());
+    }
+    let output = reg_command(&["delete", RUN_KEY, "/v", "Drill", "/f"])?;
+    if !output.status.success() {
+        return Err(DrillError::Config(format!(
+            "Could not remove startup entry: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(())
+}
+
+/// Check whether Drill is registered to start at login
+#[cfg(target_os = "windows")]
+pub fn is_autostart_enabled() -> bool {
+    reg_command(&["query", RUN_KEY, "/v", "Drill"])
+        .map(|output| output.status.success())
+        .unwrap_or(false)
+}
+
+// Other platforms have no supported autostart mechanism
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+pub fn enable_autostart() -> DrillResult<()> {
+    Err(DrillError::Config("Start at login is not supported on this platform".to_string()))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+pub fn disable_autostart() -> DrillResult<()> {
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+pub fn is_autostart_enabled() -> bool {
+    false
+}
@@ -0,0 +1,237 @@
+use std::fs;
+use std::path::{Path, PathBuf, MAIN_SEPARATOR};
+use crate::error::{DrillResult, DrillError};
+use crate::tunnels::Tunnel;
+
+/// Version of the bundle format written by this build
+const BUNDLE_VERSION: u32 = 1;
+
+/// Describes the machine and settings a bundle was exported from
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct BundleManifest {
+    pub version: u32,
+    pub created_at: String,
+    pub platform: String,
+    pub tunnel_count: usize,
+    /// Directory containing the private keys on the exporting machine
+    #[serde(default)]
+    pub key_dir: Option<String>,
+    /// Whether key paths under the home directory were rewritten as `~/...`
+    #[serde(default)]
+    pub portable_key_paths: bool,
+}
+
+/// A shareable file containing all tunnels plus a manifest
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct Bundle {
+    pub manifest: BundleManifest,
+    pub tunnels: Vec<Tunnel>,
+}
+
+impl Bundle {
+    /// Create a bundle from the given tunnels
+    /// When `portable_key_paths` is set, key paths inside the home directory
+    /// are stored relative to `~` so they resolve on the new machine
+    /// Tunnels generated from an inventory are left out, their template is exported.
+    pub fn new(tunnels: &[Tunnel], portable_key_paths: bool) -> Self {
+        let mut tunnels: Vec<Tunnel> = tunnels.iter().filter(|t| t.generated_from.is_none()).cloned().collect();
+        if portable_key_paths {
+            if let Some(home_dir) = dirs::home_dir() {
+                for tunnel in tunnels.iter_mut() {
+                    tunnel.private_key = to_portable_path(&tunnel.private_key, &home_dir);
+                }
+            }
+        }
+
+        Bundle {
+            manifest: BundleManifest {
+                version: BUNDLE_VERSION,
+                created_at: chrono::Local::now().to_rfc3339(),
+                platform: crate::app::get_platform().to_string(),
+                tunnel_count: tunnels.len(),
+                key_dir: common_key_dir(&tunnels),
+                portable_key_paths,
+            },
+            tunnels,
+        }
+    }
+
+    /// Write the bundle to a file
+    pub fn save(&self, path: &Path) -> DrillResult<()> {
+        let yaml = serde_yaml::to_string(self)?;
+        fs::write(path, format!("# Drill tunnels bundle\n{}", yaml))?;
+        Ok(())
+    }
+
+    /// Read a bundle from a file, expanding portable key paths for this machine
+    pub fn load(path: &Path) -> DrillResult<Self> {
+        let content = fs::read_to_string(path)?;
+        let mut bundle: Bundle = serde_yaml::from_str(&content)?;
+        if bundle.manifest.version > BUNDLE_VERSION {
+            return Err(DrillError::Config(format!(
+                "Bundle version {} is newer than supported version {}",
+                bundle.manifest.version, BUNDLE_VERSION
+            )));
+        }
+        if let Some(home_dir) = dirs::home_dir() {
+            for tunnel in bundle.tunnels.iter_mut() {
+                tunnel.private_key = expand_home_path(&tunnel.private_key, &home_dir);
+            }
+            bundle.manifest.key_dir = bundle.manifest.key_dir.map(|dir| expand_home_path(&dir, &home_dir));
+        }
+        Ok(bundle)
+    }
+
+    /// Get the tunnels whose private key does not exist on this machine
+    pub fn missing_keys(&self) -> Vec<&Tunnel> {
+        self.tunnels
+            .iter()
+            .filter(|t| !t.private_key.trim().is_empty() && !Path::new(&t.private_key).exists())
+            .collect()
+    }
+
+    /// Point missing private keys at `target_dir`
+    /// Paths below the manifest's key directory keep their relative layout,
+    /// anything else is remapped by file name. Returns the number of tunnels changed.
+    pub fn remap_missing_keys(&mut self, target_dir: &Path) -> usize {
+        let key_dir = self.manifest.key_dir.as_ref().map(PathBuf::from);
+        let mut remapped = 0;
+        for tunnel in self.tunnels.iter_mut() {
+            let key_path = PathBuf::from(&tunnel.private_key);
+            if tunnel.private_key.trim().is_empty() || key_path.exists() {
+                continue;
+            }
+            let relative = key_dir
+                .as_ref()
+                .and_then(|dir| key_path.strip_prefix(dir).ok())
+                .map(Path::to_path_buf)
+                .or_else(|| key_path.file_name().map(PathBuf::from));
+            if let Some(relative) = relative {
+                tunnel.private_key = target_dir.join(relative).to_string_lossy().to_string();
+                remapped += 1;
+            }
+        }
+        remapped
+    }
+}
+
+/// Find the deepest directory shared by all private key paths
+fn common_key_dir(tunnels: &[Tunnel]) -> Option<String> {
+    let mut dirs = tunnels
+        .iter()
+        .filter(|t| !t.private_key.trim().is_empty())
+        .filter_map(|t| Path::new(&t.private_key).parent().map(Path::to_path_buf));
+    let mut common = dirs.next()?;
+    for dir in dirs {
+        while !dir.starts_with(&common) {
+            if !common.pop() {
+                return None;
+            }
+        }
+    }
+    if common.as_os_str().is_empty() {
+        None
+    } else {
+        Some(common.to_string_lossy().to_string())
+    }
+}
+
+/// Rewrite a path inside the home directory as `~/...`
+fn to_portable_path(path: &str, home_dir: &Path) -> String {
+    match Path::new(path).strip_prefix(home_dir) {
+        Ok(relative) if !path.trim().is_empty() => {
+            format!("~{}{}", MAIN_SEPARATOR, relative.display())
+        }
+        _ => path.to_string(),
+    }
+}
+
+/// Expand a leading `~/` (or `~\` on Windows) to the home directory
+fn expand_home_path(path: &str, home_dir: &Path) -> String {
+    match path.strip_prefix("~/").or_else(|| path.strip_prefix("~\\")) {
+        Some(relative) => home_dir.join(relative).to_string_lossy().to_string(),
+        None => path.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::test_tunnel;
+
+    /// A tunnel using the private key at `key`
+    fn keyed_tunnel(name: &str, key: &Path) -> Tunnel {
+        Tunnel { private_key: key.to_string_lossy().to_string(), ..test_tunnel(name, "18080") }
+    }
+
+    #[test]
+    fn new_leaves_out_generated_tunnels() {
+        let generated = Tunnel { generated_from: Some("inventory-id".to_string()), ..test_tunnel("generated", "18081") };
+        let bundle = Bundle::new(&[test_tunnel("web", "18080"), generated], false);
+
+        assert_eq!(bundle.tunnels.len(), 1);
+        assert_eq!(bundle.manifest.tunnel_count, 1);
+        assert_eq!(bundle.manifest.version, BUNDLE_VERSION);
+    }
+
+    #[test]
+    fn common_key_dir_is_the_shared_parent() {
+        let keys = std::env::temp_dir().join("drill-bundle-keys");
+        let tunnels = [
+            keyed_tunnel("web", &keys.join("work").join("id_web")),
+            keyed_tunnel("db", &keys.join("id_db")),
+            test_tunnel("no-key", "18082"),
+        ];
+
+        assert_eq!(common_key_dir(&tunnels), Some(keys.to_string_lossy().to_string()));
+        assert_eq!(common_key_dir(&[test_tunnel("no-key", "18082")]), None);
+    }
+
+    #[test]
+    fn portable_paths_round_trip_through_the_home_directory() {
+        let home = std::env::temp_dir().join("drill-bundle-home");
+        let key = home.join(".ssh").join("id_ed25519");
+        let portable = to_portable_path(&key.to_string_lossy(), &home);
+
+        assert_eq!(portable, format!("~{}{}", MAIN_SEPARATOR, Path::new(".ssh").join("id_ed25519").display()));
+        assert_eq!(expand_home_path(&portable, &home), key.to_string_lossy());
+        // Outside the home directory a path is kept as is
+        let elsewhere = std::env::temp_dir().join("id_rsa").to_string_lossy().to_string();
+        assert_eq!(to_portable_path(&elsewhere, &home), elsewhere);
+    }
+
+    #[test]
+    fn remap_keeps_the_layout_below_the_key_directory() {
+        let old_dir = std::env::temp_dir().join("drill-bundle-missing");
+        let target = std::env::temp_dir().join("drill-bundle-target");
+        let mut bundle = Bundle::new(
+            &[
+                keyed_tunnel("web", &old_dir.join("work").join("id_web")),
+                keyed_tunnel("db", &old_dir.join("id_db")),
+                test_tunnel("no-key", "18082"),
+            ],
+            false,
+        );
+
+        assert_eq!(bundle.missing_keys().len(), 2);
+        assert_eq!(bundle.remap_missing_keys(&target), 2);
+        assert_eq!(bundle.tunnels[0].private_key, target.join("work").join("id_web").to_string_lossy());
+        assert_eq!(bundle.tunnels[1].private_key, target.join("id_db").to_string_lossy());
+        assert!(bundle.tunnels[2].private_key.is_empty());
+    }
+
+    #[test]
+    fn load_refuses_a_newer_bundle_version() {
+        let path = std::env::temp_dir().join(format!("drill-bundle-{}.yaml", std::process::id()));
+        let mut bundle = Bundle::new(&[test_tunnel("web", "18080")], false);
+        bundle.save(&path).unwrap();
+        assert_eq!(Bundle::load(&path).unwrap().tunnels.len(), 1);
+
+        bundle.manifest.version = BUNDLE_VERSION + 1;
+        bundle.save(&path).unwrap();
+        let result = Bundle::load(&path);
+        let _ = fs::remove_file(&path);
+
+        assert!(result.is_err());
+    }
+}
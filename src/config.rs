@@ -1,12 +1,13 @@
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::io::Write;
 use crate::logs::Logger;
 use crate::error::{DrillResult, DrillError};
 
 /// Initialize the application configuration directory and files
-/// Returns the path to the config file and a Logger
-pub fn init_config() -> DrillResult<(PathBuf, Logger)> {
+/// Returns the path to the config file, the runtime directory (emptied of
+/// the previous run's leftovers, see `get_run_dir`) and a Logger
+pub fn init_config() -> DrillResult<(PathBuf, PathBuf, Logger)> {
     // Get home directory
     let home_dir = dirs::home_dir()
         .ok_or_else(|| DrillError::Config("Could not determine home directory".to_string()))?;
@@ -36,7 +37,7 @@ pub fn init_config() -> DrillResult<(PathBuf, Logger)> {
     let log_file = fs::File::create(&log_file_path)?;
     
     // Initialize the logger
-    let mut logger = Logger::new(log_file);
+    let mut logger = Logger::new(log_file, log_file_path);
     
     // Create config file path
     let config_file = drill_dir.join("config");
@@ -50,8 +51,45 @@ pub fn init_config() -> DrillResult<(PathBuf, Logger)> {
 # Add your configuration settings here
 
 [settings]
-# Example setting
-# key=value
+# Pass the default ~/.ssh identity with -i when a tunnel has no private key
+# use_default_identity=false
+# Script run on every tunnel status change (gets DRILL_TUNNEL, DRILL_EVENT, DRILL_STATUS)
+# status_hook=/path/to/script
+# List the most recently connected tunnels first in the tray
+# sort_tunnels_by_recent=false
+# Check GitHub for a newer Drill release at startup
+# check_for_updates=false
+# Use the dashboard window instead of the tray icon (for desktops without a usable tray)
+# disable_tray=false
+# Reconnect dropped tunnels with backoff (paused for a while if a host keeps failing)
+# auto_reconnect=false
+# Group the tray tunnels into alphabetical submenus above this many tunnels (0 never groups)
+# tray_group_threshold=25
+# Show this many of the most recently connected tunnels in a Recent submenu of the tray (0 hides it)
+# tray_recent_count=5
+# Seconds between checks for dropped tunnels (lower detects drops sooner, at some overhead)
+# monitor_interval_secs=5
+# Show a tunnel as connected only once ssh reports its forwards ready (or the local port answers),
+# for slow logins such as 2FA or bastions; established_timeout_secs is how long to wait for it
+# wait_for_forwards=false
+# established_timeout_secs=60
+# Global hotkey that connects or disconnects the primary tunnel
+# toggle_hotkey=CmdOrCtrl+Shift+D
+# Tunnel toggled by the hotkey (defaults to the most recently connected one)
+# primary_tunnel=My Tunnel
+# Keep the tunnels file in YAML instead of migrating it to JSON (a YAML file with comments always stays YAML)
+# yaml_tunnels_file=false
+# Count tunnel errors by type and host in ~/.drill/error_stats.json (nothing leaves this machine)
+# error_stats=false
+# When a tunnel's local port is already served by an ssh started outside Drill, offer to watch it instead of connecting
+# adopt_external_tunnels=false
+# Milliseconds notifications stay on screen, and error notifications (not used on macOS, which decides itself)
+# notification_timeout_ms=5000
+# error_notification_timeout_ms=10000
+# Also print log messages on stdout (defaults to true only when started from a terminal)
+# log_to_stdout=false
+# Managed mode: tunnels can be connected but not created, edited or removed (also on when the tunnels file is read-only)
+# locked=false
 "#;
         file.write_all(default_config.as_bytes())?;
     } else {
@@ -67,13 +105,92 @@ pub fn init_config() -> DrillResult<(PathBuf, Logger)> {
     if !tunnels_file.exists() {
         logger.log_print(&format!("Creating default tunnels file at: {}", tunnels_file.display()));
         let mut file = fs::File::create(&tunnels_file)?;
-        // Write default empty tunnels array in YAML format
+        // Write default empty tunnels array (valid JSON and YAML)
         let default_tunnels = "[]\n";
         file.write_all(default_tunnels.as_bytes())?;
     } else {
         logger.log_print(&format!("Tunnels file found at: {}", tunnels_file.display()));
     }
-    Ok((config_file, logger))
+
+    // Runtime artifacts of the previous run are stale: its process is gone
+    // (the single-instance lock is taken before this runs)
+    let run_dir = get_run_dir()?;
+    fs::create_dir_all(&run_dir)?;
+    let removed = clean_run_dir(&run_dir, &mut logger)?;
+    if removed > 0 {
+        logger.log_print(&format!("Removed {} stale file(s) from {}", removed, run_dir.display()));
+    }
+    Ok((config_file, run_dir, logger))
+}
+
+/// Remove everything left in the runtime directory, returning how many entries went
+/// An entry that can't be removed is logged and left; it doesn't stop startup.
+fn clean_run_dir(run_dir: &Path, logger: &mut Logger) -> DrillResult<usize> {
+    let mut removed = 0;
+    for entry in fs::read_dir(run_dir)? {
+        let path = entry?.path();
+        // symlink_metadata: a link to a directory is removed, not followed
+        let result = match fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.is_dir() => fs::remove_dir_all(&path),
+            Ok(_) => fs::remove_file(&path),
+            Err(e) => Err(e),
+        };
+        match result {
+            Ok(()) => removed += 1,
+            Err(e) => logger.log_print(&format!("Could not remove stale {}: {}", path.display(), e)),
+        }
+    }
+    Ok(removed)
+}
+
+/// Get the directory for ephemeral runtime artifacts (sockets, pid files)
+/// It is created by `init_config`, which also empties it at startup, so
+/// nothing in it outlives the Drill instance that wrote it.
+pub fn get_run_dir() -> DrillResult<PathBuf> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| DrillError::Config("Could not determine home directory".to_string()))?;
+    let drill_dir = home_dir.join(".drill");
+    Ok(drill_dir.join("run"))
+}
+
+/// Get the path to the connection event log
+pub fn get_events_file_path() -> DrillResult<PathBuf> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| DrillError::Config("Could not determine home directory".to_string()))?;
+    let drill_dir = home_dir.join(".drill");
+    Ok(drill_dir.join("events"))
+}
+
+/// Get the path to the health report of the running instance
+pub fn get_health_file_path() -> DrillResult<PathBuf> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| DrillError::Config("Could not determine home directory".to_string()))?;
+    let drill_dir = home_dir.join(".drill");
+    Ok(drill_dir.join("health.json"))
+}
+
+/// Get the path to the error summary
+pub fn get_error_stats_file_path() -> DrillResult<PathBuf> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| DrillError::Config("Could not determine home directory".to_string()))?;
+    let drill_dir = home_dir.join(".drill");
+    Ok(drill_dir.join("error_stats.json"))
+}
+
+/// Get the path to the lock file held by the running instance
+pub fn get_instance_lock_path() -> DrillResult<PathBuf> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| DrillError::Config("Could not determine home directory".to_string()))?;
+    let drill_dir = home_dir.join(".drill");
+    Ok(drill_dir.join("drill.lock"))
+}
+
+/// Get the path to the tunnel templates file
+pub fn get_templates_file_path() -> DrillResult<PathBuf> {
+    let home_dir = dirs::home_dir()
+        .ok_or_else(|| DrillError::Config("Could not determine home directory".to_string()))?;
+    let drill_dir = home_dir.join(".drill");
+    Ok(drill_dir.join("templates"))
 }
 
 /// Get the path to the tunnels file
@@ -83,3 +200,208 @@ pub fn get_tunnels_file_path() -> DrillResult<PathBuf> {
     let drill_dir = home_dir.join(".drill");
     Ok(drill_dir.join("tunnels"))
 }
+
+/// Application settings read from the config file
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    /// Pass the detected default identity with `-i` when a tunnel has no private key
+    pub use_default_identity: bool,
+    /// Script run on every tunnel status change
+    pub status_hook: Option<String>,
+    /// List the most recently connected tunnels first instead of in insertion order
+    pub sort_tunnels_by_recent: bool,
+    /// Check for a newer release at startup (off unless the user opts in)
+    pub check_for_updates: bool,
+    /// Open the dashboard window as the main interface instead of creating a tray icon
+    pub disable_tray: bool,
+    /// Reconnect tunnels whose connection drops
+    pub auto_reconnect: bool,
+    /// Number of tunnels above which the tray groups them into alphabetical submenus
+    /// (`None` uses the default, 0 never groups)
+    pub tray_group_threshold: Option<usize>,
+    /// Number of recently connected tunnels in the tray's Recent submenu
+    /// (`None` uses the default, 0 hides the submenu)
+    pub tray_recent_count: Option<usize>,
+    /// Seconds between checks of the running tunnels (`None` uses the default of 5)
+    pub monitor_interval_secs: Option<u64>,
+    /// Keep a tunnel connecting until its forwards are ready, not just until ssh is running
+    pub wait_for_forwards: bool,
+    /// Seconds a tunnel may take to get its forwards ready with `wait_for_forwards`
+    /// (`None` uses the default of 60)
+    pub established_timeout_secs: Option<u64>,
+    /// Global hotkey toggling the primary tunnel (e.g. "CmdOrCtrl+Shift+D"), none when unset
+    pub toggle_hotkey: Option<String>,
+    /// Name of the tunnel the hotkey toggles (the most recently connected one when unset)
+    pub primary_tunnel: Option<String>,
+    /// Write the tunnels file as YAML (keeping comments) rather than JSON
+    pub yaml_tunnels_file: bool,
+    /// Keep a local count of tunnel errors by type and host (~/.drill/error_stats.json)
+    pub error_stats: bool,
+    /// Offer to adopt a forward already served by an ssh started outside Drill when connecting
+    pub adopt_external_tunnels: bool,
+    /// Milliseconds notifications stay up (`None` uses the default of 5000; ignored on macOS)
+    pub notification_timeout_ms: Option<u32>,
+    /// Milliseconds error notifications stay up (`None` uses the default of 10000; ignored on macOS)
+    pub error_notification_timeout_ms: Option<u32>,
+    /// Print log messages on stdout as well as in the log file
+    /// (`None` prints only when stdout is a terminal, see `log_to_stdout`)
+    pub log_to_stdout: Option<bool>,
+    /// Managed mode: tunnels can be connected but not created, edited or removed,
+    /// and the tunnels file is never written (also on when that file is read-only)
+    pub locked: bool,
+}
+
+/// Whether the tunnels file exists and is read-only, which puts Drill in managed mode
+pub fn tunnels_file_read_only(tunnels_file: &Path) -> bool {
+    fs::metadata(tunnels_file).is_ok_and(|metadata| metadata.permissions().readonly())
+}
+
+/// Whether log messages should also go to stdout
+/// Unset, a run from a terminal shows the log stream while the windowed app
+/// and a daemon under a service manager keep stdout quiet.
+pub fn log_to_stdout(settings: &Settings) -> bool {
+    use std::io::IsTerminal;
+    settings.log_to_stdout.unwrap_or_else(|| std::io::stdout().is_terminal())
+}
+
+/// Load the settings from the config file
+/// Lines are `key=value` pairs; comments, section headers and unknown keys are ignored
+pub fn load_settings(config_file: &Path) -> DrillResult<Settings> {
+    let content = fs::read_to_string(config_file)?;
+    let mut settings = Settings::default();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with('[') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        match key.trim() {
+            "use_default_identity" => settings.use_default_identity = parse_bool(value),
+            "status_hook" => settings.status_hook = parse_string(value),
+            "sort_tunnels_by_recent" => settings.sort_tunnels_by_recent = parse_bool(value),
+            "check_for_updates" => settings.check_for_updates = parse_bool(value),
+            "disable_tray" => settings.disable_tray = parse_bool(value),
+            "auto_reconnect" => settings.auto_reconnect = parse_bool(value),
+            "tray_group_threshold" => settings.tray_group_threshold = value.trim().parse().ok(),
+            "tray_recent_count" => settings.tray_recent_count = value.trim().parse().ok(),
+            "monitor_interval_secs" => settings.monitor_interval_secs = value.trim().parse().ok(),
+            "wait_for_forwards" => settings.wait_for_forwards = parse_bool(value),
+            "established_timeout_secs" => settings.established_timeout_secs = value.trim().parse().ok(),
+            "toggle_hotkey" => settings.toggle_hotkey = parse_string(value),
+            "primary_tunnel" => settings.primary_tunnel = parse_string(value),
+            "yaml_tunnels_file" => settings.yaml_tunnels_file = parse_bool(value),
+            "error_stats" => settings.error_stats = parse_bool(value),
+            "adopt_external_tunnels" => settings.adopt_external_tunnels = parse_bool(value),
+            "notification_timeout_ms" => settings.notification_timeout_ms = value.trim().parse().ok(),
+            "error_notification_timeout_ms" => settings.error_notification_timeout_ms = value.trim().parse().ok(),
+            "log_to_stdout" => settings.log_to_stdout = Some(parse_bool(value)),
+            "locked" => settings.locked = parse_bool(value),
+            _ => {}
+        }
+    }
+    Ok(settings)
+}
+
+/// Parse a boolean setting value (true/yes/on/1)
+fn parse_bool(value: &str) -> bool {
+    matches!(value.trim().to_ascii_lowercase().as_str(), "true" | "yes" | "on" | "1")
+}
+
+/// Parse an optional string setting value (empty means unset)
+fn parse_string(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn log_to_stdout_overrides_the_terminal_check() {
+        let config_file = std::env::temp_dir().join(format!("drill-config-{}-stdout", std::process::id()));
+        fs::write(&config_file, "[drill]\nlog_to_stdout=false\n").unwrap();
+
+        let settings = load_settings(&config_file).unwrap();
+
+        assert_eq!(settings.log_to_stdout, Some(false));
+        assert!(!log_to_stdout(&settings));
+        assert!(log_to_stdout(&Settings { log_to_stdout: Some(true), ..Settings::default() }));
+        let _ = fs::remove_file(&config_file);
+    }
+
+    #[test]
+    fn notification_timeouts_are_read_from_the_config() {
+        let config_file = std::env::temp_dir().join(format!("drill-config-{}-timeouts", std::process::id()));
+        fs::write(&config_file, "[drill]\nnotification_timeout_ms=2500\nerror_notification_timeout_ms=oops\n").unwrap();
+
+        let settings = load_settings(&config_file).unwrap();
+
+        assert_eq!(settings.notification_timeout_ms, Some(2500));
+        assert_eq!(settings.error_notification_timeout_ms, None);
+        let _ = fs::remove_file(&config_file);
+    }
+
+    #[test]
+    fn run_dir_is_under_the_drill_dir() {
+        let run_dir = get_run_dir().unwrap();
+        assert!(run_dir.ends_with(".drill/run"), "{}", run_dir.display());
+    }
+
+    #[test]
+    fn stale_runtime_files_are_removed() {
+        let base = std::env::temp_dir().join(format!("drill-config-{}-run", std::process::id()));
+        let run_dir = base.join("run");
+        let outside = base.join("outside");
+        fs::create_dir_all(run_dir.join("sockets")).unwrap();
+        fs::create_dir_all(&outside).unwrap();
+        fs::write(run_dir.join("drill.pid"), "42").unwrap();
+        fs::write(run_dir.join("sockets").join("web.sock"), "").unwrap();
+        fs::write(outside.join("keep"), "").unwrap();
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&outside, run_dir.join("link")).unwrap();
+        let log_path = base.join("drill.log");
+        let mut logger = Logger::new(fs::File::create(&log_path).unwrap(), log_path.clone());
+
+        let removed = clean_run_dir(&run_dir, &mut logger).unwrap();
+
+        assert_eq!(removed, if cfg!(unix) { 3 } else { 2 });
+        assert_eq!(fs::read_dir(&run_dir).unwrap().count(), 0);
+        // A link is removed, not what it points to
+        assert!(outside.join("keep").exists());
+        let _ = fs::remove_dir_all(&base);
+    }
+
+    #[test]
+    fn a_read_only_tunnels_file_means_managed_mode() {
+        let tunnels_file = std::env::temp_dir().join(format!("drill-config-{}-managed.json", std::process::id()));
+        assert!(!tunnels_file_read_only(&tunnels_file));
+        fs::write(&tunnels_file, "[]").unwrap();
+        assert!(!tunnels_file_read_only(&tunnels_file));
+
+        let mut permissions = fs::metadata(&tunnels_file).unwrap().permissions();
+        permissions.set_readonly(true);
+        fs::set_permissions(&tunnels_file, permissions.clone()).unwrap();
+        assert!(tunnels_file_read_only(&tunnels_file));
+
+        #[allow(clippy::permissions_set_readonly_false)]
+        permissions.set_readonly(false);
+        fs::set_permissions(&tunnels_file, permissions).unwrap();
+        let _ = fs::remove_file(&tunnels_file);
+    }
+
+    #[test]
+    fn locked_is_read_from_the_config() {
+        let config_file = std::env::temp_dir().join(format!("drill-config-{}-locked", std::process::id()));
+        fs::write(&config_file, "[drill]\nlocked=true\n").unwrap();
+
+        assert!(load_settings(&config_file).unwrap().locked);
+        let _ = fs::remove_file(&config_file);
+    }
+}
@@ -0,0 +1,196 @@
+use tokio::sync::broadcast;
+use crate::config;
+use crate::error_stats::ErrorStats;
+use crate::events::EventStore;
+use crate::health::{self, HealthReport};
+use crate::error::DrillResult;
+use crate::logs::Logger;
+use crate::tunnels::{monitor_interval, SaveRetry, StatusUpdate, Tunnel, TunnelManager};
+
+/// Run Drill headless, without tray or windows
+/// Starts the tunnels flagged `auto_start` and logs their status updates
+/// until SIGINT/SIGTERM is received, then stops every tunnel before returning.
+/// With `status_line`, the terminal shows a one-line summary instead of the log stream.
+pub fn run(status_line: bool) -> DrillResult<()> {
+    let started_at = std::time::Instant::now();
+    let (config_path, run_dir, mut logger) = config::init_config()?;
+    logger.set_echo_stdout(!status_line);
+    logger.log_print("Drill - running in daemon mode");
+    logger.log_print(&format!("Configuration loaded from: {}", config_path.display()));
+    logger.log_print(&format!("Runtime directory: {}", run_dir.display()));
+
+    let settings = match config::load_settings(&config_path) {
+        Ok(settings) => settings,
+        Err(e) => {
+            logger.log_print(&format!("Error loading settings, using defaults: {}", e));
+            config::Settings::default()
+        }
+    };
+    logger.set_echo_stdout(!status_line && config::log_to_stdout(&settings));
+
+    let tunnels_file = config::get_tunnels_file_path()?;
+    let tunnels = TunnelManager::load_tunnels(&tunnels_file)?;
+    let mut settings = settings;
+    if !settings.locked && config::tunnels_file_read_only(&tunnels_file) {
+        logger.log_print("Tunnels file is read-only, running in managed mode");
+        settings.locked = true;
+    }
+
+    let mut tunnel_manager = TunnelManager::new();
+    tunnel_manager.set_tunnels(tunnels);
+    tunnel_manager.set_settings(settings);
+    match logger.try_clone() {
+        Ok(ssh_logger) => tunnel_manager.set_logger(ssh_logger),
+        Err(e) => logger.log_print(&format!("Could not share log file with tunnels: {}", e)),
+    }
+    for problem in tunnel_manager.refresh_inventories().1 {
+        logger.log_print(&problem);
+    }
+    let mut event_store = match config::get_events_file_path().and_then(EventStore::open) {
+        Ok(store) => Some(store),
+        Err(e) => {
+            logger.log_print(&format!("Error opening event log: {}", e));
+            None
+        }
+    };
+    let mut error_stats = if tunnel_manager.settings().error_stats {
+        match config::get_error_stats_file_path().and_then(ErrorStats::open) {
+            Ok(stats) => Some(stats),
+            Err(e) => {
+                logger.log_print(&format!("Error opening error summary: {}", e));
+                None
+            }
+        }
+    } else {
+        None
+    };
+    let health_file = config::get_health_file_path()?;
+    let (status_tx, mut status_rx) = broadcast::channel(100);
+    tunnel_manager.set_status_channel(status_tx);
+
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()?;
+
+    // Start the tunnels flagged for automatic start
+    let auto_start: Vec<Tunnel> = tunnel_manager
+        .get_tunnels()
+        .iter()
+        .filter(|t| t.auto_start && !t.is_inventory_template())
+        .cloned()
+        .collect();
+    if auto_start.is_empty() {
+        logger.log_print("No tunnels flagged auto_start, nothing to start");
+    }
+    for tunnel in &auto_start {
+        if let Err(e) = tunnel_manager.start_tunnel(tunnel, None) {
+            logger.log_print(&format!("Error starting tunnel '{}': {}", tunnel.name, e));
+        }
+    }
+
+    runtime.block_on(async {
+        let shutdown = shutdown_signal();
+        tokio::pin!(shutdown);
+        let mut monitor_interval = tokio::time::interval(monitor_interval(tunnel_manager.settings()));
+        let mut save_retry = SaveRetry::default();
+        loop {
+            tokio::select! {
+                _ = &mut shutdown => {
+                    logger.log_print("Shutdown signal received");
+                    break;
+                }
+                _ = monitor_interval.tick() => {
+                    tunnel_manager.monitor_tunnels();
+                    match save_retry.retry(&tunnels_file, tunnel_manager.get_tunnels(), tunnel_manager.settings()) {
+                        Some(Ok(())) => logger.log_print("Saved tunnels on retry"),
+                        Some(Err(e)) => logger.log_print(&format!("Error saving tunnels: {}", e)),
+                        None => {}
+                    }
+                    for problem in tunnel_manager.refresh_inventories().1 {
+                        logger.log_print(&problem);
+                    }
+                    for tunnel_name in tunnel_manager.stop_expired_tunnels() {
+                        logger.log_print(&format!("Tunnel '{}' reached its time limit, disconnected", tunnel_name));
+                    }
+                    if status_line {
+                        crate::status_line::print(&tunnel_manager.status_snapshot());
+                    }
+                    if let Err(e) = HealthReport::collect(&tunnel_manager, started_at).write(&health_file) {
+                        logger.log_print(&format!("Error writing health report: {}", e));
+                    }
+                }
+                update = status_rx.recv() => match update {
+                    Ok(update) => {
+                        log_status_update(&mut logger, &update);
+                        if status_line {
+                            crate::status_line::print(&tunnel_manager.status_snapshot());
+                        }
+                        if let Some(store) = event_store.as_mut() {
+                            if let Err(e) = store.record(&update) {
+                                logger.log_print(&format!("Error recording event: {}", e));
+                            }
+                        }
+                        if let Some(stats) = error_stats.as_mut() {
+                            if let Err(e) = stats.record(&update, tunnel_manager.get_tunnels()) {
+                                logger.log_print(&format!("Error updating error summary: {}", e));
+                            }
+                        }
+                        // Persist the last connected timestamp (managed tunnels are never written)
+                        if matches!(update, StatusUpdate::Connected(_)) && !tunnel_manager.settings().locked && tunnel_manager.connect_times_due() {
+                            if let Err(e) = save_retry.save(&tunnels_file, tunnel_manager.get_tunnels(), tunnel_manager.settings()) {
+                                logger.log_print(&format!("Error saving tunnels: {}", e));
+                            }
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        logger.log_print(&format!("Missed {} status update(s)", skipped));
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                },
+            }
+        }
+    });
+
+    tunnel_manager.cleanup();
+    health::remove_report(&health_file);
+    if status_line {
+        crate::status_line::finish();
+    }
+    logger.log_print("All tunnels stopped, exiting daemon");
+    Ok(())
+}
+
+/// Write a status update to the log
+fn log_status_update(logger: &mut Logger, update: &StatusUpdate) {
+    match update {
+        StatusUpdate::Connecting(name) => logger.log_print(&format!("Tunnel '{}' is connecting...", name)),
+        StatusUpdate::Connected(name) => logger.log_print(&format!("Tunnel '{}' connected", name)),
+        StatusUpdate::Error(name, error) => logger.log_print(&format!("Tunnel '{}' error: {}", name, error)),
+        StatusUpdate::Disconnected(name) => logger.log_print(&format!("Tunnel '{}' disconnected", name)),
+        StatusUpdate::Reconnecting(name, attempt) => logger.log_print(&format!("Tunnel '{}' reconnecting (attempt {})", name, attempt)),
+    }
+}
+
+/// Wait for SIGINT or SIGTERM (Ctrl+C on Windows)
+pub async fn shutdown_signal() {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+        match signal(SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = sigterm.recv() => {}
+                }
+            }
+            Err(_) => {
+                let _ = tokio::signal::ctrl_c().await;
+            }
+        }
+    }
+
+    #[cfg(not(unix))]
+    {
+        let _ = tokio::signal::ctrl_c().await;
+    }
+}
@@ -4,10 +4,22 @@ impl std::fmt::Display for DrillError {
         match self {
             DrillError::Io(e) => write!(f, "IO error: {}", e),
             DrillError::Yaml(e) => write!(f, "YAML error: {}", e),
+            DrillError::Json(e) => write!(f, "JSON error: {}", e),
             DrillError::SshProcess(s) => write!(f, "SSH process error: {}", s),
+            DrillError::UnknownHostKey(host) => write!(
+                f,
+                "Unknown host key for {}: add the host to known_hosts (e.g. connect once with ssh) or set StrictHostKeyChecking=accept-new",
+                host
+            ),
+            DrillError::TooManyAuthFailures(host) => write!(
+                f,
+                "Too many authentication failures on {}: other keys (e.g. from ssh-agent) were tried first; enable \"Use only this key\" (IdentitiesOnly=yes)",
+                host
+            ),
             DrillError::Tunnel(s) => write!(f, "Tunnel error: {}", s),
             DrillError::Config(s) => write!(f, "Config error: {}", s),
             DrillError::Notification(s) => write!(f, "Notification error: {}", s),
+            DrillError::Transient(s) => write!(f, "Temporary error: {}", s),
         }
     }
 }
@@ -26,16 +38,29 @@ impl From<serde_yaml::Error> for DrillError {
     }
 }
 
+impl From<serde_json::Error> for DrillError {
+    fn from(e: serde_json::Error) -> Self {
+        DrillError::Json(e)
+    }
+}
+
 pub type DrillResult<T> = Result<T, DrillError>;
 
 #[derive(Debug)]
 pub enum DrillError {
     Io(std::io::Error),
     Yaml(serde_yaml::Error),
+    Json(serde_json::Error),
     SshProcess(String),
+    /// The ssh server's host key is not in known_hosts
+    UnknownHostKey(String),
+    /// The server gave up before the tunnel's key was offered
+    TooManyAuthFailures(String),
     Tunnel(String),
     Config(String),
     Notification(String),
+    /// Failed in a way that may go away when retried (see `SaveRetry`)
+    Transient(String),
     // Unknown(String),
 }
 
@@ -0,0 +1,199 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use crate::error::DrillResult;
+use crate::tunnels::{classify_ssh_line, SshErrorKind, SshLineKind, StatusUpdate, Tunnel};
+
+/// What kind of failure a tunnel error was, as counted in the error summary
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCategory {
+    UnknownHostKey,
+    TooManyAuthFailures,
+    Auth,
+    Timeout,
+    Connection,
+    Dns,
+    PortConflict,
+    Other,
+}
+
+impl ErrorCategory {
+    /// Categorize the message of a tunnel error
+    /// Drill's own messages (timeouts, port conflicts) are checked before ssh's error lines.
+    pub fn of(error: &str) -> Self {
+        if error.contains("timed out") {
+            return ErrorCategory::Timeout;
+        }
+        if error.contains("already used by running tunnel") {
+            return ErrorCategory::PortConflict;
+        }
+        match classify_ssh_line(error) {
+            SshLineKind::Error(SshErrorKind::UnknownHostKey) => ErrorCategory::UnknownHostKey,
+            SshLineKind::Error(SshErrorKind::TooManyAuthFailures) => ErrorCategory::TooManyAuthFailures,
+            SshLineKind::Error(SshErrorKind::Auth) => ErrorCategory::Auth,
+            SshLineKind::Error(SshErrorKind::Connection) => ErrorCategory::Connection,
+            SshLineKind::Error(SshErrorKind::Dns) => ErrorCategory::Dns,
+            SshLineKind::Error(SshErrorKind::Port) => ErrorCategory::PortConflict,
+            _ => ErrorCategory::Other,
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ErrorCategory::UnknownHostKey => "Unknown host key",
+            ErrorCategory::TooManyAuthFailures => "Too many auth failures",
+            ErrorCategory::Auth => "Authentication",
+            ErrorCategory::Timeout => "Timeout",
+            ErrorCategory::Connection => "Connection",
+            ErrorCategory::Dns => "DNS",
+            ErrorCategory::PortConflict => "Port conflict",
+            ErrorCategory::Other => "Other",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Running count of tunnel errors by category, overall and per ssh host
+/// Only categories and counts are kept, never error messages. Stored as
+/// JSON (~/.drill/error_stats.json) and rewritten on every recorded error.
+#[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct ErrorStats {
+    #[serde(skip)]
+    path: PathBuf,
+    /// When counting started (the first error, or the last reset)
+    #[serde(default)]
+    pub since: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub by_category: BTreeMap<ErrorCategory, u64>,
+    #[serde(default)]
+    pub by_host: BTreeMap<String, BTreeMap<ErrorCategory, u64>>,
+}
+
+impl ErrorStats {
+    /// Load the stats file, starting empty when it doesn't exist yet
+    pub fn open(path: PathBuf) -> DrillResult<Self> {
+        let mut stats: ErrorStats = if path.exists() {
+            serde_json::from_str(&fs::read_to_string(&path)?)?
+        } else {
+            ErrorStats::default()
+        };
+        stats.path = path;
+        Ok(stats)
+    }
+
+    /// Count a status update if it is an error
+    pub fn record(&mut self, update: &StatusUpdate, tunnels: &[Tunnel]) -> DrillResult<()> {
+        let StatusUpdate::Error(tunnel_name, error) = update else {
+            return Ok(());
+        };
+        let host = tunnels
+            .iter()
+            .find(|t| &t.name == tunnel_name)
+            .map_or_else(|| "(unknown)".to_string(), |t| t.ssh_host.clone());
+        self.increment(&host, ErrorCategory::of(error));
+        self.save()
+    }
+
+    fn increment(&mut self, host: &str, category: ErrorCategory) {
+        self.since.get_or_insert_with(chrono::Utc::now);
+        *self.by_category.entry(category).or_default() += 1;
+        *self.by_host.entry(host.to_string()).or_default().entry(category).or_default() += 1;
+    }
+
+    /// Forget every count
+    pub fn reset(&mut self) -> DrillResult<()> {
+        self.since = None;
+        self.by_category.clear();
+        self.by_host.clear();
+        self.save()
+    }
+
+    fn save(&self) -> DrillResult<()> {
+        fs::write(&self.path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Plain-text summary for the error summary window
+    pub fn summary(&self) -> String {
+        let Some(since) = self.since else {
+            return "No errors recorded.".to_string();
+        };
+        let mut summary = format!("Since {}\n\nBy type:\n", since.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M"));
+        for (category, count) in &self.by_category {
+            summary.push_str(&format!("  {:<24}{}\n", category.to_string(), count));
+        }
+        summary.push_str("\nBy host:\n");
+        for (host, counts) in &self.by_host {
+            let counts: Vec<String> = counts.iter().map(|(category, count)| format!("{} {}", category, count)).collect();
+            summary.push_str(&format!("  {}: {}\n", host, counts.join(", ")));
+        }
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::test_tunnel;
+
+    fn stats_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("drill-error-stats-{}-{}.json", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn error(tunnel_name: &str, message: &str) -> StatusUpdate {
+        StatusUpdate::Error(tunnel_name.to_string(), message.to_string())
+    }
+
+    #[test]
+    fn errors_are_categorized_from_their_message() {
+        assert_eq!(ErrorCategory::of("SSH error: Connection attempt timed out"), ErrorCategory::Timeout);
+        assert_eq!(ErrorCategory::of("Tunnel error: Local port 8080 is already used by running tunnel 'web'"), ErrorCategory::PortConflict);
+        assert_eq!(ErrorCategory::of("user@example.com: Permission denied (publickey)."), ErrorCategory::Auth);
+        assert_eq!(ErrorCategory::of("ssh: Could not resolve hostname nowhere: Name or service not known"), ErrorCategory::Dns);
+        assert_eq!(ErrorCategory::of("bind [127.0.0.1]:8080: Address already in use"), ErrorCategory::PortConflict);
+        assert_eq!(ErrorCategory::of("Process exited with status: 1"), ErrorCategory::Other);
+    }
+
+    #[test]
+    fn recorded_errors_are_counted_and_persisted() {
+        let path = stats_path("persist");
+        let tunnels = vec![test_tunnel("web", "18080")];
+        let mut stats = ErrorStats::open(path.clone()).unwrap();
+        assert_eq!(stats.summary(), "No errors recorded.");
+
+        stats.record(&error("web", "user@example.com: Permission denied (publickey)."), &tunnels).unwrap();
+        stats.record(&error("web", "Connection attempt timed out"), &tunnels).unwrap();
+        stats.record(&error("gone", "Connection attempt timed out"), &tunnels).unwrap();
+        // Other updates aren't errors
+        stats.record(&StatusUpdate::Connected("web".to_string()), &tunnels).unwrap();
+
+        let loaded = ErrorStats::open(path.clone()).unwrap();
+        assert!(loaded.since.is_some());
+        assert_eq!(loaded.by_category.get(&ErrorCategory::Timeout), Some(&2));
+        assert_eq!(loaded.by_category.get(&ErrorCategory::Auth), Some(&1));
+        assert_eq!(loaded.by_host["example.com"].get(&ErrorCategory::Timeout), Some(&1));
+        assert_eq!(loaded.by_host["(unknown)"].get(&ErrorCategory::Timeout), Some(&1));
+        // Counts only: the error messages are not stored
+        assert!(!fs::read_to_string(&path).unwrap().contains("Permission denied"));
+        assert!(loaded.summary().contains("example.com: Authentication 1, Timeout 1"));
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn reset_forgets_every_count() {
+        let path = stats_path("reset");
+        let mut stats = ErrorStats::open(path.clone()).unwrap();
+        stats.record(&error("web", "Connection attempt timed out"), &[]).unwrap();
+
+        stats.reset().unwrap();
+
+        let loaded = ErrorStats::open(path.clone()).unwrap();
+        assert!(loaded.since.is_none() && loaded.by_category.is_empty() && loaded.by_host.is_empty());
+        let _ = fs::remove_file(&path);
+    }
+}
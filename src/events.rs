@@ -0,0 +1,212 @@
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use chrono::{DateTime, Local};
+use crate::error::DrillResult;
+use crate::tunnels::StatusUpdate;
+
+/// Maximum number of events kept on disk and in memory
+const MAX_EVENTS: usize = 5000;
+
+/// Kind of status change recorded in the event log
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventKind {
+    Connecting,
+    Connected,
+    Error,
+    Disconnected,
+    Reconnecting,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 5] = [
+        EventKind::Connecting,
+        EventKind::Connected,
+        EventKind::Error,
+        EventKind::Disconnected,
+        EventKind::Reconnecting,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventKind::Connecting => "connecting",
+            EventKind::Connected => "connected",
+            EventKind::Error => "error",
+            EventKind::Disconnected => "disconnected",
+            EventKind::Reconnecting => "reconnecting",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        EventKind::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+}
+
+impl std::fmt::Display for EventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+/// A single tunnel status change
+#[derive(Debug, Clone)]
+pub struct ConnectionEvent {
+    pub timestamp: DateTime<Local>,
+    pub tunnel: String,
+    pub kind: EventKind,
+    pub detail: String,
+}
+
+impl ConnectionEvent {
+    fn from_status_update(update: &StatusUpdate) -> Self {
+        let (tunnel, kind, detail) = match update {
+            StatusUpdate::Connecting(name) => (name, EventKind::Connecting, String::new()),
+            StatusUpdate::Connected(name) => (name, EventKind::Connected, String::new()),
+            StatusUpdate::Error(name, error) => (name, EventKind::Error, error.clone()),
+            StatusUpdate::Disconnected(name) => (name, EventKind::Disconnected, String::new()),
+            StatusUpdate::Reconnecting(name, attempt) => (name, EventKind::Reconnecting, format!("attempt {}", attempt)),
+        };
+        ConnectionEvent {
+            timestamp: Local::now(),
+            tunnel: tunnel.clone(),
+            kind,
+            detail,
+        }
+    }
+
+    /// One tab-separated line; tabs and newlines in fields are flattened to spaces
+    fn to_line(&self) -> String {
+        format!(
+            "{}\t{}\t{}\t{}\n",
+            self.timestamp.to_rfc3339(),
+            flatten(&self.tunnel),
+            self.kind.as_str(),
+            flatten(&self.detail)
+        )
+    }
+
+    fn from_line(line: &str) -> Option<Self> {
+        let mut fields = line.splitn(4, '\t');
+        let timestamp = DateTime::parse_from_rfc3339(fields.next()?).ok()?.with_timezone(&Local);
+        let tunnel = fields.next()?.to_string();
+        let kind = EventKind::parse(fields.next()?)?;
+        let detail = fields.next().unwrap_or_default().to_string();
+        Some(ConnectionEvent { timestamp, tunnel, kind, detail })
+    }
+}
+
+/// Filters applied when querying the event log
+#[derive(Debug, Clone, Default)]
+pub struct EventQuery {
+    /// Case-insensitive text matched against tunnel name and detail
+    pub search: String,
+    pub tunnel: Option<String>,
+    pub kind: Option<EventKind>,
+}
+
+/// Append-only store of status-change events across all tunnels
+pub struct EventStore {
+    path: PathBuf,
+    /// Oldest first, as written to disk
+    events: Vec<ConnectionEvent>,
+}
+
+impl EventStore {
+    /// Load the event log, trimming it to the most recent `MAX_EVENTS`
+    pub fn open(path: PathBuf) -> DrillResult<Self> {
+        let mut events: Vec<ConnectionEvent> = if path.exists() {
+            fs::read_to_string(&path)?
+                .lines()
+                .filter_map(ConnectionEvent::from_line)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut store_needs_rewrite = false;
+        if events.len() > MAX_EVENTS {
+            events.drain(..events.len() - MAX_EVENTS);
+            store_needs_rewrite = true;
+        }
+
+        let mut store = EventStore { path, events };
+        if store_needs_rewrite {
+            store.rewrite()?;
+        }
+        Ok(store)
+    }
+
+    /// Append a status update to the log
+    pub fn record(&mut self, update: &StatusUpdate) -> DrillResult<()> {
+        let event = ConnectionEvent::from_status_update(update);
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        file.write_all(event.to_line().as_bytes())?;
+        self.events.push(event);
+
+        // Compact once the log has grown well past its bound, not on every write
+        if self.events.len() > MAX_EVENTS + MAX_EVENTS / 10 {
+            self.events.drain(..self.events.len() - MAX_EVENTS);
+            self.rewrite()?;
+        }
+        Ok(())
+    }
+
+    /// Get the events matching `query`, newest first
+    pub fn query(&self, query: &EventQuery) -> Vec<&ConnectionEvent> {
+        let search = query.search.trim().to_lowercase();
+        self.events
+            .iter()
+            .rev()
+            .filter(|event| query.tunnel.as_ref().is_none_or(|tunnel| &event.tunnel == tunnel))
+            .filter(|event| query.kind.is_none_or(|kind| event.kind == kind))
+            .filter(|event| {
+                search.is_empty()
+                    || event.tunnel.to_lowercase().contains(&search)
+                    || event.detail.to_lowercase().contains(&search)
+            })
+            .collect()
+    }
+
+    /// Get the distinct tunnel names present in the log, sorted
+    pub fn tunnel_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.events.iter().map(|event| event.tunnel.clone()).collect();
+        names.sort();
+        names.dedup();
+        names
+    }
+
+    /// Replace the file with the events currently in memory
+    fn rewrite(&mut self) -> DrillResult<()> {
+        let content: String = self.events.iter().map(ConnectionEvent::to_line).collect();
+        fs::write(&self.path, content)?;
+        Ok(())
+    }
+}
+
+/// Write events to a CSV file
+pub fn export_csv(path: &Path, events: &[&ConnectionEvent]) -> DrillResult<()> {
+    let mut content = String::from("timestamp,tunnel,event,detail\n");
+    for event in events {
+        content.push_str(&format!(
+            "{},{},{},{}\n",
+            csv_field(&event.timestamp.to_rfc3339()),
+            csv_field(&event.tunnel),
+            event.kind.as_str(),
+            csv_field(&event.detail)
+        ));
+    }
+    fs::write(path, content)?;
+    Ok(())
+}
+
+fn flatten(value: &str) -> String {
+    value.replace(['\t', '\n', '\r'], " ")
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
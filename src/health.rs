@@ -0,0 +1,204 @@
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use crate::error::{DrillResult, DrillError};
+use crate::tunnels::{monitor_interval, TunnelManager, TunnelStatus, MONITOR_INTERVAL};
+
+/// Version of the report layout, bumped on any incompatible change
+/// Fields may be added without a bump; consumers should ignore unknown ones.
+pub const HEALTH_REPORT_VERSION: u32 = 1;
+
+/// A report older than this many monitor intervals means the instance that wrote it is gone
+const STALE_AFTER_INTERVALS: u32 = 3;
+
+/// Overall health of a running Drill instance
+/// Written to ~/.drill/health.json on every monitor tick and printed by `drill --health`.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct HealthReport {
+    pub version: u32,
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    pub pid: u32,
+    pub uptime_secs: u64,
+    /// Seconds between report updates (older reports lack it and used the default)
+    #[serde(default)]
+    pub monitor_interval_secs: u64,
+    pub counts: StatusCounts,
+    pub errored: Vec<ErroredTunnel>,
+    pub active: Vec<ActiveTunnelHealth>,
+}
+
+/// Number of tunnels in each status
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub struct StatusCounts {
+    pub total: usize,
+    pub connected: usize,
+    pub connecting: usize,
+    pub reconnecting: usize,
+    pub error: usize,
+    pub disconnected: usize,
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ErroredTunnel {
+    pub name: String,
+    pub error: String,
+}
+
+/// I/O estimate of a running tunnel (only where the platform exposes it, i.e. Linux)
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ActiveTunnelHealth {
+    pub name: String,
+    pub uptime_secs: u64,
+    /// Bytes read and written by the whole ssh process (see `TunnelStats`)
+    pub bytes_in: u64,
+    pub bytes_out: u64,
+}
+
+impl HealthReport {
+    /// Build the report from the current tunnel states
+    pub fn collect(manager: &TunnelManager, started_at: Instant) -> Self {
+        let mut counts = StatusCounts::default();
+        let mut errored = Vec::new();
+        for (name, status) in manager.status_snapshot() {
+            counts.total += 1;
+            match status {
+                TunnelStatus::Connected { .. } | TunnelStatus::External => counts.connected += 1,
+                TunnelStatus::Connecting => counts.connecting += 1,
+                TunnelStatus::Reconnecting { .. } => counts.reconnecting += 1,
+                TunnelStatus::Disconnected => counts.disconnected += 1,
+                TunnelStatus::Error { error, .. } => {
+                    counts.error += 1;
+                    errored.push(ErroredTunnel { name, error });
+                }
+            }
+        }
+        let mut active: Vec<ActiveTunnelHealth> = manager
+            .stats_snapshot()
+            .into_iter()
+            .map(|(name, stats)| ActiveTunnelHealth {
+                name,
+                uptime_secs: stats.uptime.as_secs(),
+                bytes_in: stats.bytes_in,
+                bytes_out: stats.bytes_out,
+            })
+            .collect();
+        active.sort_by(|a, b| a.name.cmp(&b.name));
+
+        HealthReport {
+            version: HEALTH_REPORT_VERSION,
+            generated_at: chrono::Utc::now(),
+            pid: std::process::id(),
+            uptime_secs: started_at.elapsed().as_secs(),
+            monitor_interval_secs: monitor_interval(manager.settings()).as_secs(),
+            counts,
+            errored,
+            active,
+        }
+    }
+
+    /// Check whether the instance stopped updating the report
+    pub fn is_stale(&self) -> bool {
+        let age = chrono::Utc::now().signed_duration_since(self.generated_at);
+        let interval = Duration::from_secs(self.monitor_interval_secs).max(MONITOR_INTERVAL);
+        age.to_std().map(|age| age > interval * STALE_AFTER_INTERVALS).unwrap_or(false)
+    }
+
+    /// Write the report, replacing the previous one atomically
+    pub fn write(&self, path: &Path) -> DrillResult<()> {
+        let content = serde_json::to_string_pretty(self)?;
+        let tmp_path = path.with_extension("json.tmp");
+        fs::write(&tmp_path, content)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Read the report written by the running instance
+    pub fn read(path: &Path) -> DrillResult<Self> {
+        if !path.exists() {
+            return Err(DrillError::Config("No health report found, is Drill running?".to_string()));
+        }
+        let content = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&content)?)
+    }
+}
+
+/// Remove the report when the instance exits, so it isn't mistaken for a live one
+pub fn remove_report(path: &Path) {
+    let _ = fs::remove_file(path);
+}
+
+/// Print the running instance's report for `drill --health`
+/// Returns the process exit code: 0 when a live report was printed, 1 otherwise.
+pub fn print_report() -> i32 {
+    let report = match crate::config::get_health_file_path().and_then(|path| HealthReport::read(&path)) {
+        Ok(report) => report,
+        Err(e) => {
+            eprintln!("{}", e);
+            return 1;
+        }
+    };
+    match serde_json::to_string_pretty(&report) {
+        Ok(json) => println!("{}", json),
+        Err(e) => {
+            eprintln!("Error formatting health report: {}", e);
+            return 1;
+        }
+    }
+    if report.is_stale() {
+        eprintln!("Health report is stale, Drill (pid {}) is probably not running", report.pid);
+        return 1;
+    }
+    0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::MockRunner;
+    use crate::tunnels::test_tunnel;
+    use std::sync::Arc;
+
+    #[test]
+    fn report_serializes_to_the_documented_shape() {
+        let mut manager = TunnelManager::new();
+        manager.set_command_runner(Arc::new(MockRunner::exiting(255, "user@example.com: Permission denied (publickey).\n")));
+        let web = test_tunnel("web", "18140");
+        manager.set_tunnels(vec![web.clone(), test_tunnel("db", "18141")]);
+        let _ = manager.start_tunnel(&web, None);
+
+        let report = HealthReport::collect(&manager, Instant::now());
+        let json = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["version"], HEALTH_REPORT_VERSION);
+        assert_eq!(json["counts"]["total"], 2);
+        assert_eq!(json["counts"]["error"], 1);
+        assert_eq!(json["counts"]["disconnected"], 1);
+        assert_eq!(json["errored"][0]["name"], "web");
+        assert!(json["errored"][0]["error"].as_str().unwrap().contains("Permission denied"));
+        for key in ["generated_at", "pid", "uptime_secs", "monitor_interval_secs", "active"] {
+            assert!(json.get(key).is_some(), "missing {}", key);
+        }
+    }
+
+    #[test]
+    fn report_round_trips_through_the_file() {
+        let path = std::env::temp_dir().join(format!("drill-health-{}.json", std::process::id()));
+        let report = HealthReport::collect(&TunnelManager::new(), Instant::now());
+
+        report.write(&path).unwrap();
+        let read = HealthReport::read(&path);
+        remove_report(&path);
+
+        let read = read.unwrap();
+        assert_eq!(read.pid, std::process::id());
+        assert!(!read.is_stale());
+    }
+
+    #[test]
+    fn old_report_is_stale() {
+        let mut report = HealthReport::collect(&TunnelManager::new(), Instant::now());
+        report.generated_at -= chrono::Duration::hours(1);
+
+        assert!(report.is_stale());
+    }
+}
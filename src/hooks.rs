@@ -0,0 +1,63 @@
+use std::process::{Command, Stdio};
+use crate::logs::Logger;
+use crate::tunnels::StatusUpdate;
+
+/// Run the user's status hook script for a status update
+/// The script gets DRILL_TUNNEL, DRILL_EVENT and DRILL_STATUS in its environment.
+/// It is spawned detached so it can't block the UI; a watcher thread reaps it
+/// and logs non-zero exits.
+pub fn run_status_hook(script: &str, update: &StatusUpdate, logger: &mut Logger) {
+    let (tunnel_name, event, status) = match update {
+        StatusUpdate::Connecting(name) => (name, "connecting", "Connecting".to_string()),
+        StatusUpdate::Connected(name) => (name, "connected", "Connected".to_string()),
+        StatusUpdate::Error(name, error) => (name, "error", format!("Error: {}", error)),
+        StatusUpdate::Disconnected(name) => (name, "disconnected", "Disconnected".to_string()),
+        StatusUpdate::Reconnecting(name, attempt) => (name, "reconnecting", format!("Reconnecting (attempt {})", attempt)),
+    };
+
+    let mut command = Command::new(script);
+    command
+        .env("DRILL_TUNNEL", tunnel_name)
+        .env("DRILL_EVENT", event)
+        .env("DRILL_STATUS", &status)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null());
+
+    // On Windows, suppress terminal window
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    match command.spawn() {
+        Ok(mut child) => {
+            let mut watcher_logger = logger.try_clone().ok();
+            let script = script.to_string();
+            let event = event.to_string();
+            std::thread::spawn(move || {
+                let result = child.wait();
+                let Some(logger) = watcher_logger.as_mut() else {
+                    return;
+                };
+                match result {
+                    Ok(exit_status) if !exit_status.success() => {
+                        logger.log_print(&format!(
+                            "Status hook '{}' for event '{}' exited with {}",
+                            script, event, exit_status
+                        ));
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        logger.log_print(&format!("Error waiting for status hook '{}': {}", script, e));
+                    }
+                }
+            });
+        }
+        Err(e) => {
+            logger.log_print(&format!("Error running status hook '{}': {}", script, e));
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use global_hotkey::hotkey::HotKey;
+use global_hotkey::GlobalHotKeyManager;
+use crate::config::Settings;
+use crate::error::{DrillResult, DrillError};
+use crate::tunnels::Tunnel;
+
+/// Register the global hotkey that toggles the primary tunnel
+/// `spec` is e.g. "CmdOrCtrl+Shift+D". Returns the manager, which must be kept
+/// alive for the hotkey to stay registered, and the id its events carry.
+pub fn register_toggle_hotkey(spec: &str) -> DrillResult<(GlobalHotKeyManager, u32)> {
+    let hotkey: HotKey = spec
+        .parse()
+        .map_err(|e| DrillError::Config(format!("Invalid toggle_hotkey '{}': {}", spec, e)))?;
+    let manager = GlobalHotKeyManager::new()
+        .map_err(|e| DrillError::Config(format!("Global hotkeys are not available: {}", e)))?;
+    manager
+        .register(hotkey)
+        .map_err(|e| DrillError::Config(format!("Could not register '{}': {}", spec, e)))?;
+    Ok((manager, hotkey.id()))
+}
+
+/// Get the tunnel the hotkey toggles
+/// The `primary_tunnel` setting if it names an existing tunnel, otherwise the
+/// most recently connected tunnel when none is configured.
+pub fn primary_tunnel<'a>(tunnels: &'a [Tunnel], settings: &Settings) -> Option<&'a Tunnel> {
+    match &settings.primary_tunnel {
+        Some(name) => tunnels.iter().find(|t| &t.name == name),
+        None => tunnels
+            .iter()
+            .filter(|t| t.last_connected_at.is_some())
+            .max_by_key(|t| t.last_connected_at),
+    }
+}
@@ -0,0 +1,239 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::net::{Ipv4Addr, Shutdown, SocketAddr, TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use crate::error::{DrillResult, DrillError};
+
+/// Sent back by the running instance, so an unrelated program that took over
+/// the port of a crashed instance isn't mistaken for Drill
+const HANDSHAKE: &str = "drill";
+
+/// How long to wait for the other side when handing arguments over
+const FORWARD_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Age below which a lock file without a readable port is taken for one still
+/// being put in place, rather than a damaged leftover
+const FRESH_LOCK: Duration = Duration::from_secs(5);
+
+/// Pause before looking at a fresh lock file again
+const LOCK_RETRY_DELAY: Duration = Duration::from_millis(200);
+
+/// Attempts at taking the lock, enough to outlast `FRESH_LOCK`
+const LOCK_ATTEMPTS: u32 = 40;
+
+/// Held by the only running Drill instance
+/// The lock file records a loopback port this instance listens on: a second
+/// launch that finds the file connects to it, which both proves the holder is
+/// alive (a file left by a crash has nobody listening) and hands the launch's
+/// arguments over. The file is removed when the lock is dropped.
+pub struct InstanceLock {
+    path: PathBuf,
+    listener: TcpListener,
+}
+
+/// Outcome of trying to become the running instance
+pub enum Acquired {
+    Locked(InstanceLock),
+    /// Another instance holds the lock and has received our arguments
+    Forwarded,
+    /// Another instance holds the lock but is still starting: our arguments
+    /// wait on its port until it reads them
+    Pending,
+}
+
+/// What was found behind an existing lock file
+enum Holder {
+    /// The instance answered and took the arguments
+    Answered,
+    /// The instance accepted the arguments but didn't answer in time
+    Starting,
+    /// The file doesn't name a port yet
+    Writing,
+    /// Nobody (or not Drill) listens on the recorded port
+    Gone,
+}
+
+/// Take the single-instance lock, or forward `args` to the instance holding it
+pub fn acquire(path: &Path, args: &[String]) -> DrillResult<Acquired> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    let port = listener.local_addr()?.port();
+    // The lock file appears with its port already written: linking a complete
+    // temp file into place fails like create_new if the lock is taken
+    let temp = path.with_extension(format!("{}.tmp", std::process::id()));
+    fs::write(&temp, port.to_string())?;
+    let result = take_lock(path, &temp, args);
+    let _ = fs::remove_file(&temp);
+    Ok(match result? {
+        Some(acquired) => acquired,
+        None => Acquired::Locked(InstanceLock { path: path.to_path_buf(), listener }),
+    })
+}
+
+/// Link `temp` into place as the lock file, or hand `args` to its holder
+/// Returns None once the lock is ours.
+fn take_lock(path: &Path, temp: &Path, args: &[String]) -> DrillResult<Option<Acquired>> {
+    for _ in 0..LOCK_ATTEMPTS {
+        match fs::hard_link(temp, path) {
+            Ok(()) => return Ok(None),
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => match forward_args(path, args) {
+                Holder::Answered => return Ok(Some(Acquired::Forwarded)),
+                Holder::Starting => return Ok(Some(Acquired::Pending)),
+                Holder::Writing => std::thread::sleep(LOCK_RETRY_DELAY),
+                // The holder is gone, a crash left the file behind
+                Holder::Gone => match fs::remove_file(path) {
+                    Ok(()) => {}
+                    Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+                    Err(e) => return Err(e.into()),
+                },
+            },
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Err(DrillError::Config(format!("Could not take the instance lock {}", path.display())))
+}
+
+/// Send the arguments, one per line, to the instance named in the lock file
+fn forward_args(path: &Path, args: &[String]) -> Holder {
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Holder::Gone,
+        Err(_) => return Holder::Writing,
+    };
+    let Ok(port) = content.trim().parse::<u16>() else {
+        return if is_fresh(path) { Holder::Writing } else { Holder::Gone };
+    };
+    let Ok(mut stream) = TcpStream::connect_timeout(&SocketAddr::from((Ipv4Addr::LOCALHOST, port)), FORWARD_TIMEOUT) else {
+        return Holder::Gone;
+    };
+    let sent = stream.set_read_timeout(Some(FORWARD_TIMEOUT))
+        .and_then(|_| stream.write_all(args.join("\n").as_bytes()))
+        .and_then(|_| stream.shutdown(Shutdown::Write));
+    if sent.is_err() {
+        return Holder::Gone;
+    }
+    let mut reply = String::new();
+    match stream.read_to_string(&mut reply) {
+        // The port took the arguments: a holder whose listener thread hasn't started yet
+        Err(e) if matches!(e.kind(), std::io::ErrorKind::WouldBlock | std::io::ErrorKind::TimedOut) => Holder::Starting,
+        Ok(_) if reply == HANDSHAKE => Holder::Answered,
+        // Not Drill on the recorded port
+        _ => Holder::Gone,
+    }
+}
+
+/// Whether the lock file was modified within `FRESH_LOCK`
+fn is_fresh(path: &Path) -> bool {
+    fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age < FRESH_LOCK)
+}
+
+impl InstanceLock {
+    /// Arguments of later launches, one list per launch
+    /// Connections are accepted on a thread of their own for as long as the process runs.
+    pub fn forwarded_args(&self) -> DrillResult<broadcast::Receiver<Vec<String>>> {
+        let listener = self.listener.try_clone()?;
+        let (sender, receiver) = broadcast::channel(10);
+        std::thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                let _ = stream.set_read_timeout(Some(FORWARD_TIMEOUT));
+                let mut received = String::new();
+                if stream.read_to_string(&mut received).is_err() {
+                    continue;
+                }
+                let _ = stream.write_all(HANDSHAKE.as_bytes());
+                let args = received.lines().map(str::to_string).collect();
+                // The app side is gone
+                if sender.send(args).is_err() {
+                    return;
+                }
+            }
+        });
+        Ok(receiver)
+    }
+}
+
+impl Drop for InstanceLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A lock file path of its own for each test
+    fn lock_path(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("drill-instance-{}-{}.lock", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    #[test]
+    fn acquire_takes_the_lock_and_drop_releases_it() {
+        let path = lock_path("release");
+        let Acquired::Locked(lock) = acquire(&path, &[]).unwrap() else {
+            panic!("expected the lock");
+        };
+        assert!(fs::read_to_string(&path).unwrap().trim().parse::<u16>().is_ok());
+
+        drop(lock);
+
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn second_launch_forwards_its_arguments() {
+        let path = lock_path("forward");
+        let Acquired::Locked(lock) = acquire(&path, &[]).unwrap() else {
+            panic!("expected the lock");
+        };
+        let mut receiver = lock.forwarded_args().unwrap();
+
+        let args = vec!["--connect".to_string(), "web".to_string()];
+        assert!(matches!(acquire(&path, &args).unwrap(), Acquired::Forwarded));
+        assert_eq!(receiver.blocking_recv().unwrap(), args);
+    }
+
+    #[test]
+    fn lock_left_by_a_crash_is_taken_over() {
+        let path = lock_path("stale");
+        // Bound and dropped: nobody listens on it any more
+        let port = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap().local_addr().unwrap().port();
+        fs::write(&path, port.to_string()).unwrap();
+
+        assert!(matches!(acquire(&path, &[]).unwrap(), Acquired::Locked(_)));
+    }
+
+    #[test]
+    fn holder_not_answering_yet_is_not_taken_for_stale() {
+        let path = lock_path("starting");
+        let Acquired::Locked(_lock) = acquire(&path, &[]).unwrap() else {
+            panic!("expected the lock");
+        };
+
+        // No forwarded_args thread: the port accepts but nobody answers
+        assert!(matches!(acquire(&path, &[]).unwrap(), Acquired::Pending));
+        assert!(path.exists());
+    }
+
+    #[test]
+    fn fresh_lock_without_a_port_is_busy() {
+        let path = lock_path("writing");
+        fs::write(&path, "").unwrap();
+
+        assert!(matches!(forward_args(&path, &[]), Holder::Writing));
+        let old = std::time::SystemTime::now() - FRESH_LOCK * 2;
+        fs::File::options().write(true).open(&path).unwrap().set_modified(old).unwrap();
+        assert!(matches!(forward_args(&path, &[]), Holder::Gone));
+        let _ = fs::remove_file(&path);
+    }
+}
@@ -0,0 +1,193 @@
+use std::fs;
+use crate::error::{DrillError, DrillResult};
+use crate::tunnels::{local_path, Tunnel};
+
+/// Replaced by each inventory host in a template's name, display name, SSH host and remote host
+pub const HOST_PLACEHOLDER: &str = "{host}";
+
+/// Parse an inventory: one host per line
+/// Blank lines and `#` comments are skipped, as is anything after the host
+/// on its line (e.g. an Ansible-style `web1 ansible_user=x`); repeated hosts count once.
+pub fn parse_inventory(content: &str) -> Vec<String> {
+    let mut hosts: Vec<String> = Vec::new();
+    for line in content.lines() {
+        let line = line.split('#').next().unwrap_or_default();
+        let Some(host) = line.split_whitespace().next() else {
+            continue;
+        };
+        if !hosts.iter().any(|h| h == host) {
+            hosts.push(host.to_string());
+        }
+    }
+    hosts
+}
+
+/// Read the hosts of a template's inventory file
+pub fn read_inventory(inventory_file: &str) -> DrillResult<Vec<String>> {
+    let path = local_path(inventory_file.trim());
+    let content = fs::read_to_string(&path)
+        .map_err(|e| DrillError::Config(format!("Cannot read inventory {}: {}", path.display(), e)))?;
+    Ok(parse_inventory(&content))
+}
+
+/// Id of the tunnel generated from `template_id` for `host`
+/// Derived from both so it stays the same across restarts and inventory
+/// changes (the tunnel's status and history follow the host).
+pub fn generated_id(template_id: &str, host: &str) -> String {
+    format!("{}@{}", template_id, host)
+}
+
+/// Generate the concrete tunnels of a template, one per host
+/// `{host}` is substituted in the name, display name, SSH host and remote host.
+/// A name (or display name) without it gets the host appended, an SSH host
+/// without it is the host.
+pub fn expand_template(template: &Tunnel, hosts: &[String]) -> Vec<Tunnel> {
+    hosts
+        .iter()
+        .map(|host| {
+            let with_host = |label: &str| {
+                if label.contains(HOST_PLACEHOLDER) {
+                    label.replace(HOST_PLACEHOLDER, host)
+                } else {
+                    format!("{} {}", label, host)
+                }
+            };
+            let name = with_host(&template.name);
+            let display_name = match template.display_name.trim() {
+                "" => String::new(),
+                display_name => with_host(display_name),
+            };
+            let ssh_host = if template.ssh_host.contains(HOST_PLACEHOLDER) {
+                template.ssh_host.replace(HOST_PLACEHOLDER, host)
+            } else {
+                host.clone()
+            };
+            Tunnel {
+                id: generated_id(&template.id, host),
+                name,
+                display_name,
+                ssh_host,
+                remote_host: template.remote_host.replace(HOST_PLACEHOLDER, host),
+                last_connected_at: None,
+                inventory_file: String::new(),
+                generated_from: Some(template.id.clone()),
+                ..template.clone()
+            }
+        })
+        .collect()
+}
+
+/// Generate the tunnels of every template in `tunnels`
+/// Generated tunnels already in the list are ignored (they are replaced).
+/// A generated name taken by another tunnel is skipped, so names stay unique.
+/// Returns the tunnels and one message per unreadable inventory or skipped name.
+pub fn expand_inventories(tunnels: &[Tunnel]) -> (Vec<Tunnel>, Vec<String>) {
+    let mut generated: Vec<Tunnel> = Vec::new();
+    let mut problems = Vec::new();
+    let saved: Vec<&Tunnel> = tunnels.iter().filter(|t| t.generated_from.is_none()).collect();
+    for template in saved.iter().filter(|t| t.is_inventory_template()) {
+        let hosts = match read_inventory(&template.inventory_file) {
+            Ok(hosts) => hosts,
+            Err(e) => {
+                problems.push(format!("Template '{}': {}", template.name, e));
+                continue;
+            }
+        };
+        for tunnel in expand_template(template, &hosts) {
+            let taken = saved.iter().any(|t| t.name == tunnel.name) || generated.iter().any(|t| t.name == tunnel.name);
+            if taken {
+                problems.push(format!(
+                    "Template '{}': not generating '{}', a tunnel with that name exists",
+                    template.name, tunnel.name
+                ));
+                continue;
+            }
+            generated.push(tunnel);
+        }
+    }
+    (generated, problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::test_tunnel;
+
+    /// A template for `inventory_file` forwarding local port 18080 to `{host}`:80
+    fn template(inventory_file: &str) -> Tunnel {
+        Tunnel {
+            name: "web {host}".to_string(),
+            ssh_host: "{host}.example.com".to_string(),
+            remote_host: "{host}".to_string(),
+            inventory_file: inventory_file.to_string(),
+            ..test_tunnel("web", "18080")
+        }
+    }
+
+    fn hosts(hosts: &[&str]) -> Vec<String> {
+        hosts.iter().map(|h| h.to_string()).collect()
+    }
+
+    #[test]
+    fn inventory_lines_give_one_host_each() {
+        let content = "# staging\nweb1\n\n  web2 ansible_user=deploy\nweb1\nweb3 # spare\n";
+
+        assert_eq!(parse_inventory(content), ["web1", "web2", "web3"]);
+    }
+
+    #[test]
+    fn templates_expand_one_tunnel_per_host() {
+        let generated = expand_template(&template("hosts.txt"), &hosts(&["web1", "web2"]));
+
+        let summary: Vec<(&str, &str, &str)> = generated
+            .iter()
+            .map(|t| (t.name.as_str(), t.ssh_host.as_str(), t.remote_host.as_str()))
+            .collect();
+        assert_eq!(summary, [("web web1", "web1.example.com", "web1"), ("web web2", "web2.example.com", "web2")]);
+        assert!(generated.iter().all(|t| t.generated_from.as_deref() == Some("web-id") && !t.is_inventory_template()));
+    }
+
+    #[test]
+    fn labels_without_a_placeholder_get_the_host() {
+        let plain = Tunnel { inventory_file: "hosts.txt".to_string(), display_name: "Web".to_string(), ..test_tunnel("web", "18080") };
+
+        let generated = expand_template(&plain, &hosts(&["web1"]));
+
+        assert_eq!(generated[0].name, "web web1");
+        assert_eq!(generated[0].display_name, "Web web1");
+        assert_eq!(generated[0].ssh_host, "web1");
+    }
+
+    #[test]
+    fn generated_ids_depend_only_on_template_and_host() {
+        let template = template("hosts.txt");
+        let before = expand_template(&template, &hosts(&["web1", "web2"]));
+        let after = expand_template(&template, &hosts(&["web0", "web2", "web1"]));
+
+        let id_of = |tunnels: &[Tunnel], host: &str| tunnels.iter().find(|t| t.remote_host == host).unwrap().id.clone();
+        assert_eq!(id_of(&before, "web1"), id_of(&after, "web1"));
+        assert_eq!(id_of(&before, "web2"), "web-id@web2");
+        let renamed = Tunnel { name: "api {host}".to_string(), ..template };
+        assert_eq!(expand_template(&renamed, &hosts(&["web2"]))[0].id, "web-id@web2");
+    }
+
+    #[test]
+    fn expansion_skips_taken_names_and_reports_unreadable_inventories() {
+        let inventory = std::env::temp_dir().join(format!("drill-inventory-{}-hosts", std::process::id()));
+        fs::write(&inventory, "web1\nweb2\n").unwrap();
+        let tunnels = vec![
+            template(&inventory.display().to_string()),
+            test_tunnel("web web2", "18081"),
+            Tunnel { inventory_file: "/nonexistent/drill/hosts".to_string(), ..test_tunnel("db", "18082") },
+        ];
+
+        let (generated, problems) = expand_inventories(&tunnels);
+
+        let names: Vec<&str> = generated.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["web web1"]);
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].contains("not generating 'web web2'"), "{}", problems[0]);
+        assert!(problems[1].starts_with("Template 'db': Cannot read inventory"), "{}", problems[1]);
+        let _ = fs::remove_file(&inventory);
+    }
+}
@@ -1,21 +1,195 @@
 use std::fs::File;
 use std::io::Write;
+use std::path::{Path, PathBuf};
 
 pub struct Logger {
     log_file: File,
+    log_path: PathBuf,
+    echo_stdout: bool,
 }
 
 impl Logger {
-    pub fn new(log_file: File) -> Self {
-        Logger { log_file }
+    pub fn new(log_file: File, log_path: PathBuf) -> Self {
+        Logger { log_file, log_path, echo_stdout: true }
+    }
+
+    /// Create another logger writing to the same log file
+    pub fn try_clone(&self) -> std::io::Result<Self> {
+        Ok(Logger {
+            log_file: self.log_file.try_clone()?,
+            log_path: self.log_path.clone(),
+            echo_stdout: self.echo_stdout,
+        })
+    }
+
+    /// Choose whether messages are also printed to stdout
+    /// Clones made afterwards inherit the choice
+    pub fn set_echo_stdout(&mut self, echo_stdout: bool) {
+        self.echo_stdout = echo_stdout;
+    }
+
+    /// Get the path of the log file being written
+    pub fn path(&self) -> &Path {
+        &self.log_path
     }
 
     pub fn log_print(&mut self, message: &str) {
         // Print to console
-        println!("{}", message);
-        // Write to log file
+        if let Some(message) = self.write_entry(message) {
+            println!("{}", message);
+        }
+    }
+
+    /// Write `message` to the log file, returning it when it should also go to stdout
+    fn write_entry<'m>(&mut self, message: &'m str) -> Option<&'m str> {
         let timestamp = chrono::Local::now().format("%Y-%m-%d %H:%M:%S");
         let log_line = format!("[{}] {}\n", timestamp, message);
         let _ = self.log_file.write_all(log_line.as_bytes());
+        self.echo_stdout.then_some(message)
+    }
+}
+
+/// Read the last `max_lines` lines of a log file
+pub fn read_tail(path: &Path, max_lines: usize) -> std::io::Result<Vec<String>> {
+    let content = std::fs::read_to_string(path)?;
+    let lines: Vec<&str> = content.lines().collect();
+    let start = lines.len().saturating_sub(max_lines);
+    Ok(lines[start..].iter().map(|line| line.to_string()).collect())
+}
+
+/// Whether a logged message is about the tunnel `tunnel_name`
+/// ssh output is prefixed with `[ssh:name]`, app messages quote the name.
+fn mentions_tunnel(message: &str, tunnel_name: &str) -> bool {
+    message.starts_with(&format!("[ssh:{}] ", tunnel_name)) || message.contains(&format!("'{}'", tunnel_name))
+}
+
+/// Keep the lines of a log about `tunnel_name`, logged at or after `since`
+/// Lines are `[%Y-%m-%d %H:%M:%S] message`; a line without a timestamp
+/// continues the previous message and follows its fate.
+pub fn filter_tunnel_lines(content: &str, tunnel_name: &str, since: Option<chrono::NaiveDateTime>) -> Vec<String> {
+    let mut keep = false;
+    content
+        .lines()
+        .filter(|line| {
+            let parsed = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.split_once("] "))
+                .and_then(|(timestamp, message)| {
+                    chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok().map(|at| (at, message))
+                });
+            if let Some((at, message)) = parsed {
+                keep = since.map_or(true, |since| at >= since) && mentions_tunnel(message, tunnel_name);
+            }
+            keep
+        })
+        .map(|line| line.to_string())
+        .collect()
+}
+
+/// Collect a tunnel's lines since `since` from every log file in `logs_dir`
+/// Each run writes its own `drill_<timestamp>.log`, so the file names sort by age.
+pub fn tunnel_log_lines(logs_dir: &Path, tunnel_name: &str, since: Option<chrono::NaiveDateTime>) -> std::io::Result<Vec<String>> {
+    let mut log_files: Vec<PathBuf> = std::fs::read_dir(logs_dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|name| name.to_str()).unwrap_or_default();
+            name.starts_with("drill_") && name.ends_with(".log")
+        })
+        .collect();
+    log_files.sort();
+
+    let mut lines = Vec::new();
+    for log_file in log_files {
+        // A log may hold invalid UTF-8 from ssh output, don't let it abort the export
+        let content = String::from_utf8_lossy(&std::fs::read(&log_file)?).to_string();
+        lines.extend(filter_tunnel_lines(&content, tunnel_name, since));
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LOG: &str = "\
+[2026-03-01 09:00:00] Starting Drill
+[2026-03-01 09:00:01] Tunnel 'web' connecting
+[2026-03-01 09:00:02] [ssh:web] debug1: Connecting to example.com port 22.
+[2026-03-01 09:00:02] [ssh:webapp] debug1: Connecting to app.example.com port 22.
+[2026-03-01 09:30:00] Tunnel 'db' edited: ssh_host: \"a\" → \"b\"
+[2026-03-01 10:00:00] [ssh:web] user@example.com: Permission denied (publickey).
+  continued on the next line
+[2026-03-01 10:00:01] Tunnel 'db' connected
+  a continuation of another tunnel's line
+";
+
+    fn at(timestamp: &str) -> Option<chrono::NaiveDateTime> {
+        chrono::NaiveDateTime::parse_from_str(timestamp, "%Y-%m-%d %H:%M:%S").ok()
+    }
+
+    #[test]
+    fn tunnel_lines_are_picked_by_name() {
+        let lines = filter_tunnel_lines(LOG, "web", None);
+
+        assert_eq!(
+            lines,
+            vec![
+                "[2026-03-01 09:00:01] Tunnel 'web' connecting",
+                "[2026-03-01 09:00:02] [ssh:web] debug1: Connecting to example.com port 22.",
+                "[2026-03-01 10:00:00] [ssh:web] user@example.com: Permission denied (publickey).",
+                "  continued on the next line",
+            ]
+        );
+    }
+
+    #[test]
+    fn tunnel_lines_before_the_window_are_left_out() {
+        let lines = filter_tunnel_lines(LOG, "web", at("2026-03-01 09:30:00"));
+
+        assert_eq!(
+            lines,
+            vec![
+                "[2026-03-01 10:00:00] [ssh:web] user@example.com: Permission denied (publickey).",
+                "  continued on the next line",
+            ]
+        );
+        assert_eq!(filter_tunnel_lines(LOG, "db", at("2026-03-01 09:30:00")).len(), 3);
+        assert!(filter_tunnel_lines(LOG, "web", at("2026-03-02 00:00:00")).is_empty());
+    }
+
+    #[test]
+    fn tunnel_lines_are_collected_from_every_run_in_order() {
+        let logs_dir = std::env::temp_dir().join(format!("drill-logs-{}", std::process::id()));
+        std::fs::create_dir_all(&logs_dir).unwrap();
+        std::fs::write(logs_dir.join("drill_20260302_080000.log"), "[2026-03-02 08:00:00] Tunnel 'web' connected\n").unwrap();
+        std::fs::write(logs_dir.join("drill_20260301_090000.log"), LOG).unwrap();
+        std::fs::write(logs_dir.join("notes.txt"), "[2026-03-02 08:00:00] Tunnel 'web' connected\n").unwrap();
+
+        let lines = tunnel_log_lines(&logs_dir, "web", None).unwrap();
+
+        assert_eq!(lines.len(), 5);
+        assert_eq!(lines.last().map(String::as_str), Some("[2026-03-02 08:00:00] Tunnel 'web' connected"));
+        let _ = std::fs::remove_dir_all(&logs_dir);
+    }
+
+    fn logger(name: &str) -> Logger {
+        let path = std::env::temp_dir().join(format!("drill-logs-{}-{}.log", std::process::id(), name));
+        Logger::new(std::fs::File::create(&path).unwrap(), path)
+    }
+
+    #[test]
+    fn quiet_logger_still_writes_the_file() {
+        let mut logger = logger("quiet");
+        assert_eq!(logger.write_entry("echoed"), Some("echoed"));
+
+        logger.set_echo_stdout(false);
+        assert_eq!(logger.write_entry("file only"), None);
+        // Clones made afterwards stay quiet
+        assert_eq!(logger.try_clone().unwrap().write_entry("clone"), None);
+
+        let content = std::fs::read_to_string(logger.path()).unwrap();
+        let messages: Vec<&str> = content.lines().filter_map(|line| line.split_once("] ").map(|(_, message)| message)).collect();
+        assert_eq!(messages, ["echoed", "file only", "clone"]);
+        let _ = std::fs::remove_file(logger.path());
     }
 }
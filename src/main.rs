@@ -1,26 +1,111 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod app;
+mod autostart;
+mod bundle;
 mod config;
+mod daemon;
+mod events;
+mod health;
+mod hotkey;
+mod hooks;
+mod instance;
+mod inventory;
 mod logs;
 mod notifications;
+mod preconditions;
+mod putty;
+mod reconnect;
+mod runner;
+mod service;
+mod ssh_uri;
+mod status_line;
 mod systemtray;
+mod templates;
 mod tunnels;
+mod updates;
 mod windows;
 mod error;
+mod error_stats;
 
 use app::App;
+use clap::Parser;
+use std::io::IsTerminal;
+
+/// Drill - Multi-Platform SSH tunnel manager
+#[derive(Parser)]
+#[command(version, about)]
+struct Cli {
+    /// Run without tray or windows, keeping the tunnels flagged auto_start connected
+    #[arg(long)]
+    daemon: bool,
+    /// Show a one-line tunnel summary on the terminal instead of the log stream
+    /// (the log file keeps full detail; ignored when stdout is not a terminal)
+    #[arg(long)]
+    status_line: bool,
+    /// Print the health report of the running Drill instance as JSON and exit
+    /// (exit code 1 when no instance is running)
+    #[arg(long)]
+    health: bool,
+}
 
 fn main() -> iced::Result {
+    let cli = Cli::parse();
+    if cli.health {
+        std::process::exit(health::print_report());
+    }
+    let status_line = cli.status_line && std::io::stdout().is_terminal();
+
+    // Only one instance may run: a second one would start the same tunnels again
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let lock = match config::get_instance_lock_path().and_then(|path| instance::acquire(&path, &args)) {
+        Ok(instance::Acquired::Locked(lock)) => Some(lock),
+        Ok(instance::Acquired::Forwarded) => {
+            eprintln!("Drill is already running");
+            return Ok(());
+        }
+        Ok(instance::Acquired::Pending) => {
+            eprintln!("Drill is already running (still starting up)");
+            return Ok(());
+        }
+        Err(e) => {
+            eprintln!("Could not take the single-instance lock, continuing without it: {}", e);
+            None
+        }
+    };
+
+    if cli.daemon {
+        let result = daemon::run(status_line);
+        // exit() skips destructors, release the lock first
+        drop(lock);
+        if let Err(e) = result {
+            eprintln!("Error running daemon: {}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    // Later launches are forwarded here instead of starting a second instance
+    let launches = lock.as_ref().and_then(|lock| match lock.forwarded_args() {
+        Ok(receiver) => Some(receiver),
+        Err(e) => {
+            eprintln!("Could not listen for later launches: {}", e);
+            None
+        }
+    });
+
     // Initialize the notification system
     notifications::init_notifications();
     
-    iced::daemon(App::title_fn, App::update_fn, App::view_fn)
+    let result = iced::daemon(App::title_fn, App::update_fn, App::view_fn)
         .subscription(App::subscription_fn)
-        .run_with(|| {
-            let (app, task) = App::new();
+        .run_with(move || {
+            let (app, task) = App::new(status_line, launches);
             (app, task)
-        })
+        });
+    // Released once the app has quit (dropping also covers a panic unwinding)
+    drop(lock);
+    result
 }
 
 
@@ -3,6 +3,9 @@ use crate::error::{DrillResult, DrillError};
 #[cfg(not(target_os = "macos"))]
 use notify_rust::{Notification, Timeout};
 
+#[cfg(not(target_os = "macos"))]
+use std::sync::atomic::{AtomicU32, Ordering};
+
 #[cfg(target_os = "macos")]
 use std::sync::{Once, atomic::{AtomicBool, Ordering}};
 
@@ -38,6 +41,40 @@ pub fn init_notifications() {
     // No initialization needed on other platforms
 }
 
+/// How long notifications stay up unless configured (`notification_timeout_ms`)
+pub const DEFAULT_TIMEOUT_MS: u32 = 5000;
+
+/// How long error notifications stay up unless configured (`error_notification_timeout_ms`)
+pub const DEFAULT_ERROR_TIMEOUT_MS: u32 = 10000;
+
+#[cfg(not(target_os = "macos"))]
+static TIMEOUT_MS: AtomicU32 = AtomicU32::new(DEFAULT_TIMEOUT_MS);
+
+#[cfg(not(target_os = "macos"))]
+static ERROR_TIMEOUT_MS: AtomicU32 = AtomicU32::new(DEFAULT_ERROR_TIMEOUT_MS);
+
+/// Set how long notifications stay up, from the settings
+#[cfg(not(target_os = "macos"))]
+pub fn set_timeouts(timeout_ms: u32, error_timeout_ms: u32) {
+    TIMEOUT_MS.store(timeout_ms, Ordering::Relaxed);
+    ERROR_TIMEOUT_MS.store(error_timeout_ms, Ordering::Relaxed);
+}
+
+/// Set how long notifications stay up, from the settings
+/// macOS decides on its own how long a notification is shown, so this is ignored
+#[cfg(target_os = "macos")]
+pub fn set_timeouts(_timeout_ms: u32, _error_timeout_ms: u32) {}
+
+#[cfg(not(target_os = "macos"))]
+fn notification_timeout() -> Timeout {
+    Timeout::Milliseconds(TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
+#[cfg(not(target_os = "macos"))]
+fn error_notification_timeout() -> Timeout {
+    Timeout::Milliseconds(ERROR_TIMEOUT_MS.load(Ordering::Relaxed))
+}
+
 #[cfg(target_os = "macos")]
 fn show_macos_notification(title: &str, body: &str) -> DrillResult<()> {
     use mac_notification_sys::send_notification;
@@ -74,17 +111,23 @@ pub fn notify_tunnel_connected(tunnel_name: &str) -> DrillResult<()> {
     
     #[cfg(not(target_os = "macos"))]
     {
-        Notification::new()
-            .summary("Drill - Tunnel Connected")
-            .body(&format!("Tunnel '{}' is now connected", tunnel_name))
-            .icon("network-wired")
-            .timeout(Timeout::Milliseconds(5000))
+        connected_notification(tunnel_name)
             .show()
             .map_err(|e| DrillError::Notification(format!("Notification error: {}", e)))?;
     }
     Ok(())
 }
 
+#[cfg(not(target_os = "macos"))]
+fn connected_notification(tunnel_name: &str) -> Notification {
+    Notification::new()
+        .summary("Drill - Tunnel Connected")
+        .body(&format!("Tunnel '{}' is now connected", tunnel_name))
+        .icon("network-wired")
+        .timeout(notification_timeout())
+        .finalize()
+}
+
 pub fn notify_tunnel_disconnected(tunnel_name: &str) {
     // logger.log_print(&format!("Showing notification: Tunnel '{}' disconnected", tunnel_name));
     
@@ -106,7 +149,7 @@ pub fn notify_tunnel_disconnected(tunnel_name: &str) {
                 .summary("Drill - Tunnel Disconnected")
                 .body(&format!("Tunnel '{}' has been disconnected", tunnel_name))
                 .icon("network-offline")
-                .timeout(Timeout::Milliseconds(5000))
+                .timeout(notification_timeout())
                 .show()
             {
                 Ok(_) => {},
@@ -115,8 +158,35 @@ pub fn notify_tunnel_disconnected(tunnel_name: &str) {
     }
 }
 
+/// Longest error text shown in a notification, in characters (the log keeps all of it)
+const MAX_NOTIFICATION_ERROR_CHARS: usize = 200;
+
+/// Make error text fit for a notification body
+/// ssh's stderr can run long, span lines and carry control characters (which
+/// also break the AppleScript fallback on macOS): control characters are
+/// dropped, whitespace runs including newlines become one space, and text
+/// longer than `max_chars` is cut at a word boundary when one is close, with "…".
+pub fn sanitize_notification_text(text: &str, max_chars: usize) -> String {
+    let cleaned: String = text
+        .chars()
+        .filter(|c| !c.is_control() || c.is_whitespace())
+        .collect();
+    let collapsed = cleaned.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() <= max_chars {
+        return collapsed;
+    }
+    let cut: String = collapsed.chars().take(max_chars.saturating_sub(1)).collect();
+    // Prefer ending on a whole word unless that throws away too much
+    let cut = match cut.rfind(' ') {
+        Some(space) if cut[..space].chars().count() >= max_chars * 3 / 4 => &cut[..space],
+        _ => cut.as_str(),
+    };
+    format!("{}…", cut.trim_end())
+}
+
 pub fn notify_tunnel_error(tunnel_name: &str, error_message: &str) {
     // logger.log_print(&format!("Showing notification: Tunnel '{}' error - {}", tunnel_name, error_message));
+    let error_message = &sanitize_notification_text(error_message, MAX_NOTIFICATION_ERROR_CHARS);
     
     #[cfg(target_os = "macos")]
     {
@@ -132,19 +202,54 @@ pub fn notify_tunnel_error(tunnel_name: &str, error_message: &str) {
     
     #[cfg(not(target_os = "macos"))]
     {
-            match Notification::new()
-                .summary("Drill - Tunnel Error")
-                .body(&format!("Failed to connect tunnel '{}':\n{}", tunnel_name, error_message))
-                .icon("dialog-error")
-                .timeout(Timeout::Milliseconds(10000))
-                .show()
-            {
+            match error_notification(tunnel_name, error_message).show() {
                 Ok(_) => {},
                 Err(_) => {},
             }
     }
 }
 
+#[cfg(not(target_os = "macos"))]
+fn error_notification(tunnel_name: &str, error_message: &str) -> Notification {
+    Notification::new()
+        .summary("Drill - Tunnel Error")
+        .body(&format!("Failed to connect tunnel '{}':\n{}", tunnel_name, error_message))
+        .icon("dialog-error")
+        .timeout(error_notification_timeout())
+        .finalize()
+}
+
+/// Show the result of a connection test started from the tray
+/// `result` is a short summary ("SSH OK") or the error, which is sanitized here.
+pub fn notify_connection_test(tunnel_name: &str, succeeded: bool, result: &str) {
+    // logger.log_print(&format!("Showing notification: Tunnel '{}' test - {}", tunnel_name, result));
+    let body = format!("{}: {}", tunnel_name, sanitize_notification_text(result, MAX_NOTIFICATION_ERROR_CHARS));
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = succeeded;
+        match show_macos_notification("Connection Test", &body) {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        let timeout = if succeeded { notification_timeout() } else { error_notification_timeout() };
+        match Notification::new()
+            .summary("Drill - Connection Test")
+            .body(&body)
+            .icon(if succeeded { "network-wired" } else { "dialog-error" })
+            .timeout(timeout)
+            .show()
+        {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+}
+
 pub fn notify_tunnel_removed(tunnel_name: &str) {
     // logger.log_print(&format!("Showing notification: Tunnel '{}' removed", tunnel_name));
     
@@ -166,7 +271,7 @@ pub fn notify_tunnel_removed(tunnel_name: &str) {
                 .summary("Drill - Tunnel Removed")
                 .body(&format!("Tunnel '{}' has been removed", tunnel_name))
                 .icon("user-trash")
-                .timeout(Timeout::Milliseconds(5000))
+                .timeout(notification_timeout())
                 .show()
             {
                 Ok(_) => {},
@@ -196,7 +301,7 @@ pub fn notify_tunnel_created(tunnel_name: &str) {
             .summary("Drill - Tunnel Created")
             .body(&format!("Tunnel '{}' has been created successfully", tunnel_name))
             .icon("emblem-default")
-            .timeout(Timeout::Milliseconds(5000))
+            .timeout(notification_timeout())
             .show()
         {
             Ok(_) => {}
@@ -204,3 +309,337 @@ pub fn notify_tunnel_created(tunnel_name: &str) {
         }
     }
 }
+
+pub fn notify_bundle_exported(tunnel_count: usize) {
+    // logger.log_print(&format!("Showing notification: {} tunnel(s) exported", tunnel_count));
+    
+    #[cfg(target_os = "macos")]
+    {
+        match show_macos_notification(
+            "Bundle Exported",
+            &format!("{} tunnel(s) exported to bundle", tunnel_count)
+        ) {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        match Notification::new()
+            .summary("Drill - Bundle Exported")
+            .body(&format!("{} tunnel(s) exported to bundle", tunnel_count))
+            .icon("document-save")
+            .timeout(notification_timeout())
+            .show()
+        {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+}
+
+pub fn notify_bundle_imported(imported_count: usize, skipped_count: usize) {
+    // logger.log_print(&format!("Showing notification: {} tunnel(s) imported", imported_count));
+    let body = if skipped_count > 0 {
+        format!("{} tunnel(s) imported, {} already present", imported_count, skipped_count)
+    } else {
+        format!("{} tunnel(s) imported", imported_count)
+    };
+    
+    #[cfg(target_os = "macos")]
+    {
+        match show_macos_notification("Bundle Imported", &body) {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        match Notification::new()
+            .summary("Drill - Bundle Imported")
+            .body(&body)
+            .icon("document-open")
+            .timeout(notification_timeout())
+            .show()
+        {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+}
+
+pub fn notify_update_available(version: &str) {
+    // logger.log_print(&format!("Showing notification: Drill {} available", version));
+    let body = format!("Drill {} is available. Open About Drill to download it.", version);
+    
+    #[cfg(target_os = "macos")]
+    {
+        match show_macos_notification("Update Available", &body) {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        match Notification::new()
+            .summary("Drill - Update Available")
+            .body(&body)
+            .icon("software-update-available")
+            .timeout(notification_timeout())
+            .show()
+        {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+}
+
+pub fn notify_tunnel_timer_expired(tunnel_name: &str) {
+    // logger.log_print(&format!("Showing notification: Tunnel '{}' timer expired", tunnel_name));
+    let body = format!("Tunnel '{}' was disconnected, its time limit ran out", tunnel_name);
+    
+    #[cfg(target_os = "macos")]
+    {
+        match show_macos_notification("Tunnel Time Limit Reached", &body) {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        match Notification::new()
+            .summary("Drill - Tunnel Time Limit Reached")
+            .body(&body)
+            .icon("network-offline")
+            .timeout(notification_timeout())
+            .show()
+        {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+}
+
+pub fn notify_errored_tunnels_reconnected(started_count: usize, failed_count: usize) {
+    // logger.log_print(&format!("Showing notification: Reconnected {} errored tunnel(s)", started_count));
+    let body = if failed_count == 0 {
+        format!("Reconnecting {} tunnel(s)", started_count)
+    } else {
+        format!("Reconnecting {} tunnel(s), {} failed to start", started_count, failed_count)
+    };
+    
+    #[cfg(target_os = "macos")]
+    {
+        match show_macos_notification("Reconnecting Errored Tunnels", &body) {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        match Notification::new()
+            .summary("Drill - Reconnecting Errored Tunnels")
+            .body(&body)
+            .icon("network-wired")
+            .timeout(notification_timeout())
+            .show()
+        {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+}
+
+pub fn notify_no_primary_tunnel() {
+    // logger.log_print("Showing notification: No primary tunnel to toggle");
+    let body = "Set primary_tunnel in the config, or connect a tunnel once so the hotkey can use it";
+    
+    #[cfg(target_os = "macos")]
+    {
+        match show_macos_notification("No Tunnel to Toggle", body) {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+    
+    #[cfg(not(target_os = "macos"))]
+    {
+        match Notification::new()
+            .summary("Drill - No Tunnel to Toggle")
+            .body(body)
+            .icon("dialog-information")
+            .timeout(notification_timeout())
+            .show()
+        {
+            Ok(_) => {},
+            Err(_) => {},
+        }
+    }
+}
+
+/// How long tunnel notifications are held back to be shown together
+pub const BATCH_WINDOW: std::time::Duration = std::time::Duration::from_millis(500);
+
+/// Tunnel event a batched notification is about
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BatchedKind {
+    Connected,
+    Failed,
+    Disconnected,
+}
+
+/// Tunnel notifications collected during `BATCH_WINDOW`
+/// Connect All or a network flap fire many at once; they are shown as a
+/// single summary, while a lone notification is shown as usual.
+#[derive(Debug, Default)]
+pub struct NotificationBatch {
+    /// Kind, tunnel name and error message (empty unless failed)
+    pending: Vec<(BatchedKind, String, String)>,
+}
+
+impl NotificationBatch {
+    /// Queue a notification
+    /// Returns true when it opens a new batch: the caller flushes it after `BATCH_WINDOW`.
+    pub fn push(&mut self, kind: BatchedKind, tunnel_name: &str, detail: &str) -> bool {
+        self.pending.push((kind, tunnel_name.to_string(), detail.to_string()));
+        self.pending.len() == 1
+    }
+
+    /// Show the queued notifications and start over
+    pub fn flush(&mut self) {
+        let pending = std::mem::take(&mut self.pending);
+        match pending.as_slice() {
+            [] => {}
+            [(BatchedKind::Connected, tunnel_name, _)] => {
+                let _ = notify_tunnel_connected(tunnel_name);
+            }
+            [(BatchedKind::Failed, tunnel_name, error)] => notify_tunnel_error(tunnel_name, error),
+            [(BatchedKind::Disconnected, tunnel_name, _)] => notify_tunnel_disconnected(tunnel_name),
+            _ => {
+                let failed = pending.iter().any(|(kind, _, _)| *kind == BatchedKind::Failed);
+                let names: Vec<&str> = pending.iter().map(|(_, name, _)| name.as_str()).collect();
+                notify_tunnel_summary(&batch_summary(&pending), &names.join(", "), failed);
+            }
+        }
+    }
+}
+
+/// Summary of a batch, e.g. "3 tunnels connected, 1 failed"
+/// Kinds are listed connected, failed, disconnected, skipping those absent.
+pub fn batch_summary(pending: &[(BatchedKind, String, String)]) -> String {
+    let count = |kind: BatchedKind| pending.iter().filter(|(k, _, _)| *k == kind).count();
+    let parts: Vec<String> = [
+        (count(BatchedKind::Connected), "connected"),
+        (count(BatchedKind::Failed), "failed"),
+        (count(BatchedKind::Disconnected), "disconnected"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .enumerate()
+    .map(|(index, (count, label))| {
+        // Only the first part names the noun: "3 tunnels connected, 1 failed"
+        if index == 0 {
+            format!("{} tunnel{} {}", count, if count == 1 { "" } else { "s" }, label)
+        } else {
+            format!("{} {}", count, label)
+        }
+    })
+    .collect();
+    parts.join(", ")
+}
+
+fn notify_tunnel_summary(summary: &str, tunnel_names: &str, failed: bool) {
+    // logger.log_print(&format!("Showing notification: {}", summary));
+
+    #[cfg(target_os = "macos")]
+    {
+        let _ = failed;
+        match show_macos_notification("Tunnels Updated", &format!("{} ({})", summary, tunnel_names)) {
+            Ok(_) => {},
+            Err(_) => {},
+            // Err(e) => logger.log_print(&format!("Error showing notification: {}", e)),
+        }
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    {
+        // A summary with a failure stays up as long as an error notification
+        let timeout = if failed { error_notification_timeout() } else { notification_timeout() };
+        match Notification::new()
+            .summary(&format!("Drill - {}", summary))
+            .body(tunnel_names)
+            .icon(if failed { "dialog-error" } else { "network-wired" })
+            .timeout(timeout)
+            .show()
+        {
+            Ok(_) => {},
+            Err(_) => {},
+            // Err(e) => logger.log_print(&format!("Error showing notification: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn configured_timeouts_reach_the_notification() {
+        set_timeouts(1500, 30000);
+        assert_eq!(connected_notification("web").timeout, Timeout::Milliseconds(1500));
+        assert_eq!(error_notification("web", "refused").timeout, Timeout::Milliseconds(30000));
+        set_timeouts(DEFAULT_TIMEOUT_MS, DEFAULT_ERROR_TIMEOUT_MS);
+        assert_eq!(connected_notification("web").timeout, Timeout::Milliseconds(5000));
+    }
+
+    fn pending(kinds: &[BatchedKind]) -> Vec<(BatchedKind, String, String)> {
+        kinds.iter().enumerate().map(|(index, kind)| (*kind, format!("tunnel{}", index), String::new())).collect()
+    }
+
+    #[test]
+    fn batch_summary_counts_each_kind() {
+        use BatchedKind::*;
+        assert_eq!(batch_summary(&pending(&[Connected, Failed, Connected, Connected])), "3 tunnels connected, 1 failed");
+        assert_eq!(batch_summary(&pending(&[Disconnected, Failed])), "1 tunnel failed, 1 disconnected");
+        assert_eq!(batch_summary(&pending(&[Disconnected, Disconnected])), "2 tunnels disconnected");
+        assert_eq!(batch_summary(&pending(&[Connected, Failed, Disconnected])), "1 tunnel connected, 1 failed, 1 disconnected");
+        assert_eq!(batch_summary(&[]), "");
+    }
+
+    #[test]
+    fn only_the_first_notification_opens_a_batch() {
+        let mut batch = NotificationBatch::default();
+        assert!(batch.push(BatchedKind::Connected, "web", ""));
+        assert!(!batch.push(BatchedKind::Failed, "db", "refused"));
+        assert_eq!(batch.pending.len(), 2);
+        batch.pending.clear();
+        assert!(batch.push(BatchedKind::Disconnected, "web", ""));
+    }
+
+    #[test]
+    fn whitespace_and_lines_collapse_to_single_spaces() {
+        assert_eq!(sanitize_notification_text("  line one\n\tline two\r\n  three ", 200), "line one line two three");
+    }
+
+    #[test]
+    fn control_characters_are_stripped() {
+        assert_eq!(sanitize_notification_text("a\u{7}b \u{1b}[31mred\u{0}", 200), "ab [31mred");
+    }
+
+    #[test]
+    fn long_text_is_truncated_with_an_ellipsis() {
+        assert_eq!(sanitize_notification_text("short", 5), "short");
+        // Cut at the last word when that keeps most of the text
+        assert_eq!(sanitize_notification_text("aaaaaaaaaaaaaaaa bbbbbbbbbb", 20), "aaaaaaaaaaaaaaaa…");
+        // Otherwise mid-word, at the limit
+        assert_eq!(sanitize_notification_text("abcdefghij klmnopqrstuvwxyz", 20), "abcdefghij klmnopqr…");
+        // Characters, not bytes, are counted
+        assert_eq!(sanitize_notification_text("ééééééééé", 5), "éééé…");
+    }
+}
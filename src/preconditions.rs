@@ -0,0 +1,226 @@
+use std::net::IpAddr;
+use std::process::{Command, Stdio};
+use crate::error::{DrillError, DrillResult};
+use crate::tunnels::Tunnel;
+
+/// Seconds to wait for the gate host to answer a ping
+const GATE_PING_TIMEOUT_SECS: u32 = 2;
+
+/// Check the network preconditions of a tunnel before connecting
+/// Fails fast when the required interface (e.g. a VPN's tun0) is missing or
+/// the gate host doesn't answer, instead of waiting for ssh to time out.
+pub fn check(tunnel: &Tunnel) -> DrillResult<()> {
+    let interface = tunnel.required_interface.trim();
+    if !interface.is_empty() && !interface_present(interface) {
+        return Err(DrillError::Tunnel(format!(
+            "Precondition not met: VPN interface {} not found",
+            interface
+        )));
+    }
+    let gate_host = tunnel.gate_host.trim();
+    if !gate_host.is_empty() && !host_reachable(gate_host) {
+        return Err(DrillError::Tunnel(format!(
+            "Precondition not met: gate host {} is not reachable",
+            gate_host
+        )));
+    }
+    Ok(())
+}
+
+/// Whether a network interface with this name exists and is up
+#[cfg(target_os = "linux")]
+pub fn interface_present(name: &str) -> bool {
+    // A down interface reports "down"; tun devices report "unknown" while up
+    match std::fs::read_to_string(format!("/sys/class/net/{}/operstate", name)) {
+        Ok(state) => state.trim() != "down",
+        Err(_) => false,
+    }
+}
+
+/// Whether a network interface with this name exists and is up
+#[cfg(target_os = "macos")]
+pub fn interface_present(name: &str) -> bool {
+    // `ifconfig -l -u` lists the interfaces that are up, space separated
+    match Command::new("ifconfig").args(["-l", "-u"]).stderr(Stdio::null()).output() {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).split_whitespace().any(|up| up == name),
+        Err(_) => false,
+    }
+}
+
+/// Whether a network interface with this name exists and is up
+#[cfg(windows)]
+pub fn interface_present(name: &str) -> bool {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    // Lines look like "Enabled    Connected    Dedicated    Ethernet 2"
+    let output = Command::new("netsh")
+        .args(["interface", "show", "interface"])
+        .stderr(Stdio::null())
+        .creation_flags(CREATE_NO_WINDOW)
+        .output();
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).lines().any(|line| {
+            let columns: Vec<&str> = line.split_whitespace().collect();
+            columns.len() >= 4
+                && columns[1].eq_ignore_ascii_case("Connected")
+                && columns[3..].join(" ").eq_ignore_ascii_case(name)
+        }),
+        Err(_) => false,
+    }
+}
+
+/// Whether a network interface with this name exists and is up
+/// Other platforms can't be checked, the precondition is assumed met
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+pub fn interface_present(_name: &str) -> bool {
+    true
+}
+
+/// Whether the host answers a single ping within `GATE_PING_TIMEOUT_SECS`
+pub fn host_reachable(host: &str) -> bool {
+    let mut command = Command::new("ping");
+    command.args(ping_args(host)).stdin(Stdio::null()).stdout(Stdio::null()).stderr(Stdio::null());
+
+    // On Windows, suppress terminal window
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    command.status().map(|status| status.success()).unwrap_or(false)
+}
+
+/// Arguments for one ping with a timeout, the flags differ per platform
+fn ping_args(host: &str) -> Vec<String> {
+    if cfg!(windows) {
+        // -w is in milliseconds
+        vec!["-n".to_string(), "1".to_string(), "-w".to_string(), (GATE_PING_TIMEOUT_SECS * 1000).to_string(), host.to_string()]
+    } else if cfg!(target_os = "macos") {
+        // macOS ping's -W is in milliseconds, -t is the overall timeout in seconds
+        vec!["-c".to_string(), "1".to_string(), "-t".to_string(), GATE_PING_TIMEOUT_SECS.to_string(), host.to_string()]
+    } else {
+        vec!["-c".to_string(), "1".to_string(), "-W".to_string(), GATE_PING_TIMEOUT_SECS.to_string(), host.to_string()]
+    }
+}
+
+/// Network interfaces with their addresses, as reported by the system
+pub fn list_interfaces() -> Vec<(String, Vec<IpAddr>)> {
+    let networks = sysinfo::Networks::new_with_refreshed_list();
+    let mut interfaces: Vec<(String, Vec<IpAddr>)> = networks
+        .iter()
+        .map(|(name, data)| (name.clone(), data.ip_networks().iter().map(|network| network.addr).collect()))
+        .collect();
+    interfaces.sort_by(|a, b| a.0.cmp(&b.0));
+    interfaces
+}
+
+/// Current address of a network interface, to bind a tunnel's local side to
+pub fn interface_address(name: &str) -> DrillResult<IpAddr> {
+    resolve_interface_address(name, &list_interfaces())
+}
+
+/// Pick the bind address of interface `name` from an interface list
+/// IPv4 is preferred; link-local IPv6 addresses need a scope id ssh can't
+/// take in a bind address, so they are skipped.
+pub fn resolve_interface_address(name: &str, interfaces: &[(String, Vec<IpAddr>)]) -> DrillResult<IpAddr> {
+    let Some((_, addresses)) = interfaces.iter().find(|(interface, _)| interface == name) else {
+        let available: Vec<&str> = interfaces.iter().map(|(interface, _)| interface.as_str()).collect();
+        return Err(DrillError::Tunnel(format!(
+            "Bind interface {} not found (available: {})",
+            name,
+            if available.is_empty() { "none".to_string() } else { available.join(", ") }
+        )));
+    };
+    let usable = |address: &&IpAddr| match address {
+        IpAddr::V4(_) => true,
+        IpAddr::V6(v6) => (v6.segments()[0] & 0xffc0) != 0xfe80,
+    };
+    addresses
+        .iter()
+        .filter(usable)
+        .min_by_key(|address| address.is_ipv6())
+        .copied()
+        .ok_or_else(|| DrillError::Tunnel(format!("Bind interface {} is down or has no IP address", name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::test_tunnel;
+
+    #[test]
+    fn a_tunnel_without_preconditions_passes() {
+        assert!(check(&test_tunnel("web", "18080")).is_ok());
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "macos", windows))]
+    #[test]
+    fn a_missing_interface_fails_fast() {
+        let tunnel = Tunnel { required_interface: " drill-no-if0 ".to_string(), ..test_tunnel("web", "18080") };
+
+        let error = check(&tunnel).unwrap_err();
+
+        assert_eq!(error.to_string(), "Tunnel error: Precondition not met: VPN interface drill-no-if0 not found");
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn the_loopback_interface_is_present() {
+        assert!(interface_present("lo"));
+        assert!(!interface_present("drill-no-if0"));
+    }
+
+    #[test]
+    fn ping_sends_one_packet_with_a_timeout() {
+        let args = ping_args("gate.example.com");
+
+        assert_eq!(args.last().map(String::as_str), Some("gate.example.com"));
+        assert_eq!(args[1], "1");
+        if cfg!(windows) {
+            assert_eq!(args[..4], ["-n", "1", "-w", "2000"]);
+        } else {
+            assert_eq!(args[0], "-c");
+            assert_eq!(args[3], GATE_PING_TIMEOUT_SECS.to_string());
+        }
+    }
+
+    /// An interface list as `list_interfaces` would return it
+    fn interfaces(entries: &[(&str, &[&str])]) -> Vec<(String, Vec<IpAddr>)> {
+        entries
+            .iter()
+            .map(|(name, addresses)| (name.to_string(), addresses.iter().map(|a| a.parse().unwrap()).collect()))
+            .collect()
+    }
+
+    #[test]
+    fn bind_interface_prefers_ipv4_and_skips_link_local() {
+        let list = interfaces(&[
+            ("en0", &["fe80::1", "2001:db8::5", "192.168.1.20"]),
+            ("tun0", &["fe80::2", "fd00::7"]),
+            ("utun3", &["fe80::3"]),
+        ]);
+
+        assert_eq!(resolve_interface_address("en0", &list).unwrap().to_string(), "192.168.1.20");
+        assert_eq!(resolve_interface_address("tun0", &list).unwrap().to_string(), "fd00::7");
+        assert_eq!(
+            resolve_interface_address("utun3", &list).unwrap_err().to_string(),
+            "Tunnel error: Bind interface utun3 is down or has no IP address"
+        );
+    }
+
+    #[test]
+    fn a_missing_bind_interface_lists_the_available_ones() {
+        let list = interfaces(&[("en0", &["192.168.1.20"]), ("lo0", &["127.0.0.1"])]);
+
+        assert_eq!(
+            resolve_interface_address("tun0", &list).unwrap_err().to_string(),
+            "Tunnel error: Bind interface tun0 not found (available: en0, lo0)"
+        );
+        assert_eq!(
+            resolve_interface_address("tun0", &[]).unwrap_err().to_string(),
+            "Tunnel error: Bind interface tun0 not found (available: none)"
+        );
+    }
+}
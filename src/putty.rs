@@ -0,0 +1,345 @@
+// The registry parsing only runs on Windows
+#![cfg_attr(not(target_os = "windows"), allow(dead_code))]
+
+use std::path::{Path, PathBuf};
+use crate::error::{DrillResult, DrillError};
+use crate::tunnels::{AuthMode, Tunnel};
+
+/// Registry key PuTTY keeps its saved sessions under
+const SESSIONS_KEY: &str = r"HKCU\Software\SimonTatham\PuTTY\Sessions";
+
+/// A PuTTY saved session, with the values Drill can use
+#[derive(Debug, Clone, Default, PartialEq)]
+struct PuttySession {
+    /// Session name as shown in PuTTY (decoded from the registry key)
+    pub name: String,
+    pub host_name: String,
+    pub port: String,
+    pub user_name: String,
+    /// Private key file (`PublicKeyFile` in the registry, usually a .ppk)
+    pub key_file: String,
+    /// Raw `PortForwardings` value, e.g. `L8080=localhost:80,R9000=localhost:9000`
+    pub port_forwardings: String,
+}
+
+/// A local (`-L`) forward of a PuTTY session
+#[derive(Debug, Clone, PartialEq)]
+struct LocalForward {
+    local_host: String,
+    local_port: String,
+    remote_host: String,
+    remote_port: String,
+}
+
+/// Import the PuTTY saved sessions of the current user as tunnels
+/// Each local forward of a session becomes a tunnel; sessions without one
+/// (plain shells, remote or dynamic forwards only) are skipped.
+#[cfg(target_os = "windows")]
+pub fn import_putty_sessions() -> DrillResult<Vec<Tunnel>> {
+    let output = crate::autostart::reg_command(&["query", SESSIONS_KEY, "/s"])?;
+    if !output.status.success() {
+        // reg.exe fails when the key doesn't exist, i.e. PuTTY never saved a session
+        return Ok(Vec::new());
+    }
+    let sessions = parse_reg_query(&String::from_utf8_lossy(&output.stdout));
+    Ok(sessions.iter().flat_map(session_tunnels).collect())
+}
+
+/// PuTTY sessions live in the Windows registry, there is nothing to import elsewhere
+#[cfg(not(target_os = "windows"))]
+pub fn import_putty_sessions() -> DrillResult<Vec<Tunnel>> {
+    Err(DrillError::Config("PuTTY sessions can only be imported on Windows".to_string()))
+}
+
+/// Parse the output of `reg query <Sessions key> /s` into sessions
+/// Each session is a `HKEY_...\Sessions\<name>` line followed by indented
+/// `<value name>    <type>    <data>` lines. PuTTY's "Default Settings" are skipped.
+fn parse_reg_query(output: &str) -> Vec<PuttySession> {
+    let mut sessions: Vec<PuttySession> = Vec::new();
+    for line in output.lines() {
+        if line.starts_with("HKEY_") {
+            if let Some((_, name)) = line.trim().rsplit_once("\\Sessions\\") {
+                sessions.push(PuttySession { name: decode_session_name(name), ..Default::default() });
+            }
+            continue;
+        }
+        let Some(session) = sessions.last_mut() else {
+            continue;
+        };
+        // Fields are separated by four spaces; an empty value has no data field
+        let mut fields = line.trim_start().splitn(3, "    ");
+        let (Some(value_name), Some(value_type)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        let data = fields.next().unwrap_or("").trim().to_string();
+        let data = if value_type == "REG_DWORD" {
+            u32::from_str_radix(data.trim_start_matches("0x"), 16)
+                .map(|value| value.to_string())
+                .unwrap_or(data)
+        } else {
+            data
+        };
+        match value_name {
+            "HostName" => session.host_name = data,
+            "PortNumber" => session.port = data,
+            "UserName" => session.user_name = data,
+            "PublicKeyFile" => session.key_file = data,
+            "PortForwardings" => session.port_forwardings = data,
+            _ => {}
+        }
+    }
+    sessions.retain(|session| session.name != "Default Settings");
+    sessions
+}
+
+/// Decode a session name as stored in the registry (PuTTY escapes with %XX, e.g. %20)
+fn decode_session_name(name: &str) -> String {
+    let bytes = name.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let escaped = (bytes[index] == b'%')
+            .then(|| name.get(index + 1..index + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Parse the local forwards out of a `PortForwardings` value
+/// Entries are `[4|6]L[bind_address:]port=host:port`; remote (`R`) and
+/// dynamic (`D`) forwards have no Drill equivalent and are ignored.
+fn local_forwards(port_forwardings: &str) -> Vec<LocalForward> {
+    let unbracket = |host: &str| host.trim_start_matches('[').trim_end_matches(']').to_string();
+    port_forwardings
+        .split(',')
+        .filter_map(|entry| {
+            let entry = entry.trim().trim_start_matches(['4', '6']);
+            let (listen, target) = entry.strip_prefix('L')?.split_once('=')?;
+            let (local_host, local_port) = match listen.rsplit_once(':') {
+                Some((host, port)) => (unbracket(host), port.to_string()),
+                None => ("127.0.0.1".to_string(), listen.to_string()),
+            };
+            let (remote_host, remote_port) = target.rsplit_once(':')?;
+            Some(LocalForward {
+                local_host,
+                local_port,
+                remote_host: unbracket(remote_host),
+                remote_port: remote_port.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Convert a session into one tunnel per local forward
+/// A `user@host` host name supplies the user when the session has none.
+fn session_tunnels(session: &PuttySession) -> Vec<Tunnel> {
+    let (host_user, ssh_host) = match session.host_name.split_once('@') {
+        Some((user, host)) => (user.to_string(), host.to_string()),
+        None => (String::new(), session.host_name.clone()),
+    };
+    let ssh_user = if session.user_name.is_empty() { host_user } else { session.user_name.clone() };
+    let ssh_port = if session.port.is_empty() { "22".to_string() } else { session.port.clone() };
+    let forwards = local_forwards(&session.port_forwardings);
+    let several = forwards.len() > 1;
+    forwards
+        .into_iter()
+        .map(|forward| Tunnel {
+            id: uuid::Uuid::new_v4().to_string(),
+            display_name: String::new(),
+            name: if several {
+                format!("{} ({})", session.name, forward.local_port)
+            } else {
+                session.name.clone()
+            },
+            local_host: forward.local_host,
+            local_port: forward.local_port,
+            remote_host: forward.remote_host,
+            remote_port: forward.remote_port,
+            ssh_user: ssh_user.clone(),
+            ssh_host: ssh_host.clone(),
+            ssh_port: ssh_port.clone(),
+            private_key: session.key_file.clone(),
+            auto_start: false,
+            last_connected_at: None,
+            jump_tunnel_id: None,
+            ssh_config_file: String::new(),
+            auto_disconnect_minutes: None,
+            identities_only: false,
+            remote_socket: String::new(),
+            open_web_on_connect: false,
+            clean_exit_disconnects: false,
+            ssh_binary: String::new(),
+            auth_mode: AuthMode::Key,
+            notifications: true,
+            required_interface: String::new(),
+            gate_host: String::new(),
+            favorite: false,
+            env: Default::default(),
+            bind_interface: String::new(),
+            accept_new_host_keys: false,
+            inventory_file: String::new(),
+            generated_from: None,
+        })
+        .collect()
+}
+
+/// Check whether a key file is in PuTTY's format, which OpenSSH can't read
+pub fn is_ppk(path: &str) -> bool {
+    Path::new(path.trim())
+        .extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("ppk"))
+}
+
+/// Find puttygen on PATH (or in PuTTY's default install directory on Windows)
+pub fn puttygen_program() -> Option<PathBuf> {
+    let name = if cfg!(target_os = "windows") { "puttygen.exe" } else { "puttygen" };
+    let on_path = std::env::var_os("PATH").and_then(|path| {
+        std::env::split_paths(&path)
+            .map(|dir| dir.join(name))
+            .find(|candidate| candidate.is_file())
+    });
+    if on_path.is_some() || !cfg!(target_os = "windows") {
+        return on_path;
+    }
+    std::env::var_os("ProgramFiles")
+        .map(|dir| PathBuf::from(dir).join("PuTTY").join(name))
+        .filter(|candidate| candidate.is_file())
+}
+
+/// Convert a .ppk key to an OpenSSH private key with puttygen
+/// The converted key is written next to the original as `<name>_openssh`
+/// (reused if it already exists) and its path returned. Keys protected by a
+/// passphrase can't be converted non-interactively and fail.
+pub fn convert_ppk(puttygen: &Path, ppk: &str) -> DrillResult<String> {
+    let ppk = Path::new(ppk.trim());
+    let mut converted_name = ppk.file_stem().unwrap_or_default().to_os_string();
+    converted_name.push("_openssh");
+    let converted = ppk.with_file_name(converted_name);
+    if !converted.is_file() {
+        let output = std::process::Command::new(puttygen)
+            .arg(ppk)
+            .args(["-O", "private-openssh", "-o"])
+            .arg(&converted)
+            .stdin(std::process::Stdio::null())
+            .output()?;
+        if !output.status.success() {
+            return Err(DrillError::Config(format!(
+                "puttygen could not convert {}: {}",
+                ppk.display(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+    }
+    Ok(converted.to_string_lossy().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `reg query ... /s` output with a default entry, a tunnel session and a shell session
+    const REG_QUERY: &str = "\r
+HKEY_CURRENT_USER\\Software\\SimonTatham\\PuTTY\\Sessions\\Default%20Settings\r
+    HostName    REG_SZ    \r
+    PortNumber    REG_DWORD    0x16\r
+\r
+HKEY_CURRENT_USER\\Software\\SimonTatham\\PuTTY\\Sessions\\Prod%20DB\r
+    HostName    REG_SZ    deploy@bastion.example.com\r
+    PortNumber    REG_DWORD    0x8ae\r
+    UserName    REG_SZ\r
+    PublicKeyFile    REG_SZ    C:\\Users\\me\\keys\\prod key.ppk\r
+    PortForwardings    REG_SZ    L15432=db.internal:5432,R9000=localhost:9000,D1080\r
+\r
+HKEY_CURRENT_USER\\Software\\SimonTatham\\PuTTY\\Sessions\\shell\r
+    HostName    REG_SZ    shell.example.com\r
+    PortForwardings    REG_SZ\r
+";
+
+    #[test]
+    fn reg_query_output_parses_into_sessions() {
+        let sessions = parse_reg_query(REG_QUERY);
+
+        assert_eq!(
+            sessions,
+            vec![
+                PuttySession {
+                    name: "Prod DB".to_string(),
+                    host_name: "deploy@bastion.example.com".to_string(),
+                    port: "2222".to_string(),
+                    user_name: String::new(),
+                    key_file: r"C:\Users\me\keys\prod key.ppk".to_string(),
+                    port_forwardings: "L15432=db.internal:5432,R9000=localhost:9000,D1080".to_string(),
+                },
+                PuttySession {
+                    name: "shell".to_string(),
+                    host_name: "shell.example.com".to_string(),
+                    ..Default::default()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn sessions_become_one_tunnel_per_local_forward() {
+        let tunnels: Vec<Tunnel> = parse_reg_query(REG_QUERY).iter().flat_map(session_tunnels).collect();
+
+        assert_eq!(tunnels.len(), 1);
+        let db = &tunnels[0];
+        assert_eq!(db.name, "Prod DB");
+        assert_eq!((db.ssh_user.as_str(), db.ssh_host.as_str(), db.ssh_port.as_str()), ("deploy", "bastion.example.com", "2222"));
+        assert_eq!((db.local_host.as_str(), db.local_port.as_str()), ("127.0.0.1", "15432"));
+        assert_eq!((db.remote_host.as_str(), db.remote_port.as_str()), ("db.internal", "5432"));
+        assert!(is_ppk(&db.private_key));
+
+        let several = PuttySession {
+            name: "web".to_string(),
+            host_name: "web.example.com".to_string(),
+            user_name: "admin".to_string(),
+            port_forwardings: "L8080=localhost:80,L8443=localhost:443".to_string(),
+            ..Default::default()
+        };
+        let names: Vec<String> = session_tunnels(&several).into_iter().map(|t| t.name).collect();
+        assert_eq!(names, vec!["web (8080)", "web (8443)"]);
+    }
+
+    #[test]
+    fn port_forwardings_keep_bind_addresses_and_ip_versions() {
+        let forwards = local_forwards("4L0.0.0.0:8080=app:80, 6L[::1]:8443=[fd00::5]:443,Lbad");
+
+        assert_eq!(
+            forwards,
+            vec![
+                LocalForward {
+                    local_host: "0.0.0.0".to_string(),
+                    local_port: "8080".to_string(),
+                    remote_host: "app".to_string(),
+                    remote_port: "80".to_string(),
+                },
+                LocalForward {
+                    local_host: "::1".to_string(),
+                    local_port: "8443".to_string(),
+                    remote_host: "fd00::5".to_string(),
+                    remote_port: "443".to_string(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn session_names_are_unescaped() {
+        assert_eq!(decode_session_name("Prod%20DB%2Feu"), "Prod DB/eu");
+        assert_eq!(decode_session_name("100%"), "100%");
+        assert_eq!(decode_session_name("%zz"), "%zz");
+    }
+}
@@ -0,0 +1,140 @@
+use std::time::{Duration, Instant};
+
+/// Delay before the first reconnect attempt, doubled on every further attempt
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(5);
+
+/// Upper bound for the reconnect backoff
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(300);
+
+/// Minimum time between two reconnect attempts to the same ssh host,
+/// so tunnels sharing a host don't retry at the same moment
+const HOST_RETRY_SPACING: Duration = Duration::from_secs(10);
+
+/// Consecutive failures to a host after which its circuit breaker opens
+const BREAKER_FAILURE_THRESHOLD: u32 = 5;
+
+/// How long retries to a host are paused once its breaker is open
+const BREAKER_COOLDOWN: Duration = Duration::from_secs(600);
+
+/// How long a reconnected tunnel must stay up before its retry count and
+/// its host's failure history are reset
+pub const STABLE_CONNECTION: Duration = Duration::from_secs(30);
+
+/// Get the backoff delay before reconnect `attempt` (starting at 1)
+pub fn backoff_delay(attempt: u32) -> Duration {
+    let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+    RECONNECT_BASE_DELAY.saturating_mul(factor).min(RECONNECT_MAX_DELAY)
+}
+
+/// A reconnect waiting for its turn
+#[derive(Debug, Clone, Copy)]
+pub struct PendingReconnect {
+    pub attempt: u32,
+    pub due_at: Instant,
+}
+
+/// Failure history of one ssh host, shared by every tunnel going through it
+///
+/// Acts as a rate limiter (one attempt per `HOST_RETRY_SPACING`) and a circuit
+/// breaker: after `BREAKER_FAILURE_THRESHOLD` consecutive failures, attempts
+/// are refused for `BREAKER_COOLDOWN`. The first attempt after the cool-off
+/// closes the breaker again if it succeeds, or reopens it if it fails.
+#[derive(Debug, Clone, Default)]
+pub struct HostFailureTracker {
+    consecutive_failures: u32,
+    last_attempt_at: Option<Instant>,
+    open_until: Option<Instant>,
+}
+
+impl HostFailureTracker {
+    /// Whether a reconnect to this host may be attempted now
+    pub fn allow_attempt(&self, now: Instant) -> bool {
+        if self.is_open(now) {
+            return false;
+        }
+        match self.last_attempt_at {
+            Some(last) => now.duration_since(last) >= HOST_RETRY_SPACING,
+            None => true,
+        }
+    }
+
+    /// Whether the breaker is currently pausing retries
+    pub fn is_open(&self, now: Instant) -> bool {
+        self.open_until.is_some_and(|until| now < until)
+    }
+
+    pub fn record_attempt(&mut self, now: Instant) {
+        self.last_attempt_at = Some(now);
+    }
+
+    pub fn record_failure(&mut self, now: Instant) {
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= BREAKER_FAILURE_THRESHOLD {
+            self.open_until = Some(now + BREAKER_COOLDOWN);
+        }
+    }
+
+    pub fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.open_until = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        assert_eq!(backoff_delay(1), RECONNECT_BASE_DELAY);
+        assert_eq!(backoff_delay(2), RECONNECT_BASE_DELAY * 2);
+        assert_eq!(backoff_delay(40), RECONNECT_MAX_DELAY);
+    }
+
+    #[test]
+    fn attempts_to_a_host_are_spaced_out() {
+        let now = Instant::now();
+        let mut tracker = HostFailureTracker::default();
+        assert!(tracker.allow_attempt(now));
+
+        tracker.record_attempt(now);
+
+        assert!(!tracker.allow_attempt(now + HOST_RETRY_SPACING / 2));
+        assert!(tracker.allow_attempt(now + HOST_RETRY_SPACING));
+    }
+
+    #[test]
+    fn breaker_opens_after_repeated_failures() {
+        let now = Instant::now();
+        let mut tracker = HostFailureTracker::default();
+        for _ in 1..BREAKER_FAILURE_THRESHOLD {
+            tracker.record_failure(now);
+        }
+        assert!(!tracker.is_open(now));
+
+        tracker.record_failure(now);
+
+        assert!(tracker.is_open(now));
+        assert!(!tracker.allow_attempt(now + HOST_RETRY_SPACING));
+        assert!(tracker.allow_attempt(now + BREAKER_COOLDOWN));
+    }
+
+    #[test]
+    fn breaker_closes_on_success_and_reopens_on_failure() {
+        let now = Instant::now();
+        let mut tracker = HostFailureTracker::default();
+        for _ in 0..BREAKER_FAILURE_THRESHOLD {
+            tracker.record_failure(now);
+        }
+        let after_cooldown = now + BREAKER_COOLDOWN;
+
+        // A failed attempt after the cool-off opens it again right away
+        tracker.record_failure(after_cooldown);
+        assert!(tracker.is_open(after_cooldown));
+
+        tracker.record_success();
+        assert!(!tracker.is_open(after_cooldown));
+        tracker.record_failure(after_cooldown);
+        assert!(!tracker.is_open(after_cooldown));
+    }
+}
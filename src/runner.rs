@@ -0,0 +1,200 @@
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Child, ExitStatus, Stdio};
+use crate::tunnels::ssh_command;
+
+/// A running ssh process, as far as the tunnel manager needs it
+pub trait TunnelProcess: Send {
+    fn id(&self) -> u32;
+    fn try_wait(&mut self) -> io::Result<Option<ExitStatus>>;
+    fn kill(&mut self) -> io::Result<()>;
+    fn wait(&mut self) -> io::Result<ExitStatus>;
+    /// Take the process's stderr stream (only the first call gets it)
+    fn take_stderr(&mut self) -> Option<Box<dyn Read + Send>>;
+    /// Take the process's stdout stream, piped only by `spawn_captured`
+    fn take_stdout(&mut self) -> Option<Box<dyn Read + Send>>;
+}
+
+impl TunnelProcess for Child {
+    fn id(&self) -> u32 {
+        Child::id(self)
+    }
+
+    fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        Child::try_wait(self)
+    }
+
+    fn kill(&mut self) -> io::Result<()> {
+        Child::kill(self)
+    }
+
+    fn wait(&mut self) -> io::Result<ExitStatus> {
+        Child::wait(self)
+    }
+
+    fn take_stderr(&mut self) -> Option<Box<dyn Read + Send>> {
+        self.stderr.take().map(|stderr| Box::new(stderr) as Box<dyn Read + Send>)
+    }
+
+    fn take_stdout(&mut self) -> Option<Box<dyn Read + Send>> {
+        self.stdout.take().map(|stdout| Box::new(stdout) as Box<dyn Read + Send>)
+    }
+}
+
+/// Runs ssh on behalf of the tunnel manager
+///
+/// The manager never builds a `Command` itself, so another runner can stand
+/// in for ssh (e.g. one simulating an immediate exit or a given stderr).
+pub trait CommandRunner: Send + Sync {
+    /// Start a long-running ssh process; stderr is piped, stdin and stdout are not used
+    fn spawn(&self, program: &Path, args: &[String]) -> io::Result<Box<dyn TunnelProcess>>;
+    /// Start a short ssh run whose stdout and stderr are both piped
+    /// The caller reads them and waits, and can kill it meanwhile (see `ConnectionTest`).
+    fn spawn_captured(&self, program: &Path, args: &[String]) -> io::Result<Box<dyn TunnelProcess>>;
+}
+
+/// Runs the given ssh client as a real process
+pub struct SshRunner;
+
+impl CommandRunner for SshRunner {
+    fn spawn(&self, program: &Path, args: &[String]) -> io::Result<Box<dyn TunnelProcess>> {
+        let child = ssh_command(program)
+            .args(args)
+            .stderr(Stdio::piped()) // Capture stderr for error detection
+            .stdout(Stdio::null())
+            .stdin(Stdio::null())
+            .spawn()?;
+        Ok(Box::new(child))
+    }
+
+    fn spawn_captured(&self, program: &Path, args: &[String]) -> io::Result<Box<dyn TunnelProcess>> {
+        let child = ssh_command(program)
+            .args(args)
+            .stderr(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stdin(Stdio::null())
+            .spawn()?;
+        Ok(Box::new(child))
+    }
+}
+
+/// Scripted stand-in for ssh, so the tunnel manager can be driven without a real ssh client
+#[cfg(test)]
+pub struct MockRunner {
+    /// Exit code of spawned processes, `None` keeps them running until killed
+    pub exit_code: Option<i32>,
+    /// What spawned processes and captured runs write to stderr
+    pub stderr: String,
+    /// Whether spawned processes hand out their stderr (false as if it weren't piped)
+    pub stderr_piped: bool,
+    /// Exit code of `spawn_captured` runs, `None` keeps them running until killed
+    pub output_code: Option<i32>,
+    /// Arguments of every spawn and captured run, in order
+    pub calls: std::sync::Mutex<Vec<Vec<String>>>,
+    /// Program of every spawn and captured run, in order
+    pub programs: std::sync::Mutex<Vec<std::path::PathBuf>>,
+    /// Set when a spawned process is killed
+    pub killed: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+#[cfg(test)]
+impl MockRunner {
+    /// A runner whose ssh stays connected and whose test command succeeds
+    pub fn success() -> Self {
+        MockRunner {
+            exit_code: None,
+            stderr: String::new(),
+            stderr_piped: true,
+            output_code: Some(0),
+            calls: std::sync::Mutex::new(Vec::new()),
+            programs: std::sync::Mutex::new(Vec::new()),
+            killed: std::sync::Arc::new(std::sync::atomic::AtomicBool::new(false)),
+        }
+    }
+
+    /// A runner whose ssh exits right away with `code`, writing `stderr`
+    pub fn exiting(code: i32, stderr: &str) -> Self {
+        MockRunner {
+            exit_code: Some(code),
+            stderr: stderr.to_string(),
+            output_code: Some(code),
+            ..MockRunner::success()
+        }
+    }
+}
+
+/// Build the exit status of a process that exited with `code`
+#[cfg(all(test, unix))]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::unix::process::ExitStatusExt;
+    ExitStatus::from_raw(code << 8)
+}
+
+#[cfg(all(test, windows))]
+fn exit_status(code: i32) -> ExitStatus {
+    use std::os::windows::process::ExitStatusExt;
+    ExitStatus::from_raw(code as u32)
+}
+
+#[cfg(test)]
+impl CommandRunner for MockRunner {
+    fn spawn(&self, program: &Path, args: &[String]) -> io::Result<Box<dyn TunnelProcess>> {
+        self.calls.lock().unwrap().push(args.to_vec());
+        self.programs.lock().unwrap().push(program.to_path_buf());
+        Ok(Box::new(MockProcess {
+            exit: self.exit_code.map(exit_status),
+            stderr: self.stderr_piped.then(|| self.stderr.clone().into_bytes()),
+            stdout: None,
+            killed: std::sync::Arc::clone(&self.killed),
+        }))
+    }
+
+    fn spawn_captured(&self, program: &Path, args: &[String]) -> io::Result<Box<dyn TunnelProcess>> {
+        self.calls.lock().unwrap().push(args.to_vec());
+        self.programs.lock().unwrap().push(program.to_path_buf());
+        Ok(Box::new(MockProcess {
+            exit: self.output_code.map(exit_status),
+            stderr: Some(self.stderr.clone().into_bytes()),
+            stdout: Some(Vec::new()),
+            killed: std::sync::Arc::clone(&self.killed),
+        }))
+    }
+}
+
+/// A process spawned by `MockRunner`
+#[cfg(test)]
+struct MockProcess {
+    exit: Option<ExitStatus>,
+    stderr: Option<Vec<u8>>,
+    stdout: Option<Vec<u8>>,
+    killed: std::sync::Arc<std::sync::atomic::AtomicBool>,
+}
+
+#[cfg(test)]
+impl TunnelProcess for MockProcess {
+    fn id(&self) -> u32 {
+        0
+    }
+
+    fn try_wait(&mut self) -> io::Result<Option<ExitStatus>> {
+        Ok(self.exit)
+    }
+
+    fn kill(&mut self) -> io::Result<()> {
+        self.killed.store(true, std::sync::atomic::Ordering::Relaxed);
+        self.exit.get_or_insert(exit_status(255));
+        Ok(())
+    }
+
+    fn wait(&mut self) -> io::Result<ExitStatus> {
+        Ok(*self.exit.get_or_insert(exit_status(255)))
+    }
+
+    fn take_stderr(&mut self) -> Option<Box<dyn Read + Send>> {
+        self.stderr.take().map(|stderr| Box::new(io::Cursor::new(stderr)) as Box<dyn Read + Send>)
+    }
+
+    fn take_stdout(&mut self) -> Option<Box<dyn Read + Send>> {
+        self.stdout.take().map(|stdout| Box::new(io::Cursor::new(stdout)) as Box<dyn Read + Send>)
+    }
+}
@@ -0,0 +1,186 @@
+use crate::config::Settings;
+use crate::error::{DrillResult, DrillError};
+use crate::tunnels::{build_ssh_args, tunnel_ssh_program, Tunnel};
+
+/// A generated service definition and the file name it should be saved under
+pub struct ServiceFile {
+    pub file_name: String,
+    pub content: String,
+}
+
+/// Generate a service file running the tunnel's ssh command for this platform
+/// A systemd user unit on Linux, a launchd agent plist on macOS.
+pub fn generate_service_file(tunnel: &Tunnel, tunnels: &[Tunnel], settings: &Settings) -> DrillResult<ServiceFile> {
+    let mut args = build_ssh_args(tunnel, tunnels, settings)?;
+    // A service can't answer prompts: fail instead of hanging
+    args.splice(0..0, ["-o".to_string(), "BatchMode=yes".to_string()]);
+    let program = tunnel_ssh_program(tunnel).to_string_lossy().to_string();
+    let slug = service_slug(&tunnel.name);
+
+    if cfg!(target_os = "linux") {
+        Ok(ServiceFile {
+            file_name: format!("drill-{}.service", slug),
+            content: systemd_unit(tunnel, &program, &args),
+        })
+    } else if cfg!(target_os = "macos") {
+        let label = format!("com.drill.tunnel.{}", slug);
+        Ok(ServiceFile {
+            file_name: format!("{}.plist", label),
+            content: launchd_plist(&label, &program, &args),
+        })
+    } else {
+        Err(DrillError::Config(
+            "Service files can only be generated on Linux (systemd) and macOS (launchd)".to_string(),
+        ))
+    }
+}
+
+/// Systemd user unit, to install in ~/.config/systemd/user
+pub fn systemd_unit(tunnel: &Tunnel, program: &str, args: &[String]) -> String {
+    let exec = std::iter::once(program)
+        .chain(args.iter().map(|a| a.as_str()))
+        .map(systemd_quote)
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        "# Generated by Drill for tunnel '{name}'
+# Install: cp drill-{slug}.service ~/.config/systemd/user/ && systemctl --user enable --now drill-{slug}
+[Unit]
+Description=Drill SSH tunnel {name}
+After=network-online.target
+Wants=network-online.target
+
+[Service]
+ExecStart={exec}
+Restart=on-failure
+RestartSec=10
+
+[Install]
+WantedBy=default.target
+",
+        name = tunnel.name,
+        slug = service_slug(&tunnel.name),
+        exec = exec,
+    )
+}
+
+/// Launchd agent plist, to install in ~/Library/LaunchAgents
+pub fn launchd_plist(label: &str, program: &str, args: &[String]) -> String {
+    let arguments: String = std::iter::once(program)
+        .chain(args.iter().map(|a| a.as_str()))
+        .map(|arg| format!("        <string>{}</string>\n", xml_escape(arg)))
+        .collect();
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<!-- Generated by Drill. Install: cp {label}.plist ~/Library/LaunchAgents/ && launchctl load ~/Library/LaunchAgents/{label}.plist -->
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{label}</string>
+    <key>ProgramArguments</key>
+    <array>
+{arguments}    </array>
+    <key>RunAtLoad</key>
+    <true/>
+    <key>KeepAlive</key>
+    <dict>
+        <key>SuccessfulExit</key>
+        <false/>
+    </dict>
+    <key>ThrottleInterval</key>
+    <integer>10</integer>
+</dict>
+</plist>
+"#,
+        label = xml_escape(label),
+        arguments = arguments,
+    )
+}
+
+/// Lowercase ascii alphanumerics and dashes, for unit names and labels
+fn service_slug(name: &str) -> String {
+    let slug: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '-' })
+        .collect();
+    let slug = slug.trim_matches('-').to_string();
+    if slug.is_empty() { "tunnel".to_string() } else { slug }
+}
+
+/// Quote an ExecStart word when it contains characters systemd would split on
+/// `%` and `$` are doubled so systemd doesn't expand them as specifiers/variables
+fn systemd_quote(arg: &str) -> String {
+    let escaped = arg.replace('%', "%%").replace('$', "$$");
+    if escaped.chars().any(|c| c.is_whitespace() || c == '"' || c == '\\' || c == ';') {
+        format!("\"{}\"", escaped.replace('\\', "\\\\").replace('"', "\\\""))
+    } else {
+        escaped
+    }
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::test_tunnel;
+
+    fn args(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn systemd_unit_runs_the_ssh_command() {
+        let tunnel = test_tunnel("Web App", "18080");
+        let unit = systemd_unit(&tunnel, "/usr/bin/ssh", &args(&["-N", "-L", "127.0.0.1:18080:127.0.0.1:80", "user@example.com"]));
+
+        assert!(unit.contains("Description=Drill SSH tunnel Web App\n"));
+        assert!(unit.contains("ExecStart=/usr/bin/ssh -N -L 127.0.0.1:18080:127.0.0.1:80 user@example.com\n"));
+        assert!(unit.contains("systemctl --user enable --now drill-web-app"));
+        assert!(unit.contains("Restart=on-failure"));
+    }
+
+    #[test]
+    fn systemd_words_are_quoted_and_escaped() {
+        assert_eq!(systemd_quote("-N"), "-N");
+        assert_eq!(systemd_quote("/keys/my key"), "\"/keys/my key\"");
+        assert_eq!(systemd_quote("100%"), "100%%");
+        assert_eq!(systemd_quote("$HOME"), "$$HOME");
+    }
+
+    #[test]
+    fn launchd_plist_lists_the_arguments() {
+        let plist = launchd_plist("com.drill.tunnel.web", "/usr/bin/ssh", &args(&["-o", "SetEnv=A=<b>&c"]));
+
+        assert!(plist.contains("<string>com.drill.tunnel.web</string>"));
+        assert!(plist.contains("        <string>/usr/bin/ssh</string>\n        <string>-o</string>\n"));
+        assert!(plist.contains("<string>SetEnv=A=&lt;b&gt;&amp;c</string>"));
+    }
+
+    #[test]
+    fn slugs_are_safe_file_names() {
+        assert_eq!(service_slug("Prod DB (eu)"), "prod-db--eu");
+        assert_eq!(service_slug("!!!"), "tunnel");
+    }
+
+    #[test]
+    fn generated_service_never_prompts() {
+        let tunnel = test_tunnel("web", "18080");
+        let result = generate_service_file(&tunnel, std::slice::from_ref(&tunnel), &Settings::default());
+        if !cfg!(any(target_os = "linux", target_os = "macos")) {
+            assert!(result.is_err());
+            return;
+        }
+
+        let service = result.unwrap();
+
+        assert!(service.file_name.contains("web"));
+        assert!(service.content.contains("BatchMode=yes"));
+    }
+}
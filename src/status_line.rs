@@ -0,0 +1,45 @@
+use std::io::Write;
+use crate::tunnels::TunnelStatus;
+
+/// Summarize tunnel statuses on one line, e.g. `2/5 connected, 1 connecting, 1 error`
+pub fn render(snapshot: &[(String, TunnelStatus)]) -> String {
+    let mut connected = 0;
+    let mut connecting = 0;
+    let mut reconnecting = 0;
+    let mut errors = 0;
+    for (_, status) in snapshot {
+        match status {
+            TunnelStatus::Connected { .. } | TunnelStatus::External => connected += 1,
+            TunnelStatus::Connecting => connecting += 1,
+            TunnelStatus::Reconnecting { .. } => reconnecting += 1,
+            TunnelStatus::Error { .. } => errors += 1,
+            TunnelStatus::Disconnected => {}
+        }
+    }
+
+    let mut line = format!(
+        "[{}] Drill: {}/{} connected",
+        chrono::Local::now().format("%H:%M:%S"),
+        connected,
+        snapshot.len()
+    );
+    for (count, label) in [(connecting, "connecting"), (reconnecting, "reconnecting"), (errors, "error")] {
+        if count > 0 {
+            line.push_str(&format!(", {} {}", count, label));
+        }
+    }
+    line
+}
+
+/// Redraw the status line in place
+pub fn print(snapshot: &[(String, TunnelStatus)]) {
+    let mut stdout = std::io::stdout();
+    // Return to the line start and clear it before drawing
+    let _ = write!(stdout, "\r\x1b[2K{}", render(snapshot));
+    let _ = stdout.flush();
+}
+
+/// Move past the status line so later output starts on a fresh line
+pub fn finish() {
+    println!();
+}
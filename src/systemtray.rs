@@ -1,102 +1,84 @@
-use tray_icon::{TrayIconBuilder, menu::{Menu, MenuItem, MenuId, PredefinedMenuItem, Submenu}, TrayIcon};
-use crate::tunnels::{Tunnel, TunnelStatus};
+use tray_icon::{TrayIconBuilder, menu::{CheckMenuItem, Menu, MenuItem, MenuId, PredefinedMenuItem, Submenu}, TrayIcon};
+use crate::config::Settings;
+use crate::tunnels::{format_bytes, format_rate, Tunnel, TunnelStats, TunnelStatus};
 use std::collections::HashMap;
+use std::time::Duration;
 
 pub struct TrayMenuIds {
     pub create: MenuId,
+    pub create_from_uri: MenuId,
+    pub open_dashboard: MenuId,
+    pub reconnect_errored: MenuId,
+    pub export_bundle: MenuId,
+    pub import_bundle: MenuId,
+    /// Only on Windows, where PuTTY keeps its sessions
+    pub import_putty: Option<MenuId>,
+    pub batch_edit: MenuId,
+    pub view_logs: MenuId,
+    pub copy_log_path: MenuId,
+    pub open_current_log: MenuId,
+    pub event_log: MenuId,
+    pub error_summary: MenuId,
+    pub start_at_login: MenuId,
+    pub check_for_updates: MenuId,
     pub about: MenuId,
     pub quit: MenuId,
+    /// Connect/disconnect toggles of the favorite tunnels at the top of the menu
+    pub tunnel_favorite_toggle: HashMap<String, MenuId>,
+    /// Connect/disconnect toggles in the Recent submenu
+    pub tunnel_recent_toggle: HashMap<String, MenuId>,
     pub tunnel_connect: HashMap<String, MenuId>,
+    pub tunnel_connect_on_port: HashMap<String, MenuId>,
     pub tunnel_disconnect: HashMap<String, MenuId>,
     pub tunnel_open_web: HashMap<String, MenuId>,
+    pub tunnel_extend_timer: HashMap<String, MenuId>,
+    pub tunnel_test: HashMap<String, MenuId>,
     pub tunnel_edit: HashMap<String, MenuId>,
+    pub tunnel_edit_as_new: HashMap<String, MenuId>,
     pub tunnel_remove: HashMap<String, MenuId>,
+    pub tunnel_service_file: HashMap<String, MenuId>,
+    pub tunnel_effective_config: HashMap<String, MenuId>,
+    pub tunnel_copy_ssh_config: HashMap<String, MenuId>,
 }
 
-/// Initialize the system tray icon with menu
-pub fn init_tray(tunnels: &Vec<Tunnel>, tunnel_statuses: &[(String, TunnelStatus)]) -> Result<(TrayIcon, TrayMenuIds), Box<dyn std::error::Error>> {
-    // Create a simple menu
-    let menu = Menu::new();
-
-    let create_tunnel = MenuItem::new("Drill New Tunnel", true, None);
-    menu.append(&create_tunnel)?;
-
-    menu.append(&PredefinedMenuItem::separator())?;
-    
-    // Add tunnels with submenu for each tunnel
-    let mut tunnel_connect_ids = HashMap::new();
-    let mut tunnel_disconnect_ids = HashMap::new();
-    let mut tunnel_open_web_ids: HashMap<String, MenuId> = HashMap::new();
-    let mut tunnel_edit_ids = HashMap::new();
-    let mut tunnel_remove_ids = HashMap::new();
-    
-    let status_map: std::collections::HashMap<_, _> = tunnel_statuses.iter().cloned().collect();
-    for tunnel in tunnels {
-        // Get current status
-        let status = status_map.get(&tunnel.name).cloned().unwrap_or(TunnelStatus::Disconnected);
-        let display_name = get_tunnel_display_name(&tunnel.name, status.clone());
-        
-        // Create submenu for each tunnel with status indicator
-        let tunnel_submenu = Submenu::new(&display_name, true);
-        
-        // Only show Connect if not connected, only show Disconnect if connected
-        match &status {
-            TunnelStatus::Disconnected | TunnelStatus::Error { .. } => {
-                let connect_item = MenuItem::new("Connect", true, None);
-                let connect_id = connect_item.id().clone();
-                tunnel_connect_ids.insert(tunnel.name.clone(), connect_id);
-                tunnel_submenu.append(&connect_item)?;
-            },
-            TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. } => {
-                let disconnect_item = MenuItem::new("Disconnect", true, None);
-                let disconnect_id = disconnect_item.id().clone();
-                tunnel_disconnect_ids.insert(tunnel.name.clone(), disconnect_id);
-                tunnel_submenu.append(&disconnect_item)?;
-                
-                let status = tunnel_statuses.iter().find(|(name, _)| name == &tunnel.name).map(|(_, status)| status.clone()).unwrap_or(TunnelStatus::Disconnected);
-                if matches!(status, TunnelStatus::Connected { .. }) {
-                    let open_web_item = MenuItem::new("Open Web", true, None);
-                    let open_web_id = open_web_item.id().clone();
-                    tunnel_open_web_ids.insert(tunnel.name.clone(), open_web_id);
-                    tunnel_submenu.append(&open_web_item)?;
-                }
-            }
-        }
-        
-        // Add Edit option (disabled when connected)
-        let is_connected = matches!(status, TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. });
-        let edit_item = MenuItem::new("Edit", !is_connected, None);
-        let edit_id = edit_item.id().clone();
-        tunnel_edit_ids.insert(tunnel.name.clone(), edit_id);
-        tunnel_submenu.append(&edit_item)?;
-        
-        // Add Remove option (disabled when connected)
-        let remove_item = MenuItem::new("Remove", !is_connected, None);
-        let remove_id = remove_item.id().clone();
-        tunnel_remove_ids.insert(tunnel.name.clone(), remove_id);
-        tunnel_submenu.append(&remove_item)?;
-        
-        menu.append(&tunnel_submenu)?;
+impl TrayMenuIds {
+    /// Name of the tunnel a per-tunnel item acts on, if `id` is one
+    pub fn tunnel_of(&self, id: &MenuId) -> Option<&str> {
+        [
+            &self.tunnel_favorite_toggle,
+            &self.tunnel_recent_toggle,
+            &self.tunnel_connect,
+            &self.tunnel_connect_on_port,
+            &self.tunnel_disconnect,
+            &self.tunnel_open_web,
+            &self.tunnel_extend_timer,
+            &self.tunnel_test,
+            &self.tunnel_edit,
+            &self.tunnel_edit_as_new,
+            &self.tunnel_remove,
+            &self.tunnel_service_file,
+            &self.tunnel_effective_config,
+            &self.tunnel_copy_ssh_config,
+        ]
+        .into_iter()
+        .flat_map(|ids| ids.iter())
+        .find(|(_, item_id)| *item_id == id)
+        .map(|(tunnel_name, _)| tunnel_name.as_str())
     }
-    
-    
-    // Add separator if there are tunnels
-    if !tunnels.is_empty() {
-        menu.append(&PredefinedMenuItem::separator())?;
+
+    /// Name of the tunnel a per-tunnel item acts on, when that tunnel is not in
+    /// `tunnels` any more (removed or renamed since the menu was built)
+    pub fn stale_tunnel(&self, id: &MenuId, tunnels: &[Tunnel]) -> Option<&str> {
+        self.tunnel_of(id).filter(|name| !tunnels.iter().any(|t| t.name == *name))
     }
-    
-    let about_item = MenuItem::new("About Drill", true, None);
-    let quit_item = MenuItem::new("Quit", true, None);
+}
 
-    let create_id = create_tunnel.id().clone();
-    let about_id = about_item.id().clone();
-    let quit_id = quit_item.id().clone();
-    
-    menu.append(&about_item)?;
-    menu.append(&quit_item)?;
+/// Initialize the system tray icon with menu
+pub fn init_tray(tunnels: &Vec<Tunnel>, tunnel_statuses: &[(String, TunnelStatus)], tunnel_stats: &HashMap<String, TunnelStats>, disconnect_timers: &HashMap<String, Duration>, port_overrides: &HashMap<String, String>, forward_warnings: &HashMap<String, String>, settings: &Settings) -> Result<(TrayIcon, TrayMenuIds), Box<dyn std::error::Error>> {
+    let (menu, menu_ids) = build_menu(tunnels, tunnel_statuses, tunnel_stats, disconnect_timers, port_overrides, forward_warnings, settings)?;
 
     // Create the tray icon with a default icon
-    let icon = create_tray_icon();
+    let icon = create_tray_icon()?;
     
     #[cfg(target_os = "macos")]
     let tray_icon = {
@@ -118,114 +100,390 @@ pub fn init_tray(tunnels: &Vec<Tunnel>, tunnel_statuses: &[(String, TunnelStatus
     };
 
     // Return the tray icon and menu IDs to keep them alive
-    Ok((tray_icon, TrayMenuIds { 
-        about: about_id, 
-        quit: quit_id, 
-        create: create_id,
-        tunnel_connect: tunnel_connect_ids,
-        tunnel_disconnect: tunnel_disconnect_ids,
-        tunnel_open_web: tunnel_open_web_ids,
-        tunnel_edit: tunnel_edit_ids,
-        tunnel_remove: tunnel_remove_ids,
-    }))
+    Ok((tray_icon, menu_ids))
 }
 
 /// Update the tray menu with current tunnel states
-pub fn update_tray_menu(tray_icon: &mut TrayIcon, tunnels: &Vec<Tunnel>, tunnel_statuses: &[(String, TunnelStatus)]) -> Result<TrayMenuIds, Box<dyn std::error::Error>> {
+pub fn update_tray_menu(tray_icon: &mut TrayIcon, tunnels: &Vec<Tunnel>, tunnel_statuses: &[(String, TunnelStatus)], tunnel_stats: &HashMap<String, TunnelStats>, disconnect_timers: &HashMap<String, Duration>, port_overrides: &HashMap<String, String>, forward_warnings: &HashMap<String, String>, settings: &Settings) -> Result<TrayMenuIds, Box<dyn std::error::Error>> {
+    let (menu, menu_ids) = build_menu(tunnels, tunnel_statuses, tunnel_stats, disconnect_timers, port_overrides, forward_warnings, settings)?;
+
+    // Update the tray icon menu
+    tray_icon.set_menu(Some(Box::new(menu)));
+
+    // Return the new menu IDs
+    Ok(menu_ids)
+}
+
+/// Build the tray menu and collect the IDs of its actionable items
+fn build_menu(tunnels: &Vec<Tunnel>, tunnel_statuses: &[(String, TunnelStatus)], tunnel_stats: &HashMap<String, TunnelStats>, disconnect_timers: &HashMap<String, Duration>, port_overrides: &HashMap<String, String>, forward_warnings: &HashMap<String, String>, settings: &Settings) -> Result<(Menu, TrayMenuIds), Box<dyn std::error::Error>> {
     // Create new menu
     let menu = Menu::new();
+    let status_map: HashMap<_, _> = tunnel_statuses.iter().cloned().collect();
+    let tunnels = sorted_tunnels(tunnels, settings);
+
+    // Favorites come first as plain toggles; they keep their submenu further down too
+    let mut tunnel_favorite_toggle_ids = HashMap::new();
+    let favorites = favorite_tunnels(&tunnels);
+    for tunnel in &favorites {
+        let status = status_map.get(&tunnel.name).cloned().unwrap_or(TunnelStatus::Disconnected);
+        let is_active = matches!(status, TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. } | TunnelStatus::External);
+        let toggle_item = CheckMenuItem::new(get_tunnel_display_name(tunnel.label(), status), true, is_active, None);
+        tunnel_favorite_toggle_ids.insert(tunnel.name.clone(), toggle_item.id().clone());
+        menu.append(&toggle_item)?;
+    }
+
+    // The last connected tunnels, newest first; absent until a tunnel has connected
+    let mut tunnel_recent_toggle_ids = HashMap::new();
+    let recent = recent_tunnels(&tunnels, settings.tray_recent_count.unwrap_or(DEFAULT_TRAY_RECENT_COUNT));
+    if !recent.is_empty() {
+        let recent_submenu = Submenu::new("Recent", true);
+        for tunnel in &recent {
+            let status = status_map.get(&tunnel.name).cloned().unwrap_or(TunnelStatus::Disconnected);
+            let is_active = matches!(status, TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. } | TunnelStatus::External);
+            let toggle_item = CheckMenuItem::new(get_tunnel_display_name(tunnel.label(), status), true, is_active, None);
+            tunnel_recent_toggle_ids.insert(tunnel.name.clone(), toggle_item.id().clone());
+            recent_submenu.append(&toggle_item)?;
+        }
+        menu.append(&recent_submenu)?;
+    }
+    if !favorites.is_empty() || !recent.is_empty() {
+        menu.append(&PredefinedMenuItem::separator())?;
+    }
 
-    let create_tunnel = MenuItem::new("Drill New Tunnel", true, None);
+    // Managed mode keeps connect/disconnect, everything that changes tunnels is disabled
+    let editable = !settings.locked;
+    if settings.locked {
+        menu.append(&MenuItem::new("Managed by your administrator", false, None))?;
+    }
+    let create_tunnel = MenuItem::new("Drill New Tunnel", editable, None);
+    let create_from_uri_item = MenuItem::new("Paste Connection URI", editable, None);
+    let open_dashboard_item = MenuItem::new("Open Dashboard...", true, None);
     menu.append(&create_tunnel)?;
+    menu.append(&create_from_uri_item)?;
+    menu.append(&open_dashboard_item)?;
 
     menu.append(&PredefinedMenuItem::separator())?;
     
     // Add tunnels with submenu for each tunnel
     let mut tunnel_connect_ids = HashMap::new();
+    let mut tunnel_connect_on_port_ids = HashMap::new();
     let mut tunnel_disconnect_ids = HashMap::new();
     let mut tunnel_open_web_ids: HashMap<String, MenuId> = HashMap::new();
+    let mut tunnel_extend_timer_ids = HashMap::new();
+    let mut tunnel_test_ids = HashMap::new();
     let mut tunnel_edit_ids = HashMap::new();
+    let mut tunnel_edit_as_new_ids = HashMap::new();
     let mut tunnel_remove_ids = HashMap::new();
+    let mut tunnel_service_file_ids = HashMap::new();
+    let mut tunnel_effective_config_ids = HashMap::new();
+    let mut tunnel_copy_ssh_config_ids = HashMap::new();
     
-    for tunnel in tunnels {
-        // Get current status from tunnel_statuses
-        let status = tunnel_statuses.iter().find(|(name, _)| name == &tunnel.name).map(|(_, status)| status.clone()).unwrap_or(TunnelStatus::Disconnected);
-        let display_name = get_tunnel_display_name(&tunnel.name, status.clone());
+    for (group_label, group) in group_tunnels(&tunnels, settings) {
+        // Long lists go into alphabetical submenus; the id maps stay keyed by tunnel name
+        let group_submenu = group_label.map(|label| Submenu::new(label, true));
+        for tunnel in group {
+            // Get current status
+            let status = status_map.get(&tunnel.name).cloned().unwrap_or(TunnelStatus::Disconnected);
+            let mut display_name = get_tunnel_display_name(tunnel.label(), status.clone());
+            if let Some(local_port) = port_overrides.get(&tunnel.name) {
+                display_name = format!("{} (on port {})", display_name, local_port);
+            }
+            // Flagged only: the tunnel is up, just a forward's target isn't
+            let forward_warning = forward_warnings.get(&tunnel.name);
+            if forward_warning.is_some() {
+                display_name = format!("{} \u{26A0}", display_name);
+            }
+            let timer_left = disconnect_timers.get(&tunnel.name);
+            if let Some(left) = timer_left {
+                display_name = format!("{} ({} left)", display_name, format_time_left(*left));
+            }
+            if tunnel.is_inventory_template() {
+                display_name = format!("{} (template)", tunnel.label());
+            }
         
-        // Create submenu for each tunnel with status indicator
-        let tunnel_submenu = Submenu::new(&display_name, true);
+            // Create submenu for each tunnel with status indicator
+            let tunnel_submenu = Submenu::new(&display_name, true);
         
-        // Only show Connect if not connected, only show Disconnect if connected
-        match &status {
-            TunnelStatus::Disconnected | TunnelStatus::Error { .. } => {
-                let connect_item = MenuItem::new("Connect", true, None);
-                let connect_id = connect_item.id().clone();
-                tunnel_connect_ids.insert(tunnel.name.clone(), connect_id);
-                tunnel_submenu.append(&connect_item)?;
-            },
-            TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. } => {
-                let disconnect_item = MenuItem::new("Disconnect", true, None);
-                let disconnect_id = disconnect_item.id().clone();
-                tunnel_disconnect_ids.insert(tunnel.name.clone(), disconnect_id);
-                tunnel_submenu.append(&disconnect_item)?;
-                
-                // Add "Open Web" button when connected
-                if matches!(status, TunnelStatus::Connected { .. }) {
+            // Only show Connect if not connected, only show Disconnect if connected
+            match &status {
+                // Its generated tunnels are listed on their own
+                _ if tunnel.is_inventory_template() => {
+                    let template_item = MenuItem::new("Generates one tunnel per inventory host", false, None);
+                    tunnel_submenu.append(&template_item)?;
+                }
+                TunnelStatus::Disconnected | TunnelStatus::Error { .. } => {
+                    let connect_item = MenuItem::new("Connect", true, None);
+                    let connect_id = connect_item.id().clone();
+                    tunnel_connect_ids.insert(tunnel.name.clone(), connect_id);
+                    tunnel_submenu.append(&connect_item)?;
+
+                    let connect_on_port_item = MenuItem::new("Connect on Port...", true, None);
+                    tunnel_connect_on_port_ids.insert(tunnel.name.clone(), connect_on_port_item.id().clone());
+                    tunnel_submenu.append(&connect_on_port_item)?;
+                },
+                TunnelStatus::External => {
+                    // Drill can't stop a process it didn't start, only stop watching it
+                    let disconnect_item = MenuItem::new("Stop Watching External SSH", true, None);
+                    tunnel_disconnect_ids.insert(tunnel.name.clone(), disconnect_item.id().clone());
+                    tunnel_submenu.append(&disconnect_item)?;
+
                     let open_web_item = MenuItem::new("Open Web", true, None);
-                    let open_web_id = open_web_item.id().clone();
-                    tunnel_open_web_ids.insert(tunnel.name.clone(), open_web_id);
+                    tunnel_open_web_ids.insert(tunnel.name.clone(), open_web_item.id().clone());
                     tunnel_submenu.append(&open_web_item)?;
                 }
+                TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. } => {
+                    let disconnect_item = MenuItem::new("Disconnect", true, None);
+                    let disconnect_id = disconnect_item.id().clone();
+                    tunnel_disconnect_ids.insert(tunnel.name.clone(), disconnect_id);
+                    tunnel_submenu.append(&disconnect_item)?;
+                
+                    // Add "Open Web" button when connected
+                    if matches!(status, TunnelStatus::Connected { .. }) {
+                        let open_web_item = MenuItem::new("Open Web", true, None);
+                        let open_web_id = open_web_item.id().clone();
+                        tunnel_open_web_ids.insert(tunnel.name.clone(), open_web_id);
+                        tunnel_submenu.append(&open_web_item)?;
+
+                        if timer_left.is_some() {
+                            let extend_item = MenuItem::new("Keep 30 More Minutes", true, None);
+                            tunnel_extend_timer_ids.insert(tunnel.name.clone(), extend_item.id().clone());
+                            tunnel_submenu.append(&extend_item)?;
+                        }
+
+                        if forward_warning.is_some() {
+                            let warning_item = MenuItem::new("Remote target unreachable", false, None);
+                            tunnel_submenu.append(&warning_item)?;
+                        }
+
+                        // Show the estimated I/O rate (informational, not clickable; Linux only)
+                        if let Some(stats) = tunnel_stats.get(&tunnel.name) {
+                            let traffic_item = MenuItem::new(get_traffic_label(stats), false, None);
+                            tunnel_submenu.append(&traffic_item)?;
+                        }
+                    }
+                }
             }
-        }
         
-        // Add Edit option (disabled when connected)
-        let is_connected = matches!(status, TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. });
-        let edit_item = MenuItem::new("Edit", !is_connected, None);
-        let edit_id = edit_item.id().clone();
-        tunnel_edit_ids.insert(tunnel.name.clone(), edit_id);
-        tunnel_submenu.append(&edit_item)?;
+            // A separate ssh without forwards, so it doesn't disturb a running tunnel
+            let test_item = MenuItem::new("Test Connection", !tunnel.is_inventory_template(), None);
+            tunnel_test_ids.insert(tunnel.name.clone(), test_item.id().clone());
+            tunnel_submenu.append(&test_item)?;
+
+            // Add Edit option (disabled when connected)
+            // A generated tunnel is changed through its template
+            let is_connected = matches!(status, TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. });
+            let changeable = editable && tunnel.generated_from.is_none();
+            let edit_item = MenuItem::new("Edit", changeable && !is_connected, None);
+            let edit_id = edit_item.id().clone();
+            tunnel_edit_ids.insert(tunnel.name.clone(), edit_id);
+            tunnel_submenu.append(&edit_item)?;
+
+            // The source isn't modified, so this is available while connected
+            let edit_as_new_item = MenuItem::new("Edit as New...", editable, None);
+            tunnel_edit_as_new_ids.insert(tunnel.name.clone(), edit_as_new_item.id().clone());
+            tunnel_submenu.append(&edit_as_new_item)?;
         
-        // Add Remove option (disabled when connected)
-        let remove_item = MenuItem::new("Remove", !is_connected, None);
-        let remove_id = remove_item.id().clone();
-        tunnel_remove_ids.insert(tunnel.name.clone(), remove_id);
-        tunnel_submenu.append(&remove_item)?;
+            // Add Remove option (disabled when connected)
+            let remove_item = MenuItem::new("Remove", changeable && !is_connected, None);
+            let remove_id = remove_item.id().clone();
+            tunnel_remove_ids.insert(tunnel.name.clone(), remove_id);
+            tunnel_submenu.append(&remove_item)?;
+
+            let service_file_item = MenuItem::new("Generate Service File...", true, None);
+            tunnel_service_file_ids.insert(tunnel.name.clone(), service_file_item.id().clone());
+            tunnel_submenu.append(&service_file_item)?;
+
+            let effective_config_item = MenuItem::new("Show Effective Config...", true, None);
+            tunnel_effective_config_ids.insert(tunnel.name.clone(), effective_config_item.id().clone());
+            tunnel_submenu.append(&effective_config_item)?;
+
+            let copy_ssh_config_item = MenuItem::new("Copy as SSH Config", true, None);
+            tunnel_copy_ssh_config_ids.insert(tunnel.name.clone(), copy_ssh_config_item.id().clone());
+            tunnel_submenu.append(&copy_ssh_config_item)?;
         
-        menu.append(&tunnel_submenu)?;
+            match &group_submenu {
+                Some(group_submenu) => group_submenu.append(&tunnel_submenu)?,
+                None => menu.append(&tunnel_submenu)?,
+            }
+        }
+        if let Some(group_submenu) = &group_submenu {
+            menu.append(group_submenu)?;
+        }
     }
     
-    // No manager to drop
-    
     // Add separator if there are tunnels
     if !tunnels.is_empty() {
         menu.append(&PredefinedMenuItem::separator())?;
     }
+
+    let has_errors = tunnel_statuses.iter().any(|(_, status)| matches!(status, TunnelStatus::Error { .. }));
+    let reconnect_errored_item = MenuItem::new("Reconnect Errored Tunnels", has_errors, None);
+    menu.append(&reconnect_errored_item)?;
+
+    let export_bundle_item = MenuItem::new("Export Bundle...", !tunnels.is_empty(), None);
+    let import_bundle_item = MenuItem::new("Import Bundle...", editable, None);
+    let batch_edit_item = MenuItem::new("Batch Edit...", editable && !tunnels.is_empty(), None);
+    menu.append(&export_bundle_item)?;
+    menu.append(&import_bundle_item)?;
+    let import_putty_item = cfg!(target_os = "windows").then(|| MenuItem::new("Import PuTTY Sessions...", editable, None));
+    if let Some(import_putty_item) = &import_putty_item {
+        menu.append(import_putty_item)?;
+    }
+    menu.append(&batch_edit_item)?;
+    menu.append(&PredefinedMenuItem::separator())?;
+
+    let view_logs_item = MenuItem::new("View Logs...", true, None);
+    let event_log_item = MenuItem::new("Event Log...", true, None);
+    let copy_log_path_item = MenuItem::new("Copy Log Path", true, None);
+    let open_current_log_item = MenuItem::new("Open Current Log", true, None);
+    menu.append(&view_logs_item)?;
+    menu.append(&copy_log_path_item)?;
+    menu.append(&open_current_log_item)?;
+    menu.append(&event_log_item)?;
+    let error_summary_item = MenuItem::new("Error Summary...", true, None);
+    menu.append(&error_summary_item)?;
+
+    // Checked state reflects the platform autostart entry, not a stored setting
+    let start_at_login_item = CheckMenuItem::new("Start Drill at Login", true, crate::autostart::is_autostart_enabled(), None);
+    menu.append(&start_at_login_item)?;
+    menu.append(&PredefinedMenuItem::separator())?;
     
+    let check_for_updates_item = MenuItem::new("Check for Updates...", true, None);
     let about_item = MenuItem::new("About Drill", true, None);
     let quit_item = MenuItem::new("Quit", true, None);
 
     let create_id = create_tunnel.id().clone();
+    let create_from_uri_id = create_from_uri_item.id().clone();
+    let open_dashboard_id = open_dashboard_item.id().clone();
+    let reconnect_errored_id = reconnect_errored_item.id().clone();
+    let export_bundle_id = export_bundle_item.id().clone();
+    let import_bundle_id = import_bundle_item.id().clone();
+    let import_putty_id = import_putty_item.map(|item| item.id().clone());
+    let batch_edit_id = batch_edit_item.id().clone();
+    let view_logs_id = view_logs_item.id().clone();
+    let copy_log_path_id = copy_log_path_item.id().clone();
+    let open_current_log_id = open_current_log_item.id().clone();
+    let event_log_id = event_log_item.id().clone();
+    let error_summary_id = error_summary_item.id().clone();
+    let start_at_login_id = start_at_login_item.id().clone();
+    let check_for_updates_id = check_for_updates_item.id().clone();
     let about_id = about_item.id().clone();
     let quit_id = quit_item.id().clone();
     
+    menu.append(&check_for_updates_item)?;
     menu.append(&about_item)?;
     menu.append(&quit_item)?;
 
-    // Update the tray icon menu
-    tray_icon.set_menu(Some(Box::new(menu)));
-
-    // Return the new menu IDs
-    Ok(TrayMenuIds { 
+    Ok((menu, TrayMenuIds { 
+        check_for_updates: check_for_updates_id,
         about: about_id, 
         quit: quit_id, 
         create: create_id,
+        create_from_uri: create_from_uri_id,
+        open_dashboard: open_dashboard_id,
+        reconnect_errored: reconnect_errored_id,
+        export_bundle: export_bundle_id,
+        import_bundle: import_bundle_id,
+        import_putty: import_putty_id,
+        batch_edit: batch_edit_id,
+        view_logs: view_logs_id,
+        copy_log_path: copy_log_path_id,
+        open_current_log: open_current_log_id,
+        event_log: event_log_id,
+        error_summary: error_summary_id,
+        start_at_login: start_at_login_id,
+        tunnel_favorite_toggle: tunnel_favorite_toggle_ids,
+        tunnel_recent_toggle: tunnel_recent_toggle_ids,
         tunnel_connect: tunnel_connect_ids,
+        tunnel_connect_on_port: tunnel_connect_on_port_ids,
         tunnel_disconnect: tunnel_disconnect_ids,
         tunnel_open_web: tunnel_open_web_ids,
+        tunnel_extend_timer: tunnel_extend_timer_ids,
+        tunnel_test: tunnel_test_ids,
         tunnel_edit: tunnel_edit_ids,
+        tunnel_edit_as_new: tunnel_edit_as_new_ids,
         tunnel_remove: tunnel_remove_ids,
-    })
+        tunnel_service_file: tunnel_service_file_ids,
+        tunnel_effective_config: tunnel_effective_config_ids,
+        tunnel_copy_ssh_config: tunnel_copy_ssh_config_ids,
+    }))
+}
+
+/// Get the tunnels in the order they should appear in the menu
+/// Most recently connected first when enabled in the settings, insertion order otherwise
+fn sorted_tunnels(tunnels: &[Tunnel], settings: &Settings) -> Vec<Tunnel> {
+    let mut tunnels = tunnels.to_vec();
+    if settings.sort_tunnels_by_recent {
+        // Stable sort: never-connected tunnels keep their relative order at the end
+        tunnels.sort_by(|a, b| b.last_connected_at.cmp(&a.last_connected_at));
+    }
+    tunnels
+}
+
+/// Get the favorite tunnels, pinned at the top of the menu in list order
+fn favorite_tunnels(tunnels: &[Tunnel]) -> Vec<&Tunnel> {
+    tunnels.iter().filter(|t| t.favorite).collect()
+}
+
+/// Default number of tunnels in the tray's Recent submenu
+pub const DEFAULT_TRAY_RECENT_COUNT: usize = 5;
+
+/// Get up to `count` tunnels that have connected before, most recent first
+pub fn recent_tunnels(tunnels: &[Tunnel], count: usize) -> Vec<&Tunnel> {
+    let mut recent: Vec<&Tunnel> = tunnels.iter().filter(|t| t.last_connected_at.is_some()).collect();
+    recent.sort_by(|a, b| b.last_connected_at.cmp(&a.last_connected_at));
+    recent.truncate(count);
+    recent
+}
+
+/// Default number of tunnels above which the tray groups them into submenus
+pub const DEFAULT_TRAY_GROUP_THRESHOLD: usize = 25;
+
+/// Split the tunnels into the groups shown in the tray menu
+///
+/// Up to the threshold a single unlabelled group keeps the flat menu. Past it
+/// the tunnels are sorted by the label shown and chunked into submenus of at most
+/// `threshold` entries, labelled by the initials they span (e.g. "A – F").
+fn group_tunnels<'a>(tunnels: &'a [Tunnel], settings: &Settings) -> Vec<(Option<String>, Vec<&'a Tunnel>)> {
+    let threshold = settings.tray_group_threshold.unwrap_or(DEFAULT_TRAY_GROUP_THRESHOLD);
+    if threshold == 0 || tunnels.len() <= threshold {
+        return vec![(None, tunnels.iter().collect())];
+    }
+
+    let mut by_name: Vec<&Tunnel> = tunnels.iter().collect();
+    by_name.sort_by_cached_key(|tunnel| tunnel.label().to_lowercase());
+    by_name
+        .chunks(threshold)
+        .map(|chunk| {
+            let first = group_initial(chunk[0].label());
+            let last = group_initial(chunk[chunk.len() - 1].label());
+            let label = if first == last {
+                first.to_string()
+            } else {
+                format!("{} \u{2013} {}", first, last)
+            };
+            (Some(label), chunk.to_vec())
+        })
+        .collect()
+}
+
+/// Get the initial a tunnel is filed under ('#' for names not starting with a letter)
+fn group_initial(name: &str) -> char {
+    match name.chars().next() {
+        Some(c) if c.is_alphabetic() => c.to_uppercase().next().unwrap_or(c),
+        _ => '#',
+    }
+}
+
+/// Get the I/O estimate label for a connected tunnel
+///
+/// Shows the rate between the last two monitor samples, or the totals so far
+/// until there are two. The figures cover the whole ssh process (see `TunnelStats`).
+pub fn get_traffic_label(stats: &TunnelStats) -> String {
+    match stats.rate {
+        Some(rate) => format!("I/O \u{2248} read {}, written {}", format_rate(rate.bytes_in), format_rate(rate.bytes_out)),
+        None => format!("I/O \u{2248} read {}, written {}", format_bytes(stats.bytes_in), format_bytes(stats.bytes_out)),
+    }
 }
 
 /// Get status indicator for tunnel name
@@ -236,12 +494,24 @@ pub fn get_tunnel_display_name(name: &str, status: TunnelStatus) -> String {
         TunnelStatus::Connected { .. } => "● ",    // Filled circle (connected/green)
         TunnelStatus::Error { .. } => "✗ ",        // X mark (error/red)
         TunnelStatus::Reconnecting { .. } => "↻ ",  // Refresh/reconnecting
+        TunnelStatus::External => "◉ ",  // Circled dot (forwarded by an ssh outside Drill)
     };
     format!("{}{}", indicator, name)
 }
 
+/// Format the time left on a disconnect timer, e.g. "25m" or "1h 05m"
+fn format_time_left(left: Duration) -> String {
+    // Round up so the label never shows 0m while still connected
+    let minutes = left.as_secs().div_ceil(60);
+    if minutes >= 60 {
+        format!("{}h {:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
 /// Create a monochromatic icon suitable for system tray
-fn create_tray_icon() -> tray_icon::Icon {
+fn create_tray_icon() -> Result<tray_icon::Icon, tray_icon::BadIcon> {
     // Create a monochromatic icon suitable for macOS menu bar (template mode)
     // Using black/white for best template rendering
     let width = 22;  // macOS menu bar standard height
@@ -277,13 +547,129 @@ fn create_tray_icon() -> tray_icon::Icon {
         }
     }
     
-    match tray_icon::Icon::from_rgba(rgba, width as u32, height as u32) {
-        Ok(icon) => {
-            icon
-        },
-        Err(_e) => {
-            // log_print(&format!("Error creating icon: {}", _e));
-            panic!("Failed to create tray icon");
+    tray_icon::Icon::from_rgba(rgba, width as u32, height as u32)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::test_tunnel;
+
+    /// Menu ids with no per-tunnel items
+    fn menu_ids() -> TrayMenuIds {
+        let id = |name: &str| MenuId::new(name);
+        TrayMenuIds {
+            create: id("create"),
+            create_from_uri: id("create_from_uri"),
+            open_dashboard: id("open_dashboard"),
+            reconnect_errored: id("reconnect_errored"),
+            export_bundle: id("export_bundle"),
+            import_bundle: id("import_bundle"),
+            import_putty: None,
+            batch_edit: id("batch_edit"),
+            view_logs: id("view_logs"),
+            copy_log_path: id("copy_log_path"),
+            open_current_log: id("open_current_log"),
+            event_log: id("event_log"),
+            error_summary: id("error_summary"),
+            start_at_login: id("start_at_login"),
+            check_for_updates: id("check_for_updates"),
+            about: id("about"),
+            quit: id("quit"),
+            tunnel_favorite_toggle: HashMap::new(),
+            tunnel_recent_toggle: HashMap::new(),
+            tunnel_connect: HashMap::new(),
+            tunnel_connect_on_port: HashMap::new(),
+            tunnel_disconnect: HashMap::new(),
+            tunnel_open_web: HashMap::new(),
+            tunnel_extend_timer: HashMap::new(),
+            tunnel_test: HashMap::new(),
+            tunnel_edit: HashMap::new(),
+            tunnel_edit_as_new: HashMap::new(),
+            tunnel_remove: HashMap::new(),
+            tunnel_service_file: HashMap::new(),
+            tunnel_effective_config: HashMap::new(),
+            tunnel_copy_ssh_config: HashMap::new(),
         }
     }
+
+    fn favorite(name: &str) -> Tunnel {
+        Tunnel { favorite: true, ..test_tunnel(name, "18080") }
+    }
+
+    #[test]
+    fn favorites_are_pinned_and_keep_their_place() {
+        let tunnels = vec![test_tunnel("api", "18080"), favorite("web"), test_tunnel("cache", "18081"), favorite("db")];
+
+        let pinned: Vec<&str> = favorite_tunnels(&tunnels).iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(pinned, ["web", "db"]);
+
+        // The regular list still holds every tunnel, favorites included
+        let groups = group_tunnels(&tunnels, &Settings::default());
+        let listed: Vec<&str> = groups.iter().flat_map(|(_, group)| group.iter().map(|t| t.name.as_str())).collect();
+        assert_eq!(listed, ["api", "web", "cache", "db"]);
+    }
+
+    #[test]
+    fn favorite_and_regular_items_act_on_the_same_tunnel() {
+        let mut ids = menu_ids();
+        ids.tunnel_favorite_toggle.insert("web".to_string(), MenuId::new("web-favorite"));
+        ids.tunnel_connect.insert("web".to_string(), MenuId::new("web-connect"));
+        ids.tunnel_connect.insert("db".to_string(), MenuId::new("db-connect"));
+
+        assert_eq!(ids.tunnel_of(&MenuId::new("web-favorite")), Some("web"));
+        assert_eq!(ids.tunnel_of(&MenuId::new("web-connect")), Some("web"));
+        assert_eq!(ids.tunnel_of(&MenuId::new("db-connect")), Some("db"));
+        assert_eq!(ids.tunnel_of(&ids.quit.clone()), None);
+    }
+
+    #[test]
+    fn items_of_removed_tunnels_are_stale() {
+        // The menu was built with web and db, then db was removed and the rebuild failed
+        let mut ids = menu_ids();
+        ids.tunnel_connect.insert("web".to_string(), MenuId::new("web-connect"));
+        ids.tunnel_remove.insert("db".to_string(), MenuId::new("db-remove"));
+        let tunnels = vec![test_tunnel("web", "18080")];
+
+        assert_eq!(ids.stale_tunnel(&MenuId::new("db-remove"), &tunnels), Some("db"));
+        assert_eq!(ids.stale_tunnel(&MenuId::new("web-connect"), &tunnels), None);
+        assert_eq!(ids.stale_tunnel(&ids.create.clone(), &tunnels), None);
+    }
+
+    /// A tunnel last connected `minutes_ago` minutes ago
+    fn connected(name: &str, minutes_ago: i64) -> Tunnel {
+        let at = chrono::Utc::now() - chrono::Duration::minutes(minutes_ago);
+        Tunnel { last_connected_at: Some(at), ..test_tunnel(name, "18080") }
+    }
+
+    #[test]
+    fn recent_tunnels_are_most_recent_first_and_capped() {
+        let tunnels = vec![
+            connected("api", 30),
+            test_tunnel("never", "18081"),
+            connected("web", 5),
+            connected("db", 60),
+            connected("cache", 10),
+        ];
+
+        let names = |recent: Vec<&Tunnel>| recent.iter().map(|t| t.name.clone()).collect::<Vec<_>>();
+        assert_eq!(names(recent_tunnels(&tunnels, 3)), ["web", "cache", "api"]);
+        assert_eq!(names(recent_tunnels(&tunnels, DEFAULT_TRAY_RECENT_COUNT)), ["web", "cache", "api", "db"]);
+    }
+
+    #[test]
+    fn no_recent_tunnels_until_one_connects() {
+        let tunnels = vec![test_tunnel("web", "18080"), test_tunnel("db", "18081")];
+
+        assert!(recent_tunnels(&tunnels, DEFAULT_TRAY_RECENT_COUNT).is_empty());
+    }
+
+    #[test]
+    fn traffic_label_prefers_the_current_rate() {
+        let mut stats = TunnelStats { bytes_in: 3 * 1024 * 1024, bytes_out: 2048, ..TunnelStats::default() };
+        assert_eq!(get_traffic_label(&stats), "I/O \u{2248} read 3.0 MB, written 2.0 KB");
+
+        stats.rate = Some(crate::tunnels::IoRate { bytes_in: 512, bytes_out: 1536 });
+        assert_eq!(get_traffic_label(&stats), "I/O \u{2248} read 512 B/s, written 1.5 KB/s");
+    }
 }
@@ -0,0 +1,134 @@
+use std::fs;
+use std::path::Path;
+use crate::error::{DrillResult, DrillError};
+
+/// Reusable tunnel settings, without the per-instance name and ports
+#[derive(Debug, Clone, serde::Deserialize, serde::Serialize)]
+pub struct TunnelTemplate {
+    pub name: String,
+    pub local_host: String,
+    pub remote_host: String,
+    pub ssh_user: String,
+    pub ssh_host: String,
+    pub ssh_port: String,
+    #[serde(default)]
+    pub private_key: String,
+    #[serde(default)]
+    pub auto_start: bool,
+    #[serde(default)]
+    pub ssh_config_file: String,
+    #[serde(default)]
+    pub identities_only: bool,
+    #[serde(default)]
+    pub auth_mode: crate::tunnels::AuthMode,
+}
+
+/// Load all templates, an absent file meaning none were saved yet
+pub fn load_templates(path: &Path) -> DrillResult<Vec<TunnelTemplate>> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+    let content = fs::read_to_string(path)?;
+    let templates: Vec<TunnelTemplate> = serde_yaml::from_str(&content)?;
+    Ok(templates)
+}
+
+/// Get a template by name
+pub fn get_template(path: &Path, name: &str) -> DrillResult<TunnelTemplate> {
+    load_templates(path)?
+        .into_iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| DrillError::Config(format!("Template '{}' not found", name)))
+}
+
+/// Save a template, replacing any existing template with the same name
+pub fn save_template(path: &Path, template: TunnelTemplate) -> DrillResult<()> {
+    let mut templates = load_templates(path)?;
+    match templates.iter_mut().find(|t| t.name == template.name) {
+        Some(existing) => *existing = template,
+        None => templates.push(template),
+    }
+    write_templates(path, &templates)
+}
+
+/// Delete a template by name
+pub fn delete_template(path: &Path, name: &str) -> DrillResult<()> {
+    let mut templates = load_templates(path)?;
+    templates.retain(|t| t.name != name);
+    write_templates(path, &templates)
+}
+
+fn write_templates(path: &Path, templates: &[TunnelTemplate]) -> DrillResult<()> {
+    let yaml = serde_yaml::to_string(templates)?;
+    fs::write(path, yaml)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A templates file of its own for each test
+    fn templates_path(name: &str) -> std::path::PathBuf {
+        let path = std::env::temp_dir().join(format!("drill-templates-{}-{}.yaml", std::process::id(), name));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn template(name: &str, ssh_host: &str) -> TunnelTemplate {
+        TunnelTemplate {
+            name: name.to_string(),
+            local_host: "127.0.0.1".to_string(),
+            remote_host: "db.internal".to_string(),
+            ssh_user: "deploy".to_string(),
+            ssh_host: ssh_host.to_string(),
+            ssh_port: "22".to_string(),
+            private_key: "~/.ssh/id_ed25519".to_string(),
+            auto_start: false,
+            ssh_config_file: String::new(),
+            identities_only: true,
+            auth_mode: crate::tunnels::AuthMode::Agent,
+        }
+    }
+
+    #[test]
+    fn templates_round_trip_through_the_file() {
+        let path = templates_path("round-trip");
+        assert!(load_templates(&path).unwrap().is_empty());
+
+        save_template(&path, template("bastion", "bastion.example.com")).unwrap();
+        let loaded = get_template(&path, "bastion").unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(loaded.ssh_host, "bastion.example.com");
+        assert_eq!(loaded.private_key, "~/.ssh/id_ed25519");
+        assert!(loaded.identities_only);
+        assert_eq!(loaded.auth_mode, crate::tunnels::AuthMode::Agent);
+    }
+
+    #[test]
+    fn saving_a_template_replaces_the_one_with_its_name() {
+        let path = templates_path("replace");
+        save_template(&path, template("bastion", "old.example.com")).unwrap();
+        save_template(&path, template("other", "other.example.com")).unwrap();
+        save_template(&path, template("bastion", "new.example.com")).unwrap();
+
+        let templates = load_templates(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(templates.len(), 2);
+        assert_eq!(templates[0].ssh_host, "new.example.com");
+    }
+
+    #[test]
+    fn deleted_template_is_not_found() {
+        let path = templates_path("delete");
+        save_template(&path, template("bastion", "bastion.example.com")).unwrap();
+
+        delete_template(&path, "bastion").unwrap();
+        let result = get_template(&path, "bastion");
+        let _ = fs::remove_file(&path);
+
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,61 @@
+use std::time::Duration;
+
+/// Version of this build
+pub const CURRENT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// GitHub API endpoint for the latest published release
+const LATEST_RELEASE_URL: &str = "https://api.github.com/repos/vinceajello/drill/releases/latest";
+
+/// A published Drill release
+#[derive(Debug, Clone)]
+pub struct ReleaseInfo {
+    pub version: String,
+    pub url: String,
+}
+
+impl ReleaseInfo {
+    /// Whether this release is newer than the running build
+    pub fn is_newer(&self) -> bool {
+        parse_version(&self.version) > parse_version(CURRENT_VERSION)
+    }
+}
+
+/// Subset of the GitHub release payload we care about
+#[derive(serde::Deserialize)]
+struct GithubRelease {
+    tag_name: String,
+    html_url: String,
+}
+
+/// Fetch the latest published release
+/// Returns None when offline or on any error; this is a blocking call
+pub fn fetch_latest_release() -> Option<ReleaseInfo> {
+    let response = ureq::get(LATEST_RELEASE_URL)
+        .set("User-Agent", &format!("drill/{}", CURRENT_VERSION))
+        .set("Accept", "application/vnd.github+json")
+        .timeout(Duration::from_secs(10))
+        .call()
+        .ok()?;
+    let release: GithubRelease = response.into_json().ok()?;
+    Some(ReleaseInfo {
+        version: release.tag_name.trim_start_matches('v').to_string(),
+        url: release.html_url,
+    })
+}
+
+/// Check whether a newer release than the running build is available
+pub fn check_for_update() -> Option<ReleaseInfo> {
+    fetch_latest_release().filter(ReleaseInfo::is_newer)
+}
+
+/// Parse a dotted version like `0.1.2` into comparable numbers
+/// Pre-release suffixes (`-beta`) and non-numeric parts are ignored
+fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
@@ -1,31 +1,52 @@
-use iced::widget::{column, container, text, Column, Image};
+use iced::widget::{button, column, container, text, Column, Image};
 use iced::{Center, Element, Length};
+use crate::updates::{ReleaseInfo, CURRENT_VERSION};
 
 #[derive(Debug, Clone)]
 pub enum Message {
+    OpenReleasePage(String),
 }
 
-pub fn view<'a>() -> Element<'a, Message> {
+pub fn view<'a>(latest_release: &'a Option<ReleaseInfo>, checking_for_update: bool) -> Element<'a, Message> {
     // Load the icon
     let icon_bytes = include_bytes!("../../resources/icon.png");
     let icon_handle = iced::widget::image::Handle::from_bytes(icon_bytes.as_slice());
 
-    let content: Column<'a, Message> = column![
+    let mut content: Column<'a, Message> = column![
         Image::new(icon_handle).width(64).height(64),
         text("").size(4),
         text("Drill").size(24),
-        text("Version 0.1.1").size(14),
-        text("").size(8),
-        text("A multi-platform tunnel drilling application"),
-        text("for macOS, Windows, and Linux"),
-        text("").size(8),
-        text("enjoy drill :)").size(12),
-        text("").size(16)
+        text(format!("Version {}", CURRENT_VERSION)).size(14),
     ]
     .spacing(10)
     .padding(20)
     .align_x(Center);
 
+    // Only shown once the user asked for an update check
+    if checking_for_update {
+        content = content.push(text("Checking for updates...").size(12));
+    } else if let Some(release) = latest_release {
+        if release.is_newer() {
+            content = content
+                .push(text(format!("Latest version: {}", release.version)).size(12))
+                .push(
+                    button("Open Release Page")
+                        .on_press(Message::OpenReleasePage(release.url.clone()))
+                        .style(button::primary),
+                );
+        } else {
+            content = content.push(text("Drill is up to date").size(12));
+        }
+    }
+
+    content = content
+        .push(text("").size(8))
+        .push(text("A multi-platform tunnel drilling application"))
+        .push(text("for macOS, Windows, and Linux"))
+        .push(text("").size(8))
+        .push(text("enjoy drill :)").size(12))
+        .push(text("").size(16));
+
     container(content)
         .width(Length::Fill)
         .height(Length::Fill)
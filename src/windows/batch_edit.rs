@@ -0,0 +1,189 @@
+use iced::widget::{button, column, pick_list, row, scrollable, text, text_input, Column};
+use iced::{Center, Element, Length};
+use crate::tunnels::Tunnel;
+
+/// Tunnel fields that can be changed in bulk
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BatchField {
+    #[default]
+    SshHost,
+    SshUser,
+    SshPort,
+}
+
+impl BatchField {
+    pub const ALL: [BatchField; 3] = [BatchField::SshHost, BatchField::SshUser, BatchField::SshPort];
+
+    /// Current value of this field in a tunnel
+    pub fn get<'a>(&self, tunnel: &'a Tunnel) -> &'a str {
+        match self {
+            BatchField::SshHost => &tunnel.ssh_host,
+            BatchField::SshUser => &tunnel.ssh_user,
+            BatchField::SshPort => &tunnel.ssh_port,
+        }
+    }
+
+    pub fn set(&self, tunnel: &mut Tunnel, value: String) {
+        match self {
+            BatchField::SshHost => tunnel.ssh_host = value,
+            BatchField::SshUser => tunnel.ssh_user = value,
+            BatchField::SshPort => tunnel.ssh_port = value,
+        }
+    }
+}
+
+impl std::fmt::Display for BatchField {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            BatchField::SshHost => "SSH Host",
+            BatchField::SshUser => "SSH User",
+            BatchField::SshPort => "SSH Port",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    FieldSelected(BatchField),
+    FindChanged(String),
+    ReplaceChanged(String),
+    Apply,
+    Cancel,
+}
+
+/// Find/replace entered in a batch edit window
+#[derive(Debug, Clone, Default)]
+pub struct BatchEditState {
+    pub field: BatchField,
+    pub find: String,
+    pub replace: String,
+    pub error_message: Option<String>,
+}
+
+impl BatchEditState {
+    /// Apply an input message; Apply and Cancel are handled by the app
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::FieldSelected(field) => self.field = field,
+            Message::FindChanged(find) => self.find = find,
+            Message::ReplaceChanged(replace) => self.replace = replace,
+            Message::Apply | Message::Cancel => {}
+        }
+        self.error_message = None;
+    }
+
+    /// Check whether a tunnel's field holds the value being replaced
+    pub fn matches(&self, tunnel: &Tunnel) -> bool {
+        !self.find.trim().is_empty() && self.field.get(tunnel) == self.find.trim()
+    }
+
+    /// Check the replacement value, returning it trimmed
+    pub fn validated_replacement(&self) -> Result<String, String> {
+        let replace = self.replace.trim();
+        if replace.is_empty() {
+            return Err("Enter the new value".to_string());
+        }
+        if self.field == BatchField::SshPort && replace.parse::<u16>().is_err() {
+            return Err("SSH port must be a number between 1 and 65535".to_string());
+        }
+        Ok(replace.to_string())
+    }
+}
+
+/// `matching` lists the tunnels the replacement would change, with whether they are running
+pub fn view<'a>(state: &'a BatchEditState, matching: Vec<(&'a Tunnel, bool)>) -> Element<'a, Message> {
+    let match_count = matching.len();
+    let preview: Column<'a, Message> = matching
+        .into_iter()
+        .fold(Column::new().spacing(4), |rows, (tunnel, running)| {
+            let suffix = if running { " (running, will be restarted)" } else { "" };
+            rows.push(text(format!("{}{}", tunnel.name, suffix)).size(12))
+        });
+
+    let mut apply = button("Apply").style(button::primary);
+    if match_count > 0 && !state.replace.trim().is_empty() {
+        apply = apply.on_press(Message::Apply);
+    }
+
+    let mut content = column![
+        text("Batch Edit Tunnels").size(20),
+        row![
+            text("Field:").size(12),
+            pick_list(BatchField::ALL, Some(state.field), Message::FieldSelected).text_size(12),
+        ]
+        .spacing(8)
+        .align_y(Center),
+        row![
+            text_input("Current value", &state.find)
+                .on_input(Message::FindChanged)
+                .padding(8)
+                .width(Length::Fill),
+            text("\u{2192}"),
+            text_input("New value", &state.replace)
+                .on_input(Message::ReplaceChanged)
+                .padding(8)
+                .width(Length::Fill),
+        ]
+        .spacing(8)
+        .align_y(Center),
+        text(format!("{} tunnel(s) with this {}:", match_count, state.field)).size(12),
+        scrollable(preview).height(Length::Fill).width(Length::Fill),
+    ]
+    .spacing(10)
+    .padding(20);
+
+    if let Some(error) = &state.error_message {
+        content = content.push(text(error).color(iced::Color::from_rgb(0.8, 0.0, 0.0)));
+    }
+
+    content
+        .push(
+            row![
+                button("Cancel").on_press(Message::Cancel).style(button::secondary),
+                apply,
+            ]
+            .spacing(10),
+        )
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::{test_tunnel, TunnelManager};
+
+    fn state(field: BatchField, find: &str, replace: &str) -> BatchEditState {
+        BatchEditState { field, find: find.to_string(), replace: replace.to_string(), error_message: None }
+    }
+
+    #[test]
+    fn bulk_update_changes_only_matching_tunnels() {
+        let mut manager = TunnelManager::new();
+        let bastion = |name: &str, port: &str| Tunnel { ssh_host: "old-bastion".to_string(), ..test_tunnel(name, port) };
+        manager.set_tunnels(vec![bastion("web", "18150"), test_tunnel("other", "18151"), bastion("db", "18152")]);
+        let edit = state(BatchField::SshHost, " old-bastion ", "new-bastion");
+        let replacement = edit.validated_replacement().unwrap();
+
+        let updated = manager.bulk_update(|t| edit.matches(t), |t| edit.field.set(t, replacement.clone()));
+
+        assert_eq!(updated, vec!["web".to_string(), "db".to_string()]);
+        let hosts: Vec<&str> = manager.get_tunnels().iter().map(|t| t.ssh_host.as_str()).collect();
+        assert_eq!(hosts, vec!["new-bastion", "example.com", "new-bastion"]);
+    }
+
+    #[test]
+    fn empty_find_matches_nothing() {
+        let edit = state(BatchField::SshUser, "  ", "admin");
+
+        assert!(!edit.matches(&test_tunnel("web", "18153")));
+    }
+
+    #[test]
+    fn replacement_is_validated_for_the_field() {
+        assert_eq!(state(BatchField::SshUser, "user", " admin ").validated_replacement(), Ok("admin".to_string()));
+        assert!(state(BatchField::SshUser, "user", " ").validated_replacement().is_err());
+        assert!(state(BatchField::SshPort, "22", "2222").validated_replacement().is_ok());
+        assert!(state(BatchField::SshPort, "22", "ssh").validated_replacement().is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use iced::widget::{button, column, container, row, text, Column};
+use iced::{Center, Element, Length};
+
+/// What to do once the user confirms
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmAction {
+    RemoveTunnel(String),
+    Quit,
+    /// Close a tunnel form window, dropping its unsaved edits
+    DiscardForm(iced::window::Id),
+    /// Restart running tunnels so they pick up an edit
+    RestartTunnels(Vec<String>),
+    /// Mark a tunnel as served by the external forward on its local port
+    AdoptExternal(String),
+    /// Reconnect a tunnel accepting the server's unknown host key, saving
+    /// `accept_new_host_keys` on it when `remember`
+    TrustHost { tunnel_name: String, remember: bool },
+}
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Confirm,
+    /// The window's third answer, when it has one
+    Alternative,
+    Cancel,
+}
+
+/// `alternative` labels a third button between No and Yes
+pub fn view<'a>(message: &'a str, alternative: Option<&'a str>) -> Element<'a, Message> {
+    let mut buttons = row![button("No").on_press(Message::Cancel).style(button::secondary)].spacing(10);
+    if let Some(label) = alternative {
+        buttons = buttons.push(button(label).on_press(Message::Alternative).style(button::secondary));
+    }
+    let content: Column<'a, Message> = column![
+        text(message).size(14),
+        buttons.push(button("Yes").on_press(Message::Confirm).style(button::primary)),
+    ]
+    .spacing(12)
+    .padding(16)
+    .align_x(Center);
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+}
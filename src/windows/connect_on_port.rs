@@ -0,0 +1,41 @@
+use iced::widget::{button, column, container, row, text, text_input, Column};
+use iced::{Element, Length};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    PortChanged(String),
+    Connect,
+    Cancel,
+}
+
+/// Prompt for the local port of a single connection
+/// The tunnel keeps its configured port; the next normal connect uses it again.
+pub fn view<'a>(
+    tunnel_name: &'a str,
+    configured_port: &'a str,
+    local_port: &'a str,
+    error_message: &'a Option<String>,
+) -> Element<'a, Message> {
+    let content: Column<'a, Message> = column![
+        text(format!("Connect '{}' on local port (configured: {})", tunnel_name, configured_port)).size(14),
+        text_input("Local port", local_port)
+            .on_input(Message::PortChanged)
+            .on_submit(Message::Connect)
+            .padding(8),
+    ]
+    .push_maybe(error_message.as_ref().map(|error| text(error.clone()).size(12)))
+    .push(
+        row![
+            button("Cancel").on_press(Message::Cancel).style(button::secondary),
+            button("Connect").on_press(Message::Connect).style(button::primary),
+        ]
+        .spacing(10),
+    )
+    .spacing(10)
+    .padding(16);
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
@@ -1,99 +1,333 @@
-use crate::tunnels::Tunnel;
-use super::FormMode;
-use iced::widget::{button, column, container, row, text, text_input, Column};
+use crate::systemtray::get_tunnel_display_name;
+use crate::tunnels::{AuthMode, Tunnel, TunnelStatus, TEST_TIMEOUT};
+use std::time::{Duration, Instant};
+use super::{FormMode, FormTab, JumpChoice, TemplateState, TunnelFormData};
+use iced::widget::{button, checkbox, column, container, pick_list, row, text, text_input, Column};
 use iced::{Element, Length};
 
 #[derive(Debug, Clone)]
 pub enum Message {
     NameChanged(String),
+    DisplayNameChanged(String),
     LocalHostChanged(String),
+    BindInterfaceChanged(String),
     LocalPortChanged(String),
+    AssignFreePort,
     RemoteHostChanged(String),
     RemotePortChanged(String),
+    RemoteIsSocketToggled(bool),
+    RemoteSocketChanged(String),
     SshUserChanged(String),
     SshHostChanged(String),
     SshPortChanged(String),
     PrivateKeyChanged(String),
+    SshConfigFileChanged(String),
+    IdentitiesOnlyToggled(bool),
+    AcceptNewHostKeysToggled(bool),
+    AuthModeSelected(AuthMode),
+    AutoStartToggled(bool),
+    OpenWebOnConnectToggled(bool),
+    CleanExitDisconnectsToggled(bool),
+    NotificationsToggled(bool),
+    FavoriteToggled(bool),
+    EnvKeyChanged(usize, String),
+    EnvValueChanged(usize, String),
+    AddEnv,
+    RemoveEnv(usize),
+    RequiredInterfaceChanged(String),
+    GateHostChanged(String),
+    AutoDisconnectChanged(String),
+    SshBinaryChanged(String),
+    InventoryFileChanged(String),
+    JumpTunnelSelected(Option<String>),
+    TabSelected(FormTab),
+    TemplateSelected(String),
+    TemplateNameChanged(String),
+    SaveAsTemplate,
+    DeleteTemplate(String),
     BrowsePrivateKey,
+    BrowseSshConfigFile,
+    BrowseSshBinary,
     Test,
+    CancelTest,
     Create,
+    Apply,
     Cancel,
 }
 
 pub fn view<'a>(
     mode: &FormMode,
-    name: &str,
-    local_host: &str,
-    local_port: &str,
-    remote_host: &str,
-    remote_port: &str,
-    ssh_user: &str,
-    ssh_host: &str,
-    ssh_port: &str,
-    private_key: &str,
+    form: &'a TunnelFormData,
+    form_tab: FormTab,
+    default_identity: &'a Option<String>,
+    templates: &'a TemplateState,
+    jump_choices: &'a [JumpChoice],
     error_message: &'a Option<String>,
     test_message: &'a Option<String>,
+    warnings: &'a [String],
+    saved_notice: bool,
+    status: Option<TunnelStatus>,
+    test_started: Option<Instant>,
+    restart_fields: &'a [&'static str],
 ) -> Element<'a, Message> {
     let title = match mode {
         FormMode::Edit { .. } => "Edit Tunnel",
         FormMode::Create => "Drill New Tunnel",
+        FormMode::CreateFrom { .. } => "Edit as New Tunnel",
     };
     let mut content: Column<'a, Message> = column![
         text(title).size(20),
-        text("").size(8),
-        text("Tunnel Name:").size(14),
-        text_input("Enter tunnel name", name)
-            .on_input(Message::NameChanged)
-            .padding(8),
-        text("").size(4),
+    ]
+    .spacing(5)
+    .padding(20);
+
+    // Changed fields only matter while the tunnel runs with the old values
+    let is_active = matches!(
+        status,
+        Some(TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. })
+    );
+    let restart_fields: &[&str] = if is_active { restart_fields } else { &[] };
+
+    // Live status of the tunnel being edited
+    if let Some(status) = status {
+        content = content.push(status_banner(status, restart_fields));
+    }
+    content = content.push(text("").size(8));
+
+    // New tunnels can start from a saved template
+    if matches!(mode, FormMode::Create) && !templates.names.is_empty() {
+        content = content.push(template_picker(templates));
+        content = content.push(text("").size(4));
+    }
+
+    content = content.push(tab_bar(form_tab));
+    content = content.push(text("").size(4));
+
+    content = match form_tab {
+        FormTab::Forwarding => content.push(forwarding_tab(form, restart_fields)),
+        FormTab::Ssh => content.push(ssh_tab(form, default_identity, jump_choices, restart_fields)),
+        FormTab::Advanced => content.push(advanced_tab(form, templates, restart_fields)),
+    };
+
+    if let Some(error) = error_message {
+        content = content.push(text("").size(4));
+        content = content.push(
+            text(error)
+                .color(iced::Color::from_rgb(0.8, 0.0, 0.0))
+                .wrapping(iced::widget::text::Wrapping::Word)
+        );
+    }
+
+    if let Some(started) = test_started {
+        content = content.push(text("").size(4));
+        content = content.push(test_progress(started.elapsed()));
+    }
+
+    if let Some(test_msg) = test_message {
+        content = content.push(text("").size(4));
+        if test_msg.starts_with("Success") || test_msg.starts_with("✓") {
+            content = content.push(
+                text(test_msg)
+                    .color(iced::Color::from_rgb(0.0, 0.6, 0.0))
+                    .wrapping(iced::widget::text::Wrapping::Word)
+            );
+        } else {
+            content = content.push(
+                text(test_msg)
+                    .color(iced::Color::from_rgb(0.8, 0.5, 0.0))
+                    .wrapping(iced::widget::text::Wrapping::Word)
+            );
+        }
+    }
+
+    for warning in warnings {
+        content = content.push(text("").size(4));
+        content = content.push(
+            text(format!("⚠ {}", warning))
+                .color(iced::Color::from_rgb(0.8, 0.5, 0.0))
+                .wrapping(iced::widget::text::Wrapping::Word)
+        );
+    }
+
+    if saved_notice {
+        content = content.push(text("").size(4));
+        content = content.push(text("✓ Saved").color(iced::Color::from_rgb(0.0, 0.6, 0.0)));
+    }
+
+    content = content.push(text("").size(8));
+    let action_button_text = match mode {
+        FormMode::Edit { .. } => "Save",
+        FormMode::Create => "Create",
+        FormMode::CreateFrom { .. } => "Save as New",
+    };
+    // Disabled until the fields they can't do without are filled in
+    let can_save = form.can_save();
+    if !can_save {
+        content = content.push(text("Fill in the name, SSH user and SSH host to test or save").size(12));
+        content = content.push(text("").size(4));
+    }
+    content = content.push(
+        row![
+            button("Cancel").on_press(Message::Cancel).padding(8),
+            text(" "),
+            if test_started.is_some() {
+                button("Cancel Test").on_press(Message::CancelTest).padding(8)
+            } else {
+                button("Test").on_press_maybe(form.can_test().then_some(Message::Test)).padding(8)
+            },
+            text(" "),
+            button("Apply").on_press_maybe(can_save.then_some(Message::Apply)).padding(8),
+            text(" "),
+            button(action_button_text).on_press_maybe(can_save.then_some(Message::Create)).padding(8),
+        ]
+        .spacing(10),
+    );
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Shrink)
+        .padding(10)
+        .into()
+}
+
+/// Picker applying a saved template to the form
+fn template_picker<'a>(templates: &'a TemplateState) -> Element<'a, Message> {
+    let mut delete = button(text("Delete").size(12)).style(button::secondary).padding([6, 10]);
+    if let Some(selected) = &templates.selected {
+        delete = delete.on_press(Message::DeleteTemplate(selected.clone()));
+    }
+    row![
+        text("New from template:").size(12),
+        pick_list(templates.names.as_slice(), templates.selected.clone(), Message::TemplateSelected)
+            .placeholder("Choose a template")
+            .text_size(12)
+            .width(Length::Fill),
+        delete,
+    ]
+    .spacing(8)
+    .align_y(iced::Alignment::Center)
+    .into()
+}
+
+/// Row of buttons switching between the form sections
+fn tab_bar<'a>(form_tab: FormTab) -> Element<'a, Message> {
+    let tab_button = |label: &'a str, tab: FormTab| {
+        let style = if tab == form_tab { button::primary } else { button::secondary };
+        button(text(label).size(13))
+            .on_press(Message::TabSelected(tab))
+            .style(style)
+            .padding([6, 12])
+    };
+    row![
+        tab_button("Forwarding", FormTab::Forwarding),
+        tab_button("SSH", FormTab::Ssh),
+        tab_button("Advanced", FormTab::Advanced),
+    ]
+    .spacing(6)
+    .into()
+}
+
+/// Tunnel name and the local/remote endpoints
+fn forwarding_tab<'a>(form: &'a TunnelFormData, restart_fields: &[&str]) -> Element<'a, Message> {
+    let remote: Element<'a, Message> = if form.remote_is_socket {
+        column![
+            field_label("Remote Socket", "Remote Socket", restart_fields),
+            text_input("Socket path on the SSH server (e.g., /run/app.sock)", &form.remote_socket)
+                .on_input(Message::RemoteSocketChanged)
+                .padding(8),
+        ]
+        .spacing(2)
+        .into()
+    } else {
         row![
             column![
-                text("Local Host").size(12),
-                text_input("localhost", local_host)
-                    .on_input(Message::LocalHostChanged)
+                field_label("Remote Host", "Remote Host", restart_fields),
+                text_input("Remote host", &form.remote_host)
+                    .on_input(Message::RemoteHostChanged)
                     .padding(8),
             ]
             .spacing(2)
             .width(Length::Fill),
             text(" ").width(Length::Fixed(10.0)),
             column![
-                text("Local Port").size(12),
-                text_input("Port (e.g., 8080)", local_port)
-                    .on_input(Message::LocalPortChanged)
+                field_label("Remote Port", "Remote Port", restart_fields),
+                text_input("Remote port or range", &form.remote_port)
+                    .on_input(Message::RemotePortChanged)
                     .padding(8),
             ]
             .spacing(2)
             .width(Length::Fill),
-        ],
+        ]
+        .into()
+    };
+
+    column![
+        text("Tunnel Name:").size(14),
+        text_input("Enter tunnel name", &form.name)
+            .on_input(Message::NameChanged)
+            .padding(8),
+        text("Display Name:").size(14),
+        text_input("Shown in the tray and dashboard (optional)", &form.display_name)
+            .on_input(Message::DisplayNameChanged)
+            .padding(8),
         text("").size(4),
         row![
             column![
-                text("Remote Host").size(12),
-                text_input("Remote host", remote_host)
-                    .on_input(Message::RemoteHostChanged)
+                field_label("Local Host", "Local Host", restart_fields),
+                text_input("localhost", &form.local_host)
+                    .on_input(Message::LocalHostChanged)
                     .padding(8),
             ]
             .spacing(2)
             .width(Length::Fill),
             text(" ").width(Length::Fixed(10.0)),
             column![
-                text("Remote Port").size(12),
-                text_input("Remote port", remote_port)
-                    .on_input(Message::RemotePortChanged)
-                    .padding(8),
+                field_label("Local Port", "Local Port", restart_fields),
+                row![
+                    text_input("Port or range (e.g., 8080, 9000-9005)", &form.local_port)
+                        .on_input(Message::LocalPortChanged)
+                        .padding(8)
+                        .width(Length::Fill),
+                    text(" ").width(Length::Fixed(6.0)),
+                    button("Assign free port")
+                        .on_press(Message::AssignFreePort)
+                        .padding(8),
+                ],
             ]
             .spacing(2)
             .width(Length::Fill),
         ],
+        field_label("Bind to Interface (optional, its current IP replaces the local host)", "Bind Interface", restart_fields),
+        text_input("Interface name (e.g., en0, tun0)", &form.bind_interface)
+            .on_input(Message::BindInterfaceChanged)
+            .padding(8),
         text("").size(4),
+        checkbox("Remote is a Unix socket", form.remote_is_socket)
+            .on_toggle(Message::RemoteIsSocketToggled)
+            .size(16)
+            .text_size(12),
+        remote,
+    ]
+    .spacing(5)
+    .into()
+}
+
+/// SSH server, user and authentication
+fn ssh_tab<'a>(
+    form: &'a TunnelFormData,
+    default_identity: &'a Option<String>,
+    jump_choices: &'a [JumpChoice],
+    restart_fields: &[&str],
+) -> Element<'a, Message> {
+    let mut content: Column<'a, Message> = column![
         text("SSH Connection:").size(14),
-        text_input("SSH user", ssh_user)
+        text_input("SSH user", &form.ssh_user)
             .on_input(Message::SshUserChanged)
             .padding(8),
         row![
             column![
-                text("SSH Host").size(12),
-                text_input("SSH host", ssh_host)
+                field_label("SSH Host", "SSH Host", restart_fields),
+                text_input("SSH host", &form.ssh_host)
                     .on_input(Message::SshHostChanged)
                     .padding(8),
             ]
@@ -101,8 +335,8 @@ pub fn view<'a>(
             .width(Length::Fill),
             text(" ").width(Length::Fixed(10.0)),
             column![
-                text("SSH Port").size(12),
-                text_input("Port (e.g., 22)", ssh_port)
+                field_label("SSH Port", "SSH Port", restart_fields),
+                text_input("Port (e.g., 22)", &form.ssh_port)
                     .on_input(Message::SshPortChanged)
                     .padding(8),
             ]
@@ -110,116 +344,473 @@ pub fn view<'a>(
             .width(Length::Fill),
         ],
         text("").size(4),
-        text("Private Key (optional)").size(12),
         row![
-            text_input("Path to private key file", private_key)
-                .on_input(Message::PrivateKeyChanged)
+            field_label("Authentication", "Authentication", restart_fields).width(Length::Fill),
+            pick_list(AuthMode::ALL, Some(form.auth_mode), Message::AuthModeSelected).text_size(12),
+        ]
+        .align_y(iced::Alignment::Center),
+    ]
+    .spacing(5);
+
+    match form.auth_mode {
+        AuthMode::Key => {}
+        AuthMode::Agent => content = content.push(auth_hint("No key is passed, ssh offers every key held by ssh-agent")),
+        AuthMode::Password => content = content.push(auth_hint("Public keys are not tried; ssh asks for the password through SSH_ASKPASS")),
+    }
+    if form.auth_mode == AuthMode::Key {
+        content = content.push(field_label("Private Key (optional)", "Private Key", restart_fields));
+        content = content.push(
+            row![
+                text_input("Path to private key file", &form.private_key)
+                    .on_input(Message::PrivateKeyChanged)
+                    .padding(8)
+                    .width(Length::Fill),
+                text(" ").width(Length::Fixed(8.0)),
+                button("Browse")
+                    .on_press(Message::BrowsePrivateKey)
+                    .padding(8),
+            ]
+            .align_y(iced::Alignment::Center),
+        );
+    }
+
+    // Show which key ssh falls back to when none is set
+    if form.auth_mode == AuthMode::Key && form.private_key.trim().is_empty() {
+        let hint = match default_identity {
+            Some(path) => format!("No key set: ssh will use the default identity {}", path),
+            None => "No key set and no default identity found in ~/.ssh (ssh-agent may still be used)".to_string(),
+        };
+        content = content.push(
+            text(hint)
+                .size(11)
+                .color(iced::Color::from_rgb(0.5, 0.5, 0.5))
+                .wrapping(iced::widget::text::Wrapping::Word)
+        );
+    }
+    if form.auth_mode == AuthMode::Key {
+        content = content.push(
+            checkbox("Use only this key (IdentitiesOnly=yes)", form.identities_only)
+                .on_toggle(Message::IdentitiesOnlyToggled)
+                .size(16)
+                .text_size(12)
+        );
+    }
+    content = content.push(
+        checkbox("Trust the host key on first connect (StrictHostKeyChecking=accept-new)", form.accept_new_host_keys)
+            .on_toggle(Message::AcceptNewHostKeysToggled)
+            .size(16)
+            .text_size(12)
+    );
+
+    content = content.push(text("").size(4));
+    content = content.push(field_label("SSH Config File (optional, passed as -F)", "SSH Config File", restart_fields));
+    content = content.push(
+        row![
+            text_input("Path to ssh config file", &form.ssh_config_file)
+                .on_input(Message::SshConfigFileChanged)
                 .padding(8)
                 .width(Length::Fill),
             text(" ").width(Length::Fixed(8.0)),
             button("Browse")
-                .on_press(Message::BrowsePrivateKey)
+                .on_press(Message::BrowseSshConfigFile)
                 .padding(8),
         ]
         .align_y(iced::Alignment::Center),
-    ]
-    .spacing(5)
-    .padding(20);
+    );
 
-    if let Some(error) = error_message {
+    // Only offered when there is another tunnel to jump through
+    if jump_choices.len() > 1 {
+        let selected = jump_choices
+            .iter()
+            .find(|choice| choice.tunnel_id == form.jump_tunnel_id)
+            .cloned();
         content = content.push(text("").size(4));
+        content = content.push(field_label("Jump through (ssh -J)", "Jump through", restart_fields));
         content = content.push(
-            text(error)
-                .color(iced::Color::from_rgb(0.8, 0.0, 0.0))
-                .wrapping(iced::widget::text::Wrapping::Word)
+            pick_list(jump_choices, selected, |choice: JumpChoice| Message::JumpTunnelSelected(choice.tunnel_id))
+                .placeholder("Referenced tunnel no longer exists")
+                .text_size(12)
+                .width(Length::Fill),
         );
-    }
-
-    if let Some(test_msg) = test_message {
-        content = content.push(text("").size(4));
-        if test_msg.starts_with("Success") || test_msg.starts_with("✓") {
-            content = content.push(
-                text(test_msg)
-                    .color(iced::Color::from_rgb(0.0, 0.6, 0.0))
-                    .wrapping(iced::widget::text::Wrapping::Word)
-            );
-        } else {
+        if form.jump_tunnel_id.is_some() {
             content = content.push(
-                text(test_msg)
-                    .color(iced::Color::from_rgb(0.8, 0.5, 0.0))
+                text("The jump host must accept your ssh-agent or ~/.ssh/config keys")
+                    .size(11)
+                    .color(iced::Color::from_rgb(0.5, 0.5, 0.5))
                     .wrapping(iced::widget::text::Wrapping::Word)
             );
         }
     }
 
-    content = content.push(text("").size(8));
-    let is_edit_mode = matches!(mode, FormMode::Edit { .. });
-    let action_button_text = if is_edit_mode { "Save" } else { "Create" };
-    content = content.push(
+    content.into()
+}
+
+/// Spinner and countdown shown while a connection test runs
+fn test_progress<'a>(elapsed: Duration) -> Element<'a, Message> {
+    const SPINNER: [&str; 4] = ["\u{25d0}", "\u{25d3}", "\u{25d1}", "\u{25d2}"];
+    let frame = SPINNER[(elapsed.as_millis() / 250) as usize % SPINNER.len()];
+    let left = TEST_TIMEOUT.saturating_sub(elapsed).as_secs_f32().ceil() as u64;
+    text(format!("{} Testing\u{2026} (up to {}s)", frame, left))
+        .color(iced::Color::from_rgb(0.5, 0.5, 0.5))
+        .into()
+}
+
+/// Grey explanation under the authentication picker
+fn auth_hint<'a>(hint: &'a str) -> Element<'a, Message> {
+    text(hint)
+        .size(11)
+        .color(iced::Color::from_rgb(0.5, 0.5, 0.5))
+        .wrapping(iced::widget::text::Wrapping::Word)
+        .into()
+}
+
+/// Field label, highlighted when the change needs a restart of the running tunnel
+fn field_label<'a>(label: &'a str, field: &str, restart_fields: &[&str]) -> iced::widget::Text<'a> {
+    let label = text(label).size(12);
+    if restart_fields.contains(&field) {
+        label.color(iced::Color::from_rgb(0.8, 0.5, 0.0))
+    } else {
+        label
+    }
+}
+
+/// Current status of the edited tunnel, with the changes saving would need a restart for
+fn status_banner<'a>(status: TunnelStatus, restart_fields: &[&str]) -> Element<'a, Message> {
+    let (label, color) = match &status {
+        TunnelStatus::Disconnected => ("Disconnected".to_string(), iced::Color::from_rgb(0.5, 0.5, 0.5)),
+        TunnelStatus::Connecting => ("Connecting...".to_string(), iced::Color::from_rgb(0.8, 0.5, 0.0)),
+        TunnelStatus::Connected { .. } => ("Connected".to_string(), iced::Color::from_rgb(0.0, 0.6, 0.0)),
+        TunnelStatus::Error { error, .. } => (format!("Error: {}", error), iced::Color::from_rgb(0.8, 0.0, 0.0)),
+        TunnelStatus::Reconnecting { attempt } => {
+            (format!("Reconnecting (attempt {})...", attempt), iced::Color::from_rgb(0.8, 0.5, 0.0))
+        }
+        TunnelStatus::External => ("Connected by an ssh outside Drill".to_string(), iced::Color::from_rgb(0.0, 0.6, 0.0)),
+    };
+    let is_active = matches!(
+        status,
+        TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. }
+    );
+    let mut banner = column![
+        text(get_tunnel_display_name(&label, status))
+            .size(12)
+            .color(color)
+            .wrapping(iced::widget::text::Wrapping::Word),
+    ]
+    .spacing(2);
+    if !restart_fields.is_empty() {
+        banner = banner.push(
+            text(format!("These changes require a restart to take effect: {}", restart_fields.join(", ")))
+                .size(11)
+                .color(iced::Color::from_rgb(0.8, 0.5, 0.0))
+                .wrapping(iced::widget::text::Wrapping::Word)
+        );
+    } else if is_active {
+        banner = banner.push(
+            text("Saving connection changes will need a restart of the tunnel")
+                .size(11)
+                .color(iced::Color::from_rgb(0.5, 0.5, 0.5))
+        );
+    }
+    container(banner).padding(6).style(container::rounded_box).width(Length::Fill).into()
+}
+
+/// Less common options
+fn advanced_tab<'a>(form: &'a TunnelFormData, templates: &'a TemplateState, restart_fields: &[&str]) -> Element<'a, Message> {
+    let mut save_template = button(text("Save as Template").size(12)).padding([6, 10]);
+    if !templates.new_name.trim().is_empty() {
+        save_template = save_template.on_press(Message::SaveAsTemplate);
+    }
+    column![
+        checkbox("Start automatically in daemon mode", form.auto_start)
+            .on_toggle(Message::AutoStartToggled)
+            .size(16)
+            .text_size(12),
+        checkbox("Open in browser after connecting", form.open_web_on_connect)
+            .on_toggle(Message::OpenWebOnConnectToggled)
+            .size(16)
+            .text_size(12),
+        checkbox("Treat a clean exit as disconnected, not an error", form.clean_exit_disconnects)
+            .on_toggle(Message::CleanExitDisconnectsToggled)
+            .size(16)
+            .text_size(12),
+        checkbox("Show notifications for this tunnel", form.notifications)
+            .on_toggle(Message::NotificationsToggled)
+            .size(16)
+            .text_size(12),
+        checkbox("Favorite (connect toggle at the top of the tray)", form.favorite)
+            .on_toggle(Message::FavoriteToggled)
+            .size(16)
+            .text_size(12),
+        text("").size(8),
+        text("Disconnect after (minutes, optional)").size(12),
+        text_input("No time limit", &form.auto_disconnect_minutes)
+            .on_input(Message::AutoDisconnectChanged)
+            .padding(8),
+        text("").size(8),
+        text("Only connect when (optional)").size(12),
         row![
-            button("Cancel").on_press(Message::Cancel).padding(8),
-            text(" "),
-            button("Test").on_press(Message::Test).padding(8),
-            text(" "),
-            button(action_button_text).on_press(Message::Create).padding(8),
+            text_input("Interface is up (e.g., tun0)", &form.required_interface)
+                .on_input(Message::RequiredInterfaceChanged)
+                .padding(8)
+                .width(Length::Fill),
+            text_input("Gate host answers ping", &form.gate_host)
+                .on_input(Message::GateHostChanged)
+                .padding(8)
+                .width(Length::Fill),
         ]
-        .spacing(10),
-    );
+        .spacing(8),
+        text("").size(8),
+        env_rows(form, restart_fields),
+        text("").size(8),
+        field_label("SSH Client (optional, instead of the system ssh)", "SSH Client", restart_fields),
+        row![
+            text_input("Path to ssh executable", &form.ssh_binary)
+                .on_input(Message::SshBinaryChanged)
+                .padding(8)
+                .width(Length::Fill),
+            text(" ").width(Length::Fixed(8.0)),
+            button("Browse")
+                .on_press(Message::BrowseSshBinary)
+                .padding(8),
+        ]
+        .align_y(iced::Alignment::Center),
+        text("").size(8),
+        text("Inventory File (optional, makes this a template: one tunnel per host, {host} in the name and SSH host)")
+            .size(12)
+            .wrapping(iced::widget::text::Wrapping::Word),
+        text_input("File with one host per line", &form.inventory_file)
+            .on_input(Message::InventoryFileChanged)
+            .padding(8),
+        text("").size(8),
+        text("Save these settings (without name and ports) as a template").size(12),
+        row![
+            text_input("Template name", &templates.new_name)
+                .on_input(Message::TemplateNameChanged)
+                .padding(8)
+                .width(Length::Fill),
+            save_template,
+        ]
+        .spacing(8)
+        .align_y(iced::Alignment::Center),
+    ]
+    .spacing(5)
+    .into()
+}
 
-    container(content)
-        .width(Length::Fill)
-        .height(Length::Shrink)
-        .padding(10)
-        .into()
+/// Environment variable rows, each with its remove button, and an add button
+fn env_rows<'a>(form: &'a TunnelFormData, restart_fields: &[&str]) -> Element<'a, Message> {
+    let mut rows: Column<'a, Message> = column![
+        field_label("Environment (SetEnv, the server must AcceptEnv them)", "Environment", restart_fields),
+    ]
+    .spacing(5);
+    for (index, (key, value)) in form.env.iter().enumerate() {
+        rows = rows.push(
+            row![
+                text_input("NAME", key)
+                    .on_input(move |key| Message::EnvKeyChanged(index, key))
+                    .padding(8)
+                    .width(Length::FillPortion(2)),
+                text_input("value", value)
+                    .on_input(move |value| Message::EnvValueChanged(index, value))
+                    .padding(8)
+                    .width(Length::FillPortion(3)),
+                button("Remove")
+                    .on_press(Message::RemoveEnv(index))
+                    .padding(8)
+                    .style(button::secondary),
+            ]
+            .spacing(8)
+            .align_y(iced::Alignment::Center),
+        );
+    }
+    rows.push(
+        button(text("Add Variable").size(12))
+            .on_press(Message::AddEnv)
+            .padding([6, 10])
+            .style(button::secondary),
+    )
+    .into()
 }
 
-pub fn validate_and_create_tunnel(
-    name: &str,
-    local_host: &str,
-    local_port: &str,
-    remote_host: &str,
-    remote_port: &str,
-    ssh_user: &str,
-    ssh_host: &str,
-    ssh_port: &str,
-    private_key: &str,
-) -> Result<Tunnel, String> {
-    if name.trim().is_empty() {
+/// Build the tunnel from the form, or return why it can't be saved
+/// Also returns non-fatal warnings about the endpoints; `others` are the
+/// existing tunnels (excluding the one being edited) to check the local port against.
+/// Text fields are stored without surrounding whitespace (pasted values often
+/// end in a space or newline); whitespace inside a value, e.g. a key path, is kept.
+pub fn validate_and_create_tunnel(form: &TunnelFormData, others: &[Tunnel]) -> Result<(Tunnel, Vec<String>), String> {
+    if form.name.trim().is_empty() {
         return Err("Name is required".to_string());
     }
 
-    if local_port.trim().is_empty() {
+    if form.local_port.trim().is_empty() {
         return Err("Local port is required".to_string());
     }
 
-    if remote_host.trim().is_empty() {
-        return Err("Remote host is required".to_string());
-    }
+    if form.remote_is_socket {
+        if form.remote_socket.trim().is_empty() {
+            return Err("Remote socket path is required".to_string());
+        }
+        if !form.remote_socket.trim().starts_with('/') {
+            return Err("Remote socket must be an absolute path on the SSH server".to_string());
+        }
+    } else {
+        if form.remote_host.trim().is_empty() {
+            return Err("Remote host is required".to_string());
+        }
 
-    if remote_port.trim().is_empty() {
-        return Err("Remote port is required".to_string());
+        if form.remote_port.trim().is_empty() {
+            return Err("Remote port is required".to_string());
+        }
     }
 
-    if ssh_user.trim().is_empty() {
+    if form.ssh_user.trim().is_empty() {
         return Err("SSH user is required".to_string());
     }
 
-    if ssh_host.trim().is_empty() {
+    if form.ssh_host.trim().is_empty() {
         return Err("SSH host is required".to_string());
     }
 
-    Ok(Tunnel {
+    if !form.ssh_config_file.trim().is_empty()
+        && !crate::tunnels::local_path(form.ssh_config_file.trim()).is_file()
+    {
+        return Err(format!("SSH config file not found: {}", form.ssh_config_file.trim()));
+    }
+
+    if !form.ssh_binary.trim().is_empty()
+        && !is_executable(&crate::tunnels::local_path(form.ssh_binary.trim()))
+    {
+        return Err(format!("SSH client is not an executable file: {}", form.ssh_binary.trim()));
+    }
+
+    let auto_disconnect_minutes = match form.auto_disconnect_minutes.trim() {
+        "" => None,
+        minutes => match minutes.parse::<u32>() {
+            Ok(minutes) if minutes > 0 => Some(minutes),
+            _ => return Err("Disconnect after must be a whole number of minutes".to_string()),
+        },
+    };
+
+    // A socket target leaves the remote host and port unused
+    let (remote_host, remote_port, remote_socket) = if form.remote_is_socket {
+        (String::new(), String::new(), form.remote_socket.trim().to_string())
+    } else {
+        (form.remote_host.trim().to_string(), form.remote_port.trim().to_string(), String::new())
+    };
+
+    // Rows left completely empty are ignored
+    let mut env = std::collections::BTreeMap::new();
+    for (key, value) in &form.env {
+        let key = key.trim();
+        if key.is_empty() && value.trim().is_empty() {
+            continue;
+        }
+        if !crate::tunnels::is_env_name(key) {
+            return Err(format!("Invalid environment variable name '{}': use letters, digits and _", key));
+        }
+        if env.insert(key.to_string(), value.trim().to_string()).is_some() {
+            return Err(format!("Environment variable {} is set twice", key));
+        }
+    }
+
+    // Only the key mode uses the key fields
+    let (private_key, identities_only) = match form.auth_mode {
+        AuthMode::Key => (form.private_key.trim().to_string(), form.identities_only),
+        AuthMode::Agent | AuthMode::Password => (String::new(), false),
+    };
+
+    let tunnel = Tunnel {
         id: uuid::Uuid::new_v4().to_string(),
-        name: name.to_string(),
-        local_host: local_host.to_string(),
-        local_port: local_port.to_string(),
-        remote_host: remote_host.to_string(),
-        remote_port: remote_port.to_string(),
-        ssh_user: ssh_user.to_string(),
-        ssh_host: ssh_host.to_string(),
-        ssh_port: ssh_port.to_string(),
-        private_key: private_key.to_string(),
-    })
+        name: form.name.trim().to_string(),
+        display_name: form.display_name.trim().to_string(),
+        local_host: form.local_host.trim().to_string(),
+        local_port: form.local_port.trim().to_string(),
+        remote_host,
+        remote_port,
+        ssh_user: form.ssh_user.trim().to_string(),
+        ssh_host: form.ssh_host.trim().to_string(),
+        ssh_port: form.ssh_port.trim().to_string(),
+        private_key,
+        auto_start: form.auto_start,
+        last_connected_at: None,
+        jump_tunnel_id: form.jump_tunnel_id.clone(),
+        ssh_config_file: form.ssh_config_file.trim().to_string(),
+        auto_disconnect_minutes,
+        identities_only,
+        remote_socket,
+        open_web_on_connect: form.open_web_on_connect,
+        clean_exit_disconnects: form.clean_exit_disconnects,
+        ssh_binary: form.ssh_binary.trim().to_string(),
+        auth_mode: form.auth_mode,
+        notifications: form.notifications,
+        required_interface: form.required_interface.trim().to_string(),
+        gate_host: form.gate_host.trim().to_string(),
+        favorite: form.favorite,
+        env,
+        bind_interface: form.bind_interface.trim().to_string(),
+        accept_new_host_keys: form.accept_new_host_keys,
+        inventory_file: form.inventory_file.trim().to_string(),
+        generated_from: None,
+    };
+    if tunnel.is_inventory_template() {
+        crate::inventory::read_inventory(&tunnel.inventory_file).map_err(|e| e.to_string())?;
+    }
+    // Port ranges must pair up before the tunnel can connect
+    if let Err(e) = crate::tunnels::forward_specs(&tunnel) {
+        return Err(match e {
+            crate::error::DrillError::Config(message) => message,
+            e => e.to_string(),
+        });
+    }
+    let warnings = endpoint_warnings(&tunnel, others);
+    Ok((tunnel, warnings))
+}
+
+/// Check that a path is a file the current user may run
+/// On Windows any existing file is accepted, execution rights aren't a mode bit there
+fn is_executable(path: &std::path::Path) -> bool {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        path.metadata()
+            .map(|metadata| metadata.is_file() && metadata.permissions().mode() & 0o111 != 0)
+            .unwrap_or(false)
+    }
+    #[cfg(not(unix))]
+    {
+        path.is_file()
+    }
+}
+
+/// Warn about endpoints that make the tunnel loop onto itself or clash with another tunnel
+fn endpoint_warnings(tunnel: &Tunnel, others: &[Tunnel]) -> Vec<String> {
+    let mut warnings = Vec::new();
+    let is_local = |host: &str| matches!(host.trim(), "localhost" | "127.0.0.1" | "::1");
+
+    if tunnel.remote_socket.is_empty()
+        && tunnel.ssh_host.trim() == tunnel.remote_host.trim()
+        && tunnel.ssh_port.trim() == tunnel.remote_port.trim()
+    {
+        warnings.push(format!(
+            "The remote endpoint is the SSH server itself ({}:{}); use localhost as remote host to reach a port on it",
+            tunnel.ssh_host.trim(),
+            tunnel.ssh_port.trim()
+        ));
+    }
+    if is_local(&tunnel.ssh_host) && tunnel.ssh_port.trim() == tunnel.local_port.trim() {
+        warnings.push(format!(
+            "Local port {} is also the SSH port on this machine, ssh would connect to its own forward",
+            tunnel.local_port.trim()
+        ));
+    }
+    if let Some(other) = others.iter().find(|t| t.local_port.trim() == tunnel.local_port.trim()) {
+        warnings.push(format!(
+            "Local port {} is already used by tunnel '{}', they can't run at the same time",
+            tunnel.local_port.trim(),
+            other.name
+        ));
+    }
+    warnings
 }
 
 /// Open file picker dialog to select a private key file
@@ -232,3 +823,165 @@ pub fn browse_for_private_key() -> Option<String> {
         .pick_file()
         .and_then(|path| path.to_str().map(|s| s.to_string()))
 }
+
+/// Open file picker dialog to select an ssh config file
+/// Starts in ~/.ssh, where config files usually live
+pub fn browse_for_ssh_config_file() -> Option<String> {
+    let mut dialog = rfd::FileDialog::new().set_title("Select SSH Config File");
+    if let Some(ssh_dir) = dirs::home_dir().map(|home| home.join(".ssh")) {
+        dialog = dialog.set_directory(ssh_dir);
+    }
+    dialog
+        .pick_file()
+        .and_then(|path| path.to_str().map(|s| s.to_string()))
+}
+
+/// Open file picker dialog to select an ssh client executable
+pub fn browse_for_ssh_binary() -> Option<String> {
+    rfd::FileDialog::new()
+        .set_title("Select SSH Client")
+        .pick_file()
+        .and_then(|path| path.to_str().map(|s| s.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::test_tunnel;
+
+    /// A complete form forwarding local port `local_port` to db.internal:5432
+    fn form(local_port: &str) -> TunnelFormData {
+        TunnelFormData {
+            name: "db".to_string(),
+            local_port: local_port.to_string(),
+            remote_host: "db.internal".to_string(),
+            remote_port: "5432".to_string(),
+            ssh_user: "deploy".to_string(),
+            ssh_host: "bastion.example.com".to_string(),
+            ..TunnelFormData::default()
+        }
+    }
+
+    #[test]
+    fn valid_form_has_no_warnings() {
+        let (tunnel, warnings) = validate_and_create_tunnel(&form("15432"), &[test_tunnel("web", "18080")]).unwrap();
+
+        assert_eq!(tunnel.local_port, "15432");
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn forwarding_to_the_ssh_server_itself_warns() {
+        let looping = TunnelFormData {
+            remote_host: "bastion.example.com".to_string(),
+            remote_port: "22".to_string(),
+            ..form("15432")
+        };
+
+        let (_, warnings) = validate_and_create_tunnel(&looping, &[]).unwrap();
+
+        assert!(warnings.iter().any(|warning| warning.contains("SSH server itself")));
+    }
+
+    #[test]
+    fn local_port_of_a_local_ssh_server_warns() {
+        let looping = TunnelFormData { ssh_host: "localhost".to_string(), ssh_port: "2222".to_string(), ..form("2222") };
+
+        let (_, warnings) = validate_and_create_tunnel(&looping, &[]).unwrap();
+
+        assert!(warnings.iter().any(|warning| warning.contains("connect to its own forward")));
+    }
+
+    #[test]
+    fn local_port_of_another_tunnel_warns() {
+        let (_, warnings) = validate_and_create_tunnel(&form("18080"), &[test_tunnel("web", "18080")]).unwrap();
+
+        assert!(warnings.iter().any(|warning| warning.contains("already used by tunnel 'web'")));
+    }
+
+    #[test]
+    fn socket_form_needs_an_absolute_path() {
+        let socket = |path: &str| TunnelFormData {
+            remote_is_socket: true,
+            remote_socket: path.to_string(),
+            remote_host: String::new(),
+            remote_port: String::new(),
+            ..form("15432")
+        };
+
+        let (tunnel, _) = validate_and_create_tunnel(&socket(" /run/postgresql/.s.PGSQL.5432 "), &[]).unwrap();
+        assert_eq!(tunnel.remote_socket, "/run/postgresql/.s.PGSQL.5432");
+        assert!(tunnel.remote_host.is_empty() && tunnel.remote_port.is_empty());
+
+        assert_eq!(validate_and_create_tunnel(&socket(""), &[]).unwrap_err(), "Remote socket path is required");
+        assert!(validate_and_create_tunnel(&socket("run/app.sock"), &[]).unwrap_err().contains("absolute path"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn ssh_binary_must_be_executable() {
+        use std::os::unix::fs::PermissionsExt;
+        let binary = std::env::temp_dir().join(format!("drill-create-tunnel-{}-ssh", std::process::id()));
+        std::fs::write(&binary, "#!/bin/sh\n").unwrap();
+        let with_binary = TunnelFormData { ssh_binary: binary.to_string_lossy().to_string(), ..form("15432") };
+
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(validate_and_create_tunnel(&with_binary, &[]).unwrap_err().contains("not an executable"));
+
+        std::fs::set_permissions(&binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+        let (tunnel, _) = validate_and_create_tunnel(&with_binary, &[]).unwrap();
+        assert_eq!(tunnel.ssh_binary, binary.to_string_lossy());
+        let _ = std::fs::remove_file(&binary);
+    }
+
+    #[test]
+    fn agent_and_password_modes_drop_the_key() {
+        for auth_mode in [AuthMode::Agent, AuthMode::Password] {
+            let with_key = TunnelFormData {
+                auth_mode,
+                private_key: "/keys/id_ed25519".to_string(),
+                identities_only: true,
+                ..form("15432")
+            };
+
+            let (tunnel, _) = validate_and_create_tunnel(&with_key, &[]).unwrap();
+
+            assert_eq!(tunnel.auth_mode, auth_mode);
+            assert!(tunnel.private_key.is_empty());
+            assert!(!tunnel.identities_only);
+        }
+    }
+
+    #[test]
+    fn stored_fields_have_no_surrounding_whitespace() {
+        let pasted = TunnelFormData {
+            name: " db \n".to_string(),
+            display_name: "\tProd DB ".to_string(),
+            local_host: " 127.0.0.1".to_string(),
+            local_port: "15432 ".to_string(),
+            remote_host: "db.internal\r\n".to_string(),
+            remote_port: " 5432\n".to_string(),
+            ssh_user: "deploy\n".to_string(),
+            ssh_host: "  bastion.example.com\r\n".to_string(),
+            ssh_port: " 22 ".to_string(),
+            private_key: "  /keys/prod key/id_ed25519\n".to_string(),
+            env: vec![(" APP_ENV ".to_string(), " production\n".to_string())],
+            ..form("15432")
+        };
+
+        let (tunnel, _) = validate_and_create_tunnel(&pasted, &[]).unwrap();
+
+        assert_eq!(tunnel.name, "db");
+        assert_eq!(tunnel.display_name, "Prod DB");
+        assert_eq!(tunnel.local_host, "127.0.0.1");
+        assert_eq!(tunnel.local_port, "15432");
+        assert_eq!(tunnel.remote_host, "db.internal");
+        assert_eq!(tunnel.remote_port, "5432");
+        assert_eq!(tunnel.ssh_user, "deploy");
+        assert_eq!(tunnel.ssh_host, "bastion.example.com");
+        assert_eq!(tunnel.ssh_port, "22");
+        // Only the ends are trimmed, spaces inside the path are kept
+        assert_eq!(tunnel.private_key, "/keys/prod key/id_ed25519");
+        assert_eq!(tunnel.env.get("APP_ENV").map(String::as_str), Some("production"));
+    }
+}
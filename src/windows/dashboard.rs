@@ -0,0 +1,190 @@
+use iced::widget::{button, checkbox, column, container, horizontal_rule, row, scrollable, text, Column};
+use iced::{Center, Element, Length};
+use crate::systemtray::{get_traffic_label, get_tunnel_display_name};
+use crate::tunnels::{Tunnel, TunnelStats, TunnelStatus};
+
+/// Actions available from the dashboard, mirroring the tray menu
+#[derive(Debug, Clone)]
+pub enum Message {
+    NewTunnel,
+    PasteUri,
+    ReconnectErrored,
+    Connect(String),
+    ConnectOnPort(String),
+    Disconnect(String),
+    OpenWeb(String),
+    Edit(String),
+    EditAsNew(String),
+    Remove(String),
+    ServiceFile(String),
+    EffectiveConfig(String),
+    CopySshConfig(String),
+    ExportBundle,
+    ImportBundle,
+    ImportPutty,
+    BatchEdit,
+    ViewLogs,
+    EventLog,
+    ErrorSummary,
+    StartAtLoginToggled(bool),
+    CheckForUpdates,
+    About,
+    Quit,
+}
+
+/// A tunnel as listed in the dashboard: its status, the local port override of
+/// its current connection and its I/O estimate (Linux only, see `TunnelStats`)
+pub type DashboardTunnel<'a> = (&'a Tunnel, TunnelStatus, Option<&'a str>, Option<TunnelStats>);
+
+/// Caveat shown under the tunnels when any of them has an I/O estimate
+const IO_ESTIMATE_NOTE: &str = "I/O is an estimate for the whole ssh process, config and key file reads included. \
+    It is only available on Linux, for the tunnels Drill starts itself (GUI and daemon mode).";
+
+/// With `locked` (managed mode) the actions that change tunnels are left out.
+pub fn view<'a>(tunnels: Vec<DashboardTunnel<'a>>, start_at_login: bool, locked: bool) -> Element<'a, Message> {
+    let has_tunnels = !tunnels.is_empty();
+    let has_stats = tunnels.iter().any(|(_, _, _, stats)| stats.is_some());
+    let mut reconnect_errored = button("Reconnect Errored").style(button::secondary);
+    if tunnels.iter().any(|(_, status, _, _)| matches!(status, TunnelStatus::Error { .. })) {
+        reconnect_errored = reconnect_errored.on_press(Message::ReconnectErrored);
+    }
+    let tunnel_rows: Column<'a, Message> = tunnels
+        .into_iter()
+        .fold(Column::new().spacing(8), |rows, (tunnel, status, local_port, stats)| rows.push(tunnel_row(tunnel, status, local_port, stats, locked)));
+
+    let mut export_bundle = button("Export Bundle...").style(button::secondary);
+    let mut batch_edit = button("Batch Edit...").style(button::secondary);
+    if has_tunnels {
+        export_bundle = export_bundle.on_press(Message::ExportBundle);
+    }
+    if has_tunnels && !locked {
+        batch_edit = batch_edit.on_press(Message::BatchEdit);
+    }
+
+    let mut import_row = row![export_bundle].spacing(10);
+    if !locked {
+        import_row = import_row.push(button("Import Bundle...").on_press(Message::ImportBundle).style(button::secondary));
+        // PuTTY keeps its sessions in the Windows registry
+        if cfg!(target_os = "windows") {
+            import_row = import_row.push(button("Import PuTTY...").on_press(Message::ImportPutty).style(button::secondary));
+        }
+    }
+
+    let mut header = row![text("Drill").size(24)].spacing(10).align_y(Center);
+    if locked {
+        header = header.push(text("Managed").size(12).color(iced::Color::from_rgb(0.5, 0.5, 0.5)));
+    }
+    header = header.push(text("").width(Length::Fill)).push(reconnect_errored);
+    if !locked {
+        header = header
+            .push(button("Paste URI").on_press(Message::PasteUri).style(button::secondary))
+            .push(button("New Tunnel").on_press(Message::NewTunnel).style(button::primary));
+    }
+
+    let content = column![
+        header,
+        horizontal_rule(1),
+        scrollable(tunnel_rows).height(Length::Fill).width(Length::Fill),
+        horizontal_rule(1),
+        row![
+            import_row,
+            batch_edit,
+            button("View Logs...").on_press(Message::ViewLogs).style(button::secondary),
+            button("Event Log...").on_press(Message::EventLog).style(button::secondary),
+            button("Error Summary...").on_press(Message::ErrorSummary).style(button::secondary),
+        ]
+        .spacing(10),
+        row![
+            checkbox("Start Drill at Login", start_at_login)
+                .on_toggle(Message::StartAtLoginToggled)
+                .width(Length::Fill),
+            button("Check for Updates...").on_press(Message::CheckForUpdates).style(button::secondary),
+            button("About Drill").on_press(Message::About).style(button::secondary),
+            button("Quit").on_press(Message::Quit).style(button::danger),
+        ]
+        .spacing(10)
+        .align_y(Center),
+    ]
+    .push_maybe(has_stats.then(|| text(IO_ESTIMATE_NOTE).size(11)))
+    .push(text("Ctrl/Cmd+N: new tunnel   Ctrl/Cmd+Q: quit").size(11))
+    .spacing(12)
+    .padding(20);
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+/// One tunnel with the same actions its tray submenu offers
+fn tunnel_row<'a>(tunnel: &'a Tunnel, status: TunnelStatus, local_port_override: Option<&'a str>, stats: Option<TunnelStats>, locked: bool) -> Element<'a, Message> {
+    let name = &tunnel.name;
+    let is_connected = matches!(status, TunnelStatus::Connecting | TunnelStatus::Connected { .. } | TunnelStatus::Reconnecting { .. });
+
+    let mut actions = row![].spacing(8);
+    if matches!(status, TunnelStatus::External) {
+        // Drill can't stop a process it didn't start, only stop watching it
+        actions = actions
+            .push(button("Stop Watching").on_press(Message::Disconnect(name.clone())).style(button::secondary))
+            .push(button("Open Web").on_press(Message::OpenWeb(name.clone())).style(button::secondary));
+    } else if is_connected {
+        actions = actions.push(button("Disconnect").on_press(Message::Disconnect(name.clone())).style(button::secondary));
+        if matches!(status, TunnelStatus::Connected { .. }) {
+            actions = actions.push(button("Open Web").on_press(Message::OpenWeb(name.clone())).style(button::secondary));
+        }
+    } else {
+        actions = actions
+            .push(button("Connect").on_press(Message::Connect(name.clone())).style(button::primary))
+            .push(button("On Port...").on_press(Message::ConnectOnPort(name.clone())).style(button::secondary));
+    }
+
+    // Edit and Remove are disabled while connected, as in the tray; managed tunnels can't be changed
+    if !locked {
+        let mut edit = button("Edit").style(button::secondary);
+        let mut remove = button("Remove").style(button::secondary);
+        if !is_connected {
+            edit = edit.on_press(Message::Edit(name.clone()));
+            remove = remove.on_press(Message::Remove(name.clone()));
+        }
+        actions = actions
+            .push(edit)
+            .push(button("Edit as New...").on_press(Message::EditAsNew(name.clone())).style(button::secondary))
+            .push(remove);
+    }
+    actions = actions
+        .push(button("Service File...").on_press(Message::ServiceFile(name.clone())).style(button::secondary))
+        .push(button("Config...").on_press(Message::EffectiveConfig(name.clone())).style(button::secondary))
+        .push(button("Copy SSH Config").on_press(Message::CopySshConfig(name.clone())).style(button::secondary));
+
+    let error = match &status {
+        TunnelStatus::Error { error, .. } => Some(text(error.clone()).size(11)),
+        TunnelStatus::Reconnecting { attempt } => Some(text(format!("Reconnecting (attempt {})...", attempt)).size(11)),
+        TunnelStatus::External => Some(text("Forwarded by an ssh process not started by Drill").size(11)),
+        _ => None,
+    };
+    // Only while connected, and only where the platform has per-process counters
+    let traffic = stats
+        .filter(|_| matches!(status, TunnelStatus::Connected { .. }))
+        .map(|stats| text(get_traffic_label(&stats)).size(11));
+
+    column![
+        row![
+            text(get_tunnel_display_name(tunnel.label(), status.clone())).size(16).width(Length::Fill),
+            actions,
+        ]
+        .align_y(Center),
+        text(format!(
+            "{}:{} \u{2192} {} via {}@{}",
+            tunnel.local_host,
+            local_port_override.unwrap_or(&tunnel.local_port),
+            tunnel.remote_target(),
+            tunnel.ssh_user,
+            tunnel.ssh_host
+        ))
+        .size(12),
+    ]
+    .push_maybe(traffic)
+    .push_maybe(error)
+    .spacing(4)
+    .into()
+}
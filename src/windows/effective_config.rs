@@ -0,0 +1,155 @@
+use iced::widget::{button, column, row, scrollable, text};
+use iced::{Element, Font, Length};
+use crate::config::Settings;
+use crate::error::DrillResult;
+use crate::tunnels::{build_ssh_args, AuthMode, monitor_interval, tunnel_ssh_program, Tunnel};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Copy,
+}
+
+pub fn view<'a>(tunnel_name: &'a str, config: &'a str) -> Element<'a, Message> {
+    column![
+        row![
+            text(format!("Effective configuration of '{}'", tunnel_name)).size(14).width(Length::Fill),
+            button("Copy").on_press(Message::Copy).style(button::secondary),
+        ]
+        .spacing(10),
+        scrollable(text(config).size(12).font(Font::MONOSPACE)).height(Length::Fill).width(Length::Fill),
+    ]
+    .spacing(10)
+    .padding(15)
+    .into()
+}
+
+/// Render what Drill will actually do for a tunnel, as read-only text
+///
+/// The command line comes from `build_ssh_args` (with the `-v` that
+/// `start_tunnel` adds), and the breakdown is read back from those arguments,
+/// so it can't drift from what is run. Drill's own behaviour (reconnects,
+/// timers, ...) follows.
+pub fn render(tunnel: &Tunnel, tunnels: &[Tunnel], settings: &Settings) -> DrillResult<String> {
+    let args = build_ssh_args(tunnel, tunnels, settings)?;
+    let arg_value = |flag: &str| {
+        args.iter()
+            .position(|arg| arg == flag)
+            .and_then(|index| args.get(index + 1))
+            .cloned()
+    };
+    let options: Vec<&String> = args
+        .iter()
+        .enumerate()
+        .filter(|(index, _)| *index > 0 && args[index - 1] == "-o")
+        .map(|(_, option)| option)
+        .collect();
+
+    let authentication = match (tunnel.auth_mode, arg_value("-i")) {
+        (AuthMode::Agent, _) => "ssh-agent keys only".to_string(),
+        (AuthMode::Password, _) => "password or keyboard-interactive, through SSH_ASKPASS".to_string(),
+        (AuthMode::Key, Some(key)) if tunnel.private_key.trim().is_empty() => format!("default identity {} (use_default_identity)", key),
+        (AuthMode::Key, Some(key)) => format!("private key {}", key),
+        (AuthMode::Key, None) => "ssh defaults (ssh-agent, ~/.ssh/config, default keys)".to_string(),
+    };
+
+    let mut command = vec![tunnel_ssh_program(tunnel).to_string_lossy().to_string(), "-v".to_string()];
+    command.extend(args.iter().cloned());
+    let command: Vec<String> = command
+        .into_iter()
+        .map(|arg| if arg.contains(char::is_whitespace) { format!("\"{}\"", arg) } else { arg })
+        .collect();
+
+    let yes_no = |value: bool| if value { "yes" } else { "no" };
+    let mut lines = vec![
+        "Command".to_string(),
+        format!("  {}", command.join(" ")),
+        String::new(),
+        "SSH".to_string(),
+        format!("  Server:             {}@{}:{}", tunnel.ssh_user, tunnel.ssh_host, tunnel.ssh_port),
+        format!("  Forward (-L):       {}", arg_value("-L").unwrap_or_default()),
+        format!("  Jump hosts (-J):    {}", arg_value("-J").unwrap_or_else(|| "none".to_string())),
+        format!("  Config file (-F):   {}", arg_value("-F").unwrap_or_else(|| "ssh default".to_string())),
+        format!("  Authentication:     {}", authentication),
+        "  Options (-o):".to_string(),
+    ];
+    lines.extend(options.iter().map(|option| format!("    {}", option)));
+    lines.extend([
+        String::new(),
+        "Drill".to_string(),
+        format!("  Auto reconnect:     {}", yes_no(settings.auto_reconnect)),
+        format!("  Monitor interval:   {}s", monitor_interval(settings).as_secs()),
+        format!(
+            "  Disconnect after:   {}",
+            tunnel
+                .auto_disconnect_minutes
+                .map(|minutes| format!("{} min", minutes))
+                .unwrap_or_else(|| "no limit".to_string())
+        ),
+        format!(
+            "  Clean exit:         {}",
+            if tunnel.clean_exit_disconnects { "Disconnected" } else { "Error" }
+        ),
+        format!("  Open browser:       {}", yes_no(tunnel.open_web_on_connect)),
+        format!("  Start in daemon:    {}", yes_no(tunnel.auto_start)),
+    ]);
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::test_tunnel;
+
+    #[test]
+    fn render_breaks_down_the_ssh_command() {
+        let web = Tunnel {
+            private_key: "/home/me/.ssh/id_ed25519".to_string(),
+            ssh_binary: "/opt/ssh/bin/ssh".to_string(),
+            auto_disconnect_minutes: Some(30),
+            ..test_tunnel("web", "18080")
+        };
+        let settings = Settings { auto_reconnect: true, monitor_interval_secs: Some(10), ..Settings::default() };
+
+        let rendered = render(&web, std::slice::from_ref(&web), &settings).unwrap();
+
+        assert_eq!(
+            rendered,
+            "\
+Command
+  /opt/ssh/bin/ssh -v -i /home/me/.ssh/id_ed25519 -L 127.0.0.1:18080:127.0.0.1:80 -N -o ServerAliveInterval=60 -o ServerAliveCountMax=3 -o ExitOnForwardFailure=yes -o ConnectTimeout=10 -p 22 user@example.com
+
+SSH
+  Server:             user@example.com:22
+  Forward (-L):       127.0.0.1:18080:127.0.0.1:80
+  Jump hosts (-J):    none
+  Config file (-F):   ssh default
+  Authentication:     private key /home/me/.ssh/id_ed25519
+  Options (-o):
+    ServerAliveInterval=60
+    ServerAliveCountMax=3
+    ExitOnForwardFailure=yes
+    ConnectTimeout=10
+
+Drill
+  Auto reconnect:     yes
+  Monitor interval:   10s
+  Disconnect after:   30 min
+  Clean exit:         Error
+  Open browser:       no
+  Start in daemon:    no"
+        );
+    }
+
+    #[test]
+    fn render_quotes_arguments_with_spaces() {
+        let web = Tunnel {
+            private_key: "/home/me/my keys/id_rsa".to_string(),
+            auth_mode: AuthMode::Key,
+            ..test_tunnel("web", "18080")
+        };
+
+        let rendered = render(&web, std::slice::from_ref(&web), &Settings::default()).unwrap();
+
+        assert!(rendered.contains(" -i \"/home/me/my keys/id_rsa\" "));
+    }
+}
@@ -0,0 +1,30 @@
+use iced::widget::{button, column, row, scrollable, text};
+use iced::{Element, Font, Length};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Reset,
+}
+
+/// Show the error summary, or how to turn it on when `summary` is None
+pub fn view<'a>(summary: Option<String>) -> Element<'a, Message> {
+    let mut reset = button("Reset").style(button::secondary);
+    let body = match summary {
+        Some(summary) => {
+            reset = reset.on_press(Message::Reset);
+            summary
+        }
+        None => "The error summary is off. Set error_stats=true in ~/.drill/config to keep one.".to_string(),
+    };
+    column![
+        row![
+            text("Tunnel errors by type and host").size(14).width(Length::Fill),
+            reset,
+        ]
+        .spacing(10),
+        scrollable(text(body).size(12).font(Font::MONOSPACE)).height(Length::Fill).width(Length::Fill),
+    ]
+    .spacing(10)
+    .padding(15)
+    .into()
+}
@@ -0,0 +1,134 @@
+use iced::widget::{button, column, pick_list, row, scrollable, text, text_input, Column};
+use iced::{Center, Element, Font, Length};
+use crate::events::{ConnectionEvent, EventKind, EventQuery};
+
+/// Number of events shown per page
+pub const PAGE_SIZE: usize = 50;
+
+const ALL_TUNNELS: &str = "All tunnels";
+const ALL_EVENTS: &str = "All events";
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    SearchChanged(String),
+    TunnelSelected(String),
+    KindSelected(String),
+    PreviousPage,
+    NextPage,
+    ExportCsv,
+}
+
+/// Filters and current page of an event log window
+#[derive(Debug, Clone, Default)]
+pub struct EventLogState {
+    pub query: EventQuery,
+    pub page: usize,
+}
+
+impl EventLogState {
+    /// Apply a filter or paging message; export is handled by the app
+    pub fn update(&mut self, message: Message, page_count: usize) {
+        match message {
+            Message::SearchChanged(search) => {
+                self.query.search = search;
+                self.page = 0;
+            }
+            Message::TunnelSelected(tunnel) => {
+                self.query.tunnel = (tunnel != ALL_TUNNELS).then_some(tunnel);
+                self.page = 0;
+            }
+            Message::KindSelected(kind) => {
+                self.query.kind = EventKind::ALL.into_iter().find(|k| k.as_str() == kind);
+                self.page = 0;
+            }
+            Message::PreviousPage => self.page = self.page.saturating_sub(1),
+            Message::NextPage => self.page = (self.page + 1).min(page_count.saturating_sub(1)),
+            Message::ExportCsv => {}
+        }
+    }
+}
+
+/// Number of pages needed for `event_count` events
+pub fn page_count(event_count: usize) -> usize {
+    event_count.div_ceil(PAGE_SIZE).max(1)
+}
+
+pub fn view<'a>(state: &'a EventLogState, events: Vec<&'a ConnectionEvent>, tunnel_names: Vec<String>) -> Element<'a, Message> {
+    let tunnel_options: Vec<String> = std::iter::once(ALL_TUNNELS.to_string()).chain(tunnel_names).collect();
+    let kind_options: Vec<String> = std::iter::once(ALL_EVENTS.to_string())
+        .chain(EventKind::ALL.iter().map(|kind| kind.as_str().to_string()))
+        .collect();
+    let selected_tunnel = state.query.tunnel.clone().unwrap_or_else(|| ALL_TUNNELS.to_string());
+    let selected_kind = state.query.kind.map(|kind| kind.as_str().to_string()).unwrap_or_else(|| ALL_EVENTS.to_string());
+
+    let filters = row![
+        text_input("Search tunnel or message...", &state.query.search)
+            .on_input(Message::SearchChanged)
+            .width(Length::Fill),
+        pick_list(tunnel_options, Some(selected_tunnel), Message::TunnelSelected),
+        pick_list(kind_options, Some(selected_kind), Message::KindSelected),
+    ]
+    .spacing(10);
+
+    let pages = page_count(events.len());
+    let page = state.page.min(pages - 1);
+    let total = events.len();
+
+    let header = event_row("Time", "Tunnel", "Event", "Detail");
+    let rows: Column<'a, Message> = events
+        .into_iter()
+        .skip(page * PAGE_SIZE)
+        .take(PAGE_SIZE)
+        .fold(Column::new().spacing(4), |rows, event| {
+            rows.push(event_row(
+                event.timestamp.format("%Y-%m-%d %H:%M:%S").to_string(),
+                event.tunnel.clone(),
+                event.kind.as_str(),
+                event.detail.clone(),
+            ))
+        });
+
+    let mut previous = button("Previous").style(button::secondary);
+    if page > 0 {
+        previous = previous.on_press(Message::PreviousPage);
+    }
+    let mut next = button("Next").style(button::secondary);
+    if page + 1 < pages {
+        next = next.on_press(Message::NextPage);
+    }
+    let footer = row![
+        text(format!("{} event(s)", total)).size(12).width(Length::Fill),
+        previous,
+        text(format!("Page {} of {}", page + 1, pages)).size(12),
+        next,
+        button("Export CSV...").on_press(Message::ExportCsv).style(button::primary),
+    ]
+    .spacing(10)
+    .align_y(Center);
+
+    column![
+        filters,
+        header,
+        scrollable(rows).height(Length::Fill).width(Length::Fill),
+        footer,
+    ]
+    .spacing(10)
+    .padding(15)
+    .into()
+}
+
+fn event_row<'a>(
+    time: impl text::IntoFragment<'a>,
+    tunnel: impl text::IntoFragment<'a>,
+    event: impl text::IntoFragment<'a>,
+    detail: impl text::IntoFragment<'a>,
+) -> Element<'a, Message> {
+    row![
+        text(time).size(12).font(Font::MONOSPACE).width(Length::Fixed(150.0)),
+        text(tunnel).size(12).width(Length::Fixed(160.0)),
+        text(event).size(12).width(Length::Fixed(100.0)),
+        text(detail).size(12).width(Length::Fill),
+    ]
+    .spacing(10)
+    .into()
+}
@@ -0,0 +1,113 @@
+use iced::widget::{button, column, pick_list, row, scrollable, text, Column};
+use iced::{Center, Color, Element, Font, Length};
+use crate::tunnels::{classify_ssh_line, SshLineKind};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Refresh,
+    ExportTunnelSelected(String),
+    ExportPeriodSelected(ExportPeriod),
+    Export,
+}
+
+/// How far back a tunnel log export goes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ExportPeriod {
+    LastQuarterHour,
+    #[default]
+    LastHour,
+    LastDay,
+    LastWeek,
+    AllLogs,
+}
+
+impl ExportPeriod {
+    pub const ALL: [ExportPeriod; 5] = [
+        ExportPeriod::LastQuarterHour,
+        ExportPeriod::LastHour,
+        ExportPeriod::LastDay,
+        ExportPeriod::LastWeek,
+        ExportPeriod::AllLogs,
+    ];
+
+    /// Local time the export starts at, None for everything logged
+    pub fn since(&self) -> Option<chrono::NaiveDateTime> {
+        let period = match self {
+            ExportPeriod::LastQuarterHour => chrono::Duration::minutes(15),
+            ExportPeriod::LastHour => chrono::Duration::hours(1),
+            ExportPeriod::LastDay => chrono::Duration::days(1),
+            ExportPeriod::LastWeek => chrono::Duration::weeks(1),
+            ExportPeriod::AllLogs => return None,
+        };
+        Some(chrono::Local::now().naive_local() - period)
+    }
+}
+
+impl std::fmt::Display for ExportPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let label = match self {
+            ExportPeriod::LastQuarterHour => "Last 15 minutes",
+            ExportPeriod::LastHour => "Last hour",
+            ExportPeriod::LastDay => "Last 24 hours",
+            ExportPeriod::LastWeek => "Last 7 days",
+            ExportPeriod::AllLogs => "All logs",
+        };
+        write!(f, "{}", label)
+    }
+}
+
+/// Tunnel and period picked for exporting a tunnel's log lines
+#[derive(Debug, Clone, Default)]
+pub struct LogExportState {
+    pub tunnel: Option<String>,
+    pub period: ExportPeriod,
+}
+
+pub fn view<'a>(log_path: &'a str, lines: &'a [String], export: &'a LogExportState, tunnel_names: Vec<String>) -> Element<'a, Message> {
+    let log_lines: Column<'a, Message> = lines
+        .iter()
+        .fold(Column::new().spacing(2), |lines, line| lines.push(log_line(line)));
+
+    let mut export_button = button("Export Log...").style(button::secondary);
+    if export.tunnel.is_some() {
+        export_button = export_button.on_press(Message::Export);
+    }
+
+    column![
+        row![
+            text(log_path).size(12).width(Length::Fill),
+            button("Refresh").on_press(Message::Refresh).style(button::secondary),
+        ]
+        .spacing(10),
+        scrollable(log_lines).height(Length::Fill).width(Length::Fill),
+        row![
+            text("Export the lines of").size(12),
+            pick_list(tunnel_names, export.tunnel.clone(), Message::ExportTunnelSelected)
+                .placeholder("Choose a tunnel")
+                .text_size(12),
+            pick_list(ExportPeriod::ALL, Some(export.period), Message::ExportPeriodSelected).text_size(12),
+            export_button,
+        ]
+        .spacing(10)
+        .align_y(Center),
+    ]
+    .spacing(10)
+    .padding(15)
+    .into()
+}
+
+/// Render a log line colored by its ssh classification
+/// Red for errors, yellow for warnings (failed forwards included), dimmed for debug output
+fn log_line<'a>(line: &'a str) -> Element<'a, Message> {
+    let color = match classify_ssh_line(line) {
+        SshLineKind::Error(_) => Some(Color::from_rgb(0.86, 0.24, 0.24)),
+        SshLineKind::ChannelOpenFailed | SshLineKind::Warning => Some(Color::from_rgb(0.85, 0.65, 0.13)),
+        SshLineKind::Debug => Some(Color::from_rgb(0.55, 0.55, 0.55)),
+        SshLineKind::Info => None,
+    };
+    let line = text(line).size(12).font(Font::MONOSPACE);
+    match color {
+        Some(color) => line.color(color).into(),
+        None => line.into(),
+    }
+}
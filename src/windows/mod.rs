@@ -1,37 +1,79 @@
 pub mod about;
+pub mod batch_edit;
+pub mod confirm;
+pub mod connect_on_port;
 pub mod create_tunnel;
+pub mod dashboard;
+pub mod effective_config;
+pub mod error_summary;
+pub mod event_log;
+pub mod log_viewer;
+pub mod putty_import;
+pub mod undo_toast;
 
 #[derive(Debug, Clone)]
 
 pub enum FormMode {
     Create,
     Edit { tunnel_id: String },
+    /// Pre-filled from an existing tunnel but saved as a new one, leaving the source untouched
+    CreateFrom { source_id: String },
 }
 
-#[derive(Debug, Clone)]
-pub enum WindowType {
-    About,
-    TunnelForm {
-        mode: FormMode,
-        name: String,
-        local_host: String,
-        local_port: String,
-        remote_host: String,
-        remote_port: String,
-        ssh_user: String,
-        ssh_host: String,
-        ssh_port: String,
-        private_key: String,
-        error_message: Option<String>,
-        test_message: Option<String>,
-    },
+/// Sections of the tunnel form, shown one at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormTab {
+    #[default]
+    Forwarding,
+    Ssh,
+    Advanced,
 }
 
-impl WindowType {
-    pub fn new_tunnel_form_create() -> Self {
-        WindowType::TunnelForm {
-            mode: FormMode::Create,
+/// Values currently entered in the tunnel form
+#[derive(Debug, Clone, PartialEq)]
+pub struct TunnelFormData {
+    pub name: String,
+    /// Label for the tray and dashboard, empty to show the name
+    pub display_name: String,
+    pub local_host: String,
+    pub local_port: String,
+    pub remote_host: String,
+    pub remote_port: String,
+    pub ssh_user: String,
+    pub ssh_host: String,
+    pub ssh_port: String,
+    pub private_key: String,
+    pub auto_start: bool,
+    pub jump_tunnel_id: Option<String>,
+    pub ssh_config_file: String,
+    /// Minutes after which to disconnect, empty for no limit
+    pub auto_disconnect_minutes: String,
+    pub identities_only: bool,
+    /// Forward to a Unix socket on the server instead of a host and port
+    pub remote_is_socket: bool,
+    pub remote_socket: String,
+    pub open_web_on_connect: bool,
+    pub clean_exit_disconnects: bool,
+    /// ssh client to run instead of the system one, empty for the default
+    pub ssh_binary: String,
+    pub auth_mode: crate::tunnels::AuthMode,
+    pub notifications: bool,
+    pub required_interface: String,
+    pub gate_host: String,
+    pub favorite: bool,
+    pub bind_interface: String,
+    pub accept_new_host_keys: bool,
+    /// Inventory of hosts making this tunnel a template, empty for a plain tunnel
+    pub inventory_file: String,
+    /// Environment variable rows (name, value), possibly incomplete while editing
+    pub env: Vec<(String, String)>,
+}
+
+impl Default for TunnelFormData {
+    fn default() -> Self {
+        TunnelFormData {
             name: String::new(),
+            display_name: String::new(),
             local_host: "127.0.0.1".to_string(),
             local_port: String::new(),
             remote_host: "127.0.0.1".to_string(),
@@ -40,15 +82,75 @@ impl WindowType {
             ssh_host: String::new(),
             ssh_port: "22".to_string(),
             private_key: String::new(),
-            error_message: None,
-            test_message: None,
+            auto_start: false,
+            jump_tunnel_id: None,
+            ssh_config_file: String::new(),
+            auto_disconnect_minutes: String::new(),
+            identities_only: false,
+            remote_is_socket: false,
+            remote_socket: String::new(),
+            open_web_on_connect: false,
+            clean_exit_disconnects: false,
+            ssh_binary: String::new(),
+            auth_mode: crate::tunnels::AuthMode::default(),
+            notifications: true,
+            required_interface: String::new(),
+            gate_host: String::new(),
+            favorite: false,
+            bind_interface: String::new(),
+            accept_new_host_keys: false,
+            inventory_file: String::new(),
+            env: Vec::new(),
         }
     }
+}
 
-    pub fn new_tunnel_form_edit(tunnel: &crate::tunnels::Tunnel) -> Self {
-        WindowType::TunnelForm {
-            mode: FormMode::Edit { tunnel_id: tunnel.id.clone() },
+impl TunnelFormData {
+    /// Whether enough is filled in to try connecting: an SSH user and host
+    pub fn can_test(&self) -> bool {
+        !self.ssh_user.trim().is_empty() && !self.ssh_host.trim().is_empty()
+    }
+
+    /// Whether saving is worth trying; the remaining fields are checked on save
+    pub fn can_save(&self) -> bool {
+        !self.name.trim().is_empty() && self.can_test()
+    }
+
+    /// Build a template from the current values, leaving out name and ports
+    pub fn to_template(&self, template_name: &str) -> crate::templates::TunnelTemplate {
+        crate::templates::TunnelTemplate {
+            name: template_name.to_string(),
+            local_host: self.local_host.clone(),
+            remote_host: self.remote_host.clone(),
+            ssh_user: self.ssh_user.clone(),
+            ssh_host: self.ssh_host.clone(),
+            ssh_port: self.ssh_port.clone(),
+            private_key: self.private_key.clone(),
+            auto_start: self.auto_start,
+            ssh_config_file: self.ssh_config_file.clone(),
+            identities_only: self.identities_only,
+            auth_mode: self.auth_mode,
+        }
+    }
+
+    /// Pre-fill the form from a template, keeping the name and ports already entered
+    pub fn apply_template(&mut self, template: &crate::templates::TunnelTemplate) {
+        self.local_host = template.local_host.clone();
+        self.remote_host = template.remote_host.clone();
+        self.ssh_user = template.ssh_user.clone();
+        self.ssh_host = template.ssh_host.clone();
+        self.ssh_port = template.ssh_port.clone();
+        self.private_key = template.private_key.clone();
+        self.auto_start = template.auto_start;
+        self.ssh_config_file = template.ssh_config_file.clone();
+        self.identities_only = template.identities_only;
+        self.auth_mode = template.auth_mode;
+    }
+
+    pub fn from_tunnel(tunnel: &crate::tunnels::Tunnel) -> Self {
+        TunnelFormData {
             name: tunnel.name.clone(),
+            display_name: tunnel.display_name.clone(),
             local_host: tunnel.local_host.clone(),
             local_port: tunnel.local_port.clone(),
             remote_host: tunnel.remote_host.clone(),
@@ -57,8 +159,384 @@ impl WindowType {
             ssh_host: tunnel.ssh_host.clone(),
             ssh_port: tunnel.ssh_port.clone(),
             private_key: tunnel.private_key.clone(),
+            auto_start: tunnel.auto_start,
+            jump_tunnel_id: tunnel.jump_tunnel_id.clone(),
+            ssh_config_file: tunnel.ssh_config_file.clone(),
+            auto_disconnect_minutes: tunnel
+                .auto_disconnect_minutes
+                .map(|minutes| minutes.to_string())
+                .unwrap_or_default(),
+            identities_only: tunnel.identities_only,
+            remote_is_socket: !tunnel.remote_socket.is_empty(),
+            remote_socket: tunnel.remote_socket.clone(),
+            open_web_on_connect: tunnel.open_web_on_connect,
+            clean_exit_disconnects: tunnel.clean_exit_disconnects,
+            ssh_binary: tunnel.ssh_binary.clone(),
+            auth_mode: tunnel.auth_mode,
+            notifications: tunnel.notifications,
+            required_interface: tunnel.required_interface.clone(),
+            gate_host: tunnel.gate_host.clone(),
+            favorite: tunnel.favorite,
+            bind_interface: tunnel.bind_interface.clone(),
+            accept_new_host_keys: tunnel.accept_new_host_keys,
+            inventory_file: tunnel.inventory_file.clone(),
+            env: tunnel.env.iter().map(|(key, value)| (key.clone(), value.clone())).collect(),
+        }
+    }
+}
+
+/// Connection settings changed in `form` since `original`, by form label
+/// These are the fields `Tunnel::connection_differs` compares: a running
+/// tunnel only picks them up when restarted.
+pub fn connection_changes(original: &TunnelFormData, form: &TunnelFormData) -> Vec<&'static str> {
+    let changed = |a: &str, b: &str| a.trim() != b.trim();
+    let remote_socket = |f: &TunnelFormData| if f.remote_is_socket { f.remote_socket.trim().to_string() } else { String::new() };
+    [
+        ("Local Host", changed(&original.local_host, &form.local_host)),
+        ("Bind Interface", changed(&original.bind_interface, &form.bind_interface)),
+        ("Local Port", changed(&original.local_port, &form.local_port)),
+        ("Remote Host", !form.remote_is_socket && changed(&original.remote_host, &form.remote_host)),
+        ("Remote Port", !form.remote_is_socket && changed(&original.remote_port, &form.remote_port)),
+        ("Remote Socket", remote_socket(original) != remote_socket(form)),
+        ("SSH User", changed(&original.ssh_user, &form.ssh_user)),
+        ("SSH Host", changed(&original.ssh_host, &form.ssh_host)),
+        ("SSH Port", changed(&original.ssh_port, &form.ssh_port)),
+        ("Authentication", original.auth_mode != form.auth_mode),
+        ("Private Key", changed(&original.private_key, &form.private_key)),
+        ("Use only this key", original.identities_only != form.identities_only),
+        ("Trust new host keys", original.accept_new_host_keys != form.accept_new_host_keys),
+        ("SSH Config File", changed(&original.ssh_config_file, &form.ssh_config_file)),
+        ("Jump through", original.jump_tunnel_id != form.jump_tunnel_id),
+        ("SSH Client", changed(&original.ssh_binary, &form.ssh_binary)),
+        ("Environment", original.env != form.env),
+    ]
+    .into_iter()
+    .filter(|(_, changed)| *changed)
+    .map(|(label, _)| label)
+    .collect()
+}
+
+/// Entry of the "Jump through" picker: no jump host, or another tunnel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JumpChoice {
+    pub tunnel_id: Option<String>,
+    pub label: String,
+}
+
+impl JumpChoice {
+    pub fn none() -> Self {
+        JumpChoice { tunnel_id: None, label: "None (direct connection)".to_string() }
+    }
+
+    /// "None" followed by every tunnel except `exclude_id` (the one being edited)
+    pub fn list(tunnels: &[crate::tunnels::Tunnel], exclude_id: Option<&str>) -> Vec<JumpChoice> {
+        let mut choices = vec![JumpChoice::none()];
+        choices.extend(
+            tunnels
+                .iter()
+                .filter(|t| Some(t.id.as_str()) != exclude_id)
+                .map(|t| JumpChoice {
+                    tunnel_id: Some(t.id.clone()),
+                    label: format!("{} ({}@{})", t.name, t.ssh_user, t.ssh_host),
+                }),
+        );
+        choices
+    }
+}
+
+impl std::fmt::Display for JumpChoice {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.label)
+    }
+}
+
+/// Saved templates as offered by the tunnel form
+#[derive(Debug, Clone, Default)]
+pub struct TemplateState {
+    pub names: Vec<String>,
+    pub selected: Option<String>,
+    /// Name to save the current settings under
+    pub new_name: String,
+}
+
+impl TemplateState {
+    pub fn new(names: Vec<String>) -> Self {
+        TemplateState { names, ..Default::default() }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum WindowType {
+    About,
+    Dashboard,
+    TunnelForm {
+        mode: FormMode,
+        form: TunnelFormData,
+        form_tab: FormTab,
+        default_identity: Option<String>,
+        templates: TemplateState,
+        jump_choices: Vec<JumpChoice>,
+        error_message: Option<String>,
+        test_message: Option<String>,
+        /// When the running connection test started, None when not testing
+        test_started: Option<std::time::Instant>,
+        /// Non-blocking problems (private key, endpoints) found at save time
+        warnings: Vec<String>,
+        /// Shows a short "Saved" confirmation after Apply
+        saved_notice: bool,
+        /// Values the running tunnel uses, to tell which edits need a restart (Edit only)
+        original: Option<TunnelFormData>,
+        /// Connection fields changed since `original`, by form label
+        restart_fields: Vec<&'static str>,
+        /// Values when the form opened or was last saved, to tell unsaved edits on close
+        saved: TunnelFormData,
+    },
+    UndoToast {
+        tunnel_name: String,
+    },
+    LogViewer {
+        lines: Vec<String>,
+        export: log_viewer::LogExportState,
+    },
+    EventLog {
+        state: event_log::EventLogState,
+    },
+    BatchEdit {
+        state: batch_edit::BatchEditState,
+    },
+    EffectiveConfig {
+        tunnel_name: String,
+        config: String,
+    },
+    PuttyImport {
+        state: putty_import::PuttyImportState,
+    },
+    ErrorSummary,
+    /// Asks for the local port of a one-off connection
+    ConnectOnPort {
+        tunnel_name: String,
+        configured_port: String,
+        local_port: String,
+        error_message: Option<String>,
+    },
+    /// Yes/No question; `on_confirm` runs only when the user answers Yes
+    /// `alternative` adds a third, labelled answer with its own action.
+    Confirm {
+        title: String,
+        message: String,
+        on_confirm: confirm::ConfirmAction,
+        alternative: Option<(String, confirm::ConfirmAction)>,
+    },
+}
+
+impl WindowType {
+    /// Whether this is a tunnel form with edits that were never saved
+    pub fn has_unsaved_changes(&self) -> bool {
+        matches!(self, WindowType::TunnelForm { form, saved, .. } if form != saved)
+    }
+
+    pub fn new_tunnel_form_create(form: TunnelFormData, template_names: Vec<String>, jump_choices: Vec<JumpChoice>) -> Self {
+        WindowType::TunnelForm {
+            mode: FormMode::Create,
+            form: form.clone(),
+            form_tab: FormTab::default(),
+            default_identity: default_identity_hint(),
+            templates: TemplateState::new(template_names),
+            jump_choices,
+            error_message: None,
+            test_message: None,
+            test_started: None,
+            warnings: Vec::new(),
+            saved_notice: false,
+            original: None,
+            restart_fields: Vec::new(),
+            saved: form,
+        }
+    }
+
+    pub fn new_tunnel_form_edit(
+        tunnel: &crate::tunnels::Tunnel,
+        template_names: Vec<String>,
+        jump_choices: Vec<JumpChoice>,
+    ) -> Self {
+        WindowType::TunnelForm {
+            mode: FormMode::Edit { tunnel_id: tunnel.id.clone() },
+            form: TunnelFormData::from_tunnel(tunnel),
+            form_tab: FormTab::default(),
+            default_identity: default_identity_hint(),
+            templates: TemplateState::new(template_names),
+            jump_choices,
             error_message: None,
             test_message: None,
+            test_started: None,
+            warnings: Vec::new(),
+            saved_notice: false,
+            original: Some(TunnelFormData::from_tunnel(tunnel)),
+            restart_fields: Vec::new(),
+            saved: TunnelFormData::from_tunnel(tunnel),
+        }
+    }
+
+    /// Form for trying a variation of `tunnel`, saved as a new tunnel
+    /// The name is made unique among `tunnels` so saving right away works.
+    pub fn new_tunnel_form_create_from(
+        tunnel: &crate::tunnels::Tunnel,
+        tunnels: &[crate::tunnels::Tunnel],
+        template_names: Vec<String>,
+        jump_choices: Vec<JumpChoice>,
+    ) -> Self {
+        let mut form = TunnelFormData::from_tunnel(tunnel);
+        form.name = copy_name(&tunnel.name, tunnels);
+        WindowType::TunnelForm {
+            mode: FormMode::CreateFrom { source_id: tunnel.id.clone() },
+            form: form.clone(),
+            form_tab: FormTab::default(),
+            default_identity: default_identity_hint(),
+            templates: TemplateState::new(template_names),
+            jump_choices,
+            error_message: None,
+            test_message: None,
+            test_started: None,
+            warnings: Vec::new(),
+            saved_notice: false,
+            original: None,
+            restart_fields: Vec::new(),
+            saved: form,
+        }
+    }
+}
+
+/// First of "name (copy)", "name (copy 2)", ... not used by any tunnel
+fn copy_name(name: &str, tunnels: &[crate::tunnels::Tunnel]) -> String {
+    let taken = |candidate: &str| tunnels.iter().any(|t| t.name == candidate);
+    let mut candidate = format!("{} (copy)", name);
+    let mut number = 2;
+    while taken(&candidate) {
+        candidate = format!("{} (copy {})", name, number);
+        number += 1;
+    }
+    candidate
+}
+
+/// Get the default identity ssh would use, for display in the form
+fn default_identity_hint() -> Option<String> {
+    crate::tunnels::default_identity().map(|path| path.display().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::test_tunnel;
+
+    #[test]
+    fn labels_and_behaviour_edits_need_no_restart() {
+        let original = TunnelFormData::from_tunnel(&test_tunnel("web", "18080"));
+        let form = TunnelFormData {
+            // The manager moves a running tunnel to its new name, so renaming needs no restart either
+            name: "website".to_string(),
+            display_name: "Web".to_string(),
+            auto_start: true,
+            auto_disconnect_minutes: "30".to_string(),
+            notifications: false,
+            ..original.clone()
+        };
+
+        assert!(connection_changes(&original, &original).is_empty());
+        assert!(connection_changes(&original, &form).is_empty());
+    }
+
+    #[test]
+    fn connection_edits_are_flagged_by_label() {
+        let original = TunnelFormData::from_tunnel(&test_tunnel("web", "18080"));
+        let form = TunnelFormData {
+            ssh_host: "bastion.example.com".to_string(),
+            ssh_port: "2222".to_string(),
+            // Surrounding whitespace is trimmed on save, so it changes nothing
+            local_port: " 18080 ".to_string(),
+            identities_only: true,
+            ..original.clone()
+        };
+
+        assert_eq!(connection_changes(&original, &form), vec!["SSH Host", "SSH Port", "Use only this key"]);
+    }
+
+    #[test]
+    fn switching_to_a_socket_flags_only_the_socket() {
+        let original = TunnelFormData::from_tunnel(&test_tunnel("web", "18080"));
+        let form = TunnelFormData {
+            remote_is_socket: true,
+            remote_socket: "/run/app.sock".to_string(),
+            remote_host: String::new(),
+            ..original.clone()
+        };
+
+        assert_eq!(connection_changes(&original, &form), vec!["Remote Socket"]);
+    }
+
+    #[test]
+    fn copy_names_skip_the_taken_ones() {
+        let tunnels = vec![test_tunnel("web", "18080"), test_tunnel("web (copy)", "18081")];
+        assert_eq!(copy_name("db", &tunnels), "db (copy)");
+        assert_eq!(copy_name("web", &tunnels), "web (copy 2)");
+    }
+
+    #[test]
+    fn edit_as_new_saves_a_new_tunnel_and_leaves_the_source() {
+        let source = test_tunnel("web", "18080");
+        let tunnels = vec![source.clone()];
+        let window = WindowType::new_tunnel_form_create_from(&source, &tunnels, Vec::new(), Vec::new());
+        let WindowType::TunnelForm { mode, mut form, .. } = window else {
+            panic!("not a tunnel form");
+        };
+        assert!(matches!(&mode, FormMode::CreateFrom { source_id } if *source_id == source.id));
+        assert_eq!(form.name, "web (copy)");
+        assert_eq!(form.ssh_host, source.ssh_host);
+
+        form.local_port = "18090".to_string();
+        let (created, _) = create_tunnel::validate_and_create_tunnel(&form, &tunnels).unwrap();
+        let mut manager = crate::tunnels::TunnelManager::new();
+        manager.set_tunnels(tunnels);
+        manager.add_tunnel(created.clone());
+
+        assert_ne!(created.id, source.id);
+        let saved = manager.get_tunnels();
+        assert_eq!(saved.len(), 2);
+        assert_eq!(serde_json::to_value(&saved[0]).unwrap(), serde_json::to_value(&source).unwrap());
+        assert_eq!((saved[1].name.as_str(), saved[1].local_port.as_str()), ("web (copy)", "18090"));
+    }
+
+    /// The form data of a tunnel form window, to edit in place
+    fn form_of(window: &mut WindowType) -> &mut TunnelFormData {
+        match window {
+            WindowType::TunnelForm { form, .. } => form,
+            _ => panic!("not a tunnel form"),
+        }
+    }
+
+    #[test]
+    fn fresh_forms_have_no_unsaved_changes() {
+        let web = test_tunnel("web", "18080");
+        assert!(!WindowType::new_tunnel_form_create(TunnelFormData::default(), Vec::new(), Vec::new()).has_unsaved_changes());
+        assert!(!WindowType::new_tunnel_form_edit(&web, Vec::new(), Vec::new()).has_unsaved_changes());
+        assert!(!WindowType::new_tunnel_form_create_from(&web, &[web.clone()], Vec::new(), Vec::new()).has_unsaved_changes());
+        assert!(!WindowType::About.has_unsaved_changes());
+    }
+
+    #[test]
+    fn edits_are_unsaved_until_reverted_or_saved() {
+        let mut window = WindowType::new_tunnel_form_edit(&test_tunnel("web", "18080"), Vec::new(), Vec::new());
+
+        form_of(&mut window).local_port = "18081".to_string();
+        assert!(window.has_unsaved_changes());
+
+        form_of(&mut window).local_port = "18080".to_string();
+        assert!(!window.has_unsaved_changes());
+
+        form_of(&mut window).notifications = false;
+        assert!(window.has_unsaved_changes());
+        if let WindowType::TunnelForm { form, saved, .. } = &mut window {
+            *saved = form.clone();
         }
+        assert!(!window.has_unsaved_changes());
     }
 }
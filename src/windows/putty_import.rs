@@ -0,0 +1,141 @@
+use iced::widget::{button, checkbox, column, row, scrollable, text, Column};
+use iced::{Element, Length};
+use crate::putty::is_ppk;
+use crate::tunnels::Tunnel;
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    TunnelToggled(usize, bool),
+    ConvertKeysToggled(bool),
+    Import,
+    Cancel,
+}
+
+/// Tunnels found in the PuTTY sessions and which of them to import
+#[derive(Debug, Clone)]
+pub struct PuttyImportState {
+    pub tunnels: Vec<Tunnel>,
+    pub selected: Vec<bool>,
+    /// Same connection as an existing tunnel, these start deselected
+    pub duplicates: Vec<bool>,
+    /// Convert .ppk keys to OpenSSH format with puttygen while importing
+    pub convert_keys: bool,
+    pub puttygen_available: bool,
+}
+
+impl PuttyImportState {
+    /// Everything but duplicates of `existing` selected; keys are converted
+    /// by default when puttygen is there
+    pub fn new(tunnels: Vec<Tunnel>, existing: &[Tunnel], puttygen_available: bool) -> Self {
+        let duplicates: Vec<bool> = tunnels.iter().map(|t| t.duplicate_of(existing).is_some()).collect();
+        let selected = duplicates.iter().map(|duplicate| !duplicate).collect();
+        PuttyImportState { tunnels, selected, duplicates, convert_keys: puttygen_available, puttygen_available }
+    }
+
+    /// Apply an input message; Import and Cancel are handled by the app
+    pub fn update(&mut self, message: Message) {
+        match message {
+            Message::TunnelToggled(index, selected) => {
+                if let Some(entry) = self.selected.get_mut(index) {
+                    *entry = selected;
+                }
+            }
+            Message::ConvertKeysToggled(convert_keys) => self.convert_keys = convert_keys,
+            Message::Import | Message::Cancel => {}
+        }
+    }
+
+    /// The tunnels checked for import
+    pub fn selected_tunnels(&self) -> Vec<Tunnel> {
+        self.tunnels
+            .iter()
+            .zip(&self.selected)
+            .filter(|(_, selected)| **selected)
+            .map(|(tunnel, _)| tunnel.clone())
+            .collect()
+    }
+}
+
+pub fn view(state: &PuttyImportState) -> Element<'_, Message> {
+    let rows: Column<'_, Message> = state
+        .tunnels
+        .iter()
+        .zip(&state.selected)
+        .zip(&state.duplicates)
+        .enumerate()
+        .fold(Column::new().spacing(6), |rows, (index, ((tunnel, selected), duplicate))| {
+            let user = if tunnel.ssh_user.is_empty() { "(no user)" } else { &tunnel.ssh_user };
+            let label = if *duplicate { format!("{} (duplicate)", tunnel.name) } else { tunnel.name.clone() };
+            let mut entry = column![
+                checkbox(label, *selected)
+                    .on_toggle(move |selected| Message::TunnelToggled(index, selected))
+                    .size(16)
+                    .text_size(13),
+                text(format!(
+                    "{}:{} \u{2192} {}:{} via {}@{}:{}",
+                    tunnel.local_host, tunnel.local_port, tunnel.remote_host, tunnel.remote_port, user, tunnel.ssh_host, tunnel.ssh_port
+                ))
+                .size(11),
+            ]
+            .spacing(2);
+            if is_ppk(&tunnel.private_key) {
+                entry = entry.push(
+                    text(format!("\u{26a0} PuTTY key {} (OpenSSH can't read .ppk keys)", tunnel.private_key))
+                        .size(11)
+                        .color(iced::Color::from_rgb(0.8, 0.5, 0.0)),
+                );
+            }
+            rows.push(entry)
+        });
+
+    let convert_label = if state.puttygen_available {
+        "Convert .ppk keys to OpenSSH format with puttygen"
+    } else {
+        "Convert .ppk keys (puttygen not found, convert them with PuTTYgen \u{2192} Conversions \u{2192} Export OpenSSH key)"
+    };
+    let mut convert_keys = checkbox(convert_label, state.convert_keys).size(16).text_size(12);
+    if state.puttygen_available {
+        convert_keys = convert_keys.on_toggle(Message::ConvertKeysToggled);
+    }
+
+    let mut import = button("Import").style(button::primary);
+    if state.selected.iter().any(|selected| *selected) {
+        import = import.on_press(Message::Import);
+    }
+
+    column![
+        text("Import PuTTY Sessions").size(20),
+        text("Each local port forwarding of a saved session becomes a tunnel").size(12),
+        scrollable(rows).height(Length::Fill).width(Length::Fill),
+        convert_keys,
+        row![
+            button("Cancel").on_press(Message::Cancel).style(button::secondary),
+            import,
+        ]
+        .spacing(10),
+    ]
+    .spacing(10)
+    .padding(20)
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tunnels::test_tunnel;
+
+    #[test]
+    fn duplicates_start_deselected() {
+        let existing = vec![test_tunnel("web", "8080")];
+        let imported = vec![test_tunnel("putty web", "8080"), test_tunnel("putty db", "5432")];
+        let state = PuttyImportState::new(imported, &existing, false);
+        assert_eq!(state.duplicates, vec![true, false]);
+        assert_eq!(state.selected, vec![false, true]);
+    }
+
+    #[test]
+    fn keys_are_converted_only_with_puttygen() {
+        assert!(PuttyImportState::new(vec![], &[], true).convert_keys);
+        assert!(!PuttyImportState::new(vec![], &[], false).convert_keys);
+    }
+}
@@ -0,0 +1,29 @@
+use iced::widget::{button, column, container, row, text, Column};
+use iced::{Center, Element, Length};
+
+#[derive(Debug, Clone)]
+pub enum Message {
+    Undo,
+    Dismiss,
+}
+
+pub fn view<'a>(tunnel_name: &'a str) -> Element<'a, Message> {
+    let content: Column<'a, Message> = column![
+        text(format!("Tunnel '{}' removed", tunnel_name)).size(16),
+        row![
+            button("Dismiss").on_press(Message::Dismiss).style(button::secondary),
+            button("Undo").on_press(Message::Undo).style(button::primary),
+        ]
+        .spacing(10),
+    ]
+    .spacing(12)
+    .padding(16)
+    .align_x(Center);
+
+    container(content)
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .center_x(Length::Fill)
+        .center_y(Length::Fill)
+        .into()
+}